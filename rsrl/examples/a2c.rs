@@ -1,7 +1,7 @@
 #[macro_use]
 extern crate rsrl;
 
-use rand::thread_rng;
+use rand::{rngs::StdRng, thread_rng, SeedableRng};
 use rsrl::{
     control::{ac::ActorCritic, td::SARSA},
     domains::{Domain, MountainCar, Transition},
@@ -34,6 +34,8 @@ fn main() {
         q_func: q_func.clone(),
         policy: policy.clone(),
         gamma: 1.0,
+        greedy: false,
+        rng: StdRng::from_entropy(),
     };
     let critic = {
         let q = q_func.clone();
@@ -1,7 +1,7 @@
 extern crate rsrl;
 extern crate openblas_src;
 
-use rand::thread_rng;
+use rand::{rngs::StdRng, thread_rng, SeedableRng};
 use rsrl::{
     control::{nac::NAC, td::SARSA},
     domains::{ContinuousMountainCar, Domain},
@@ -47,6 +47,8 @@ fn main() {
             q_func: cfa,
             policy: policy.clone(),
             gamma: 0.999,
+            greedy: false,
+            rng: StdRng::from_entropy(),
         }
     };
 
@@ -1,6 +1,6 @@
 extern crate rsrl;
 
-use rand::thread_rng;
+use rand::{rngs::StdRng, thread_rng, SeedableRng};
 use rsrl::{
     control::{nac::NAC, td::SARSA},
     domains::{Domain, MountainCar},
@@ -48,6 +48,8 @@ fn main() {
             policy: policy.clone(),
 
             gamma: 0.999,
+            greedy: false,
+            rng: StdRng::from_entropy(),
         }
     };
 
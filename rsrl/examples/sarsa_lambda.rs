@@ -2,7 +2,7 @@ extern crate rsrl;
 
 use rand::{rngs::StdRng, SeedableRng};
 use rsrl::{
-    control::td::SARSALambda,
+    control::td::{OnPolicyTransition, SARSALambda},
     domains::{Domain, MountainCar},
     fa::linear::{
         basis::{Combinators, Fourier},
@@ -12,6 +12,7 @@ use rsrl::{
     make_shared,
     params::Parameterised,
     policies::{EpsilonGreedy, Greedy, Policy, Random},
+    schedule::Parameter,
     spaces::Space,
     traces::Trace,
     Handler,
@@ -31,7 +32,8 @@ fn main() {
         let basis = Fourier::from_space(5, env.state_space()).with_bias();
         let fa_theta = make_shared(LFA::vector(basis, SGD(1.0), n_actions));
 
-        let policy = EpsilonGreedy::new(Greedy::new(fa_theta.clone()), Random::new(n_actions), 0.2);
+        let policy = EpsilonGreedy::new(Greedy::new(fa_theta.clone()), Random::new(n_actions), 0.2)
+            .with_epsilon_schedule(Parameter::exponential(0.2, 0.995, 0.0));
         let wdim = fa_theta.weights_dim();
 
         let trace = Trace::replacing(wdim, GAMMA, LAMBDA);
@@ -42,6 +44,9 @@ fn main() {
             trace,
             alpha: ALPHA,
             gamma: GAMMA,
+            grad_clip_norm: None,
+            greedy: false,
+            rng: StdRng::seed_from_u64(1),
         }
     };
 
@@ -57,15 +62,18 @@ fn main() {
 
             let t = env.transition(action);
 
-            agent.handle(&t).ok();
             action = agent.policy.sample(&mut rng, t.to.state());
+            agent.handle(OnPolicyTransition {
+                transition: &t,
+                next_action: action,
+            }).ok();
 
             if t.terminated() {
                 break;
             }
         }
 
-        agent.policy.epsilon *= 0.995;
+        agent.policy.epsilon.step();
 
         println!("Batch {}: {} steps...", e + 1, j + 1);
     }
@@ -1,11 +1,16 @@
 //! Actor-critic algorithms.
 use crate::{
     domains::Transition,
-    fa::StateActionUpdate,
-    policies::Policy,
+    fa::{ScaledGradientUpdate, StateActionUpdate},
+    params::{Buffer, BufferMut, Parameterised},
+    policies::{DifferentiablePolicy, Entropy, Policy},
+    schedule::Parameter,
+    traces::{Accumulate, Trace},
+    Differentiable,
     Function,
     Handler,
 };
+use ndarray::{Array1, Array2};
 
 pub trait Critic<'t, S: 't, A: 't> {
     fn target(&self, t: &'t Transition<S, A>) -> f64;
@@ -113,3 +118,305 @@ where
         })
     }
 }
+
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate")
+)]
+pub struct Response {
+    pub td_error: f64,
+}
+
+/// Actor-critic with accumulating eligibility traces for both the actor and
+/// the critic.
+///
+/// The critic is a state-value function trained by TD(λ) with step size
+/// `beta`, and the actor is a differentiable policy whose log-likelihood
+/// gradients are accumulated in a separate trace and scaled by the critic's
+/// TD error and step size `alpha`. Each trace decays at rate `γλ`, with
+/// independent `λ` for the actor and critic. Both traces are cleared at the
+/// end of an episode.
+///
+/// An entropy bonus may be added to the actor's objective to discourage the
+/// policy from collapsing prematurely onto a single action; see
+/// [`with_entropy_bonus`](ActorCriticLambda::with_entropy_bonus).
+///
+/// # References
+/// - Sutton, R. S., & Barto, A. G. (2018). Reinforcement learning: An
+///   introduction (2nd ed., Section 13.5). MIT press.
+#[derive(Clone, Debug)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate")
+)]
+pub struct ActorCriticLambda<V, P> {
+    pub v_func: V,
+    pub policy: P,
+
+    pub critic_trace: Trace<Array1<f64>, Accumulate>,
+    pub actor_trace: Trace<Array2<f64>, Accumulate>,
+
+    pub alpha: f64,
+    pub beta: f64,
+    pub gamma: f64,
+}
+
+impl<V: Parameterised, P: Parameterised> ActorCriticLambda<V, P> {
+    pub fn new(
+        v_func: V,
+        policy: P,
+        alpha: f64,
+        beta: f64,
+        gamma: f64,
+        lambda_critic: f64,
+        lambda_actor: f64,
+    ) -> Self
+    {
+        let critic_trace = Trace::accumulating(v_func.n_weights(), gamma, lambda_critic);
+        let actor_trace = Trace::accumulating(policy.weights_dim(), gamma, lambda_actor);
+
+        ActorCriticLambda {
+            v_func,
+            policy,
+
+            critic_trace,
+            actor_trace,
+
+            alpha,
+            beta,
+            gamma,
+        }
+    }
+
+    /// Add the policy entropy at each visited state, scaled by the given
+    /// coefficient, to the objective of the actor; the policy must implement
+    /// [`Entropy`].
+    pub fn with_entropy_bonus(self, coefficient: Parameter) -> EntropyBonus<Self> {
+        EntropyBonus {
+            agent: self,
+            coefficient,
+        }
+    }
+}
+
+impl<'m, S, V, P> Handler<&'m Transition<S, P::Action>> for ActorCriticLambda<V, P>
+where
+    V: Differentiable<(&'m S,), Output = f64>
+        + for<'j> Handler<ScaledGradientUpdate<&'j Array1<f64>>>,
+    V::Jacobian: Buffer<Dim = ndarray::Ix1>,
+    P: DifferentiablePolicy<&'m S> + for<'j> Handler<ScaledGradientUpdate<&'j Array2<f64>>>,
+{
+    type Response = Response;
+    type Error = ();
+
+    fn handle(&mut self, t: &'m Transition<S, P::Action>) -> Result<Response, ()> {
+        let s = t.from.state();
+        let v = self.v_func.evaluate((s,));
+
+        let td_error = if t.terminated() {
+            t.reward - v
+        } else {
+            t.reward + self.gamma * self.v_func.evaluate((t.to.state(),)) - v
+        };
+
+        self.critic_trace.update(&self.v_func.grad((s,)).into_dense());
+        self.actor_trace.update(&self.policy.grad_log((s, &t.action)));
+
+        self.v_func
+            .handle(ScaledGradientUpdate {
+                alpha: self.beta * td_error,
+                jacobian: &self.critic_trace.buffer,
+            })
+            .map_err(|_| ())?;
+        self.policy
+            .handle(ScaledGradientUpdate {
+                alpha: self.alpha * td_error,
+                jacobian: &self.actor_trace.buffer,
+            })
+            .map_err(|_| ())?;
+
+        if t.terminated() {
+            self.critic_trace.buffer.reset();
+            self.actor_trace.buffer.reset();
+        }
+
+        Ok(Response { td_error })
+    }
+}
+
+/// Actor-critic agent with an entropy bonus added to the objective of its
+/// actor.
+///
+/// After each update of the wrapped agent, the policy is moved along the
+/// gradient of its entropy at the visited state, scaled by the actor's step
+/// size and `coefficient`. This discourages the policy from collapsing
+/// prematurely onto a single action, and requires a policy that implements
+/// [`Entropy`].
+#[derive(Clone, Debug)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate")
+)]
+pub struct EntropyBonus<A> {
+    pub agent: A,
+
+    /// Coefficient of the entropy bonus, annealed by calling its `step`
+    /// method (e.g. once per episode).
+    pub coefficient: Parameter,
+}
+
+impl<'m, S, V, P> Handler<&'m Transition<S, P::Action>> for EntropyBonus<ActorCriticLambda<V, P>>
+where
+    ActorCriticLambda<V, P>:
+        Handler<&'m Transition<S, P::Action>, Response = Response, Error = ()>,
+    P: DifferentiablePolicy<&'m S>
+        + Entropy<&'m S>
+        + for<'j> Handler<ScaledGradientUpdate<&'j Array2<f64>>>,
+{
+    type Response = Response;
+    type Error = ();
+
+    fn handle(&mut self, t: &'m Transition<S, P::Action>) -> Result<Response, ()> {
+        let response = self.agent.handle(t)?;
+        let coefficient = self.coefficient.value();
+
+        if coefficient != 0.0 {
+            let grad_entropy = self.agent.policy.grad_entropy(t.from.state());
+
+            self.agent
+                .policy
+                .handle(ScaledGradientUpdate {
+                    alpha: self.agent.alpha * coefficient,
+                    jacobian: &grad_entropy,
+                })
+                .map_err(|_| ())?;
+        }
+
+        Ok(response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ActorCriticLambda;
+    use crate::{
+        domains::{Domain, GridWorld, Observation, Transition},
+        fa::tabular::Table,
+        policies::{Entropy, Gaussian, Policy, Softmax},
+        schedule::Parameter,
+        Function,
+        Handler,
+    };
+    use ndarray::{Array1, Array2};
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn test_corridor_improves() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut agent = ActorCriticLambda::new(
+            Table::dense(Array1::zeros(6)),
+            Softmax::standard(Table::dense(Array2::zeros((6, 4)))),
+            0.1,
+            0.1,
+            0.95,
+            0.8,
+            0.8,
+        );
+
+        let returns: Vec<f64> = (0..200)
+            .map(|_| {
+                let mut domain = GridWorld::new(6, 1, [0, 0], &[[5, 0]], 0.0);
+                let mut total = 0.0;
+
+                for _ in 0..200 {
+                    let a = agent.policy.sample(&mut rng, domain.emit().state());
+                    let t = domain.transition(a);
+
+                    agent.handle(&t).unwrap();
+                    total += t.reward;
+
+                    if t.terminated() {
+                        break;
+                    }
+                }
+
+                total
+            })
+            .collect();
+
+        let early: f64 = returns[..20].iter().sum::<f64>() / 20.0;
+        let late: f64 = returns[180..].iter().sum::<f64>() / 20.0;
+
+        assert!(late > early);
+        assert!(late > -10.0);
+    }
+
+    #[test]
+    fn test_gaussian_actor() {
+        // Policies without a closed-form entropy gradient are still supported
+        // when no entropy bonus is requested.
+        let mut agent = ActorCriticLambda::new(
+            Table::dense(Array1::zeros(1)),
+            Gaussian::new(Table::dense(Array1::zeros(1)), 1.0),
+            0.1,
+            0.1,
+            0.9,
+            0.0,
+            0.0,
+        );
+
+        let t = Transition {
+            from: Observation::Full(0usize),
+            action: 0.5,
+            reward: 1.0,
+            to: Observation::Terminal(0),
+        };
+
+        assert_eq!(agent.handle(&t).unwrap().td_error, 1.0);
+        assert!(agent.policy.mean.evaluate((&0,)) > 0.0);
+    }
+
+    #[test]
+    fn test_entropy_bonus() {
+        // Two-armed bandit in which arm 0 pays 1 and arm 1 pays 0.
+        let train = |entropy_coef| {
+            let mut rng = StdRng::seed_from_u64(0);
+            let mut agent = ActorCriticLambda::new(
+                Table::dense(Array1::zeros(1)),
+                Softmax::standard(Table::dense(Array2::zeros((1, 2)))),
+                0.1,
+                0.1,
+                0.9,
+                0.0,
+                0.0,
+            )
+            .with_entropy_bonus(Parameter::constant(entropy_coef));
+
+            for _ in 0..500 {
+                let a = agent.agent.policy.sample(&mut rng, &0usize);
+
+                agent
+                    .handle(&Transition {
+                        from: Observation::Full(0usize),
+                        action: a,
+                        reward: if a == 0 { 1.0 } else { 0.0 },
+                        to: Observation::Terminal(0),
+                    })
+                    .unwrap();
+            }
+
+            agent.agent.policy.entropy(&0)
+        };
+
+        let h_plain = train(0.0);
+        let h_bonus = train(1.0);
+
+        assert!(h_plain < 0.5);
+        assert!(h_bonus > h_plain);
+        assert!(h_bonus <= 2.0f64.ln() + 1e-10);
+    }
+}
@@ -0,0 +1,249 @@
+use crate::{
+    domains::Batch,
+    fa::linear::{basis::Basis, Features},
+    Enumerable,
+    Function,
+    Handler,
+};
+use ndarray::{Array1, Array2, ArrayView1, Axis};
+use spaces::Space;
+
+/// Projected sample, `(φ(s), a, r, φ(s'))`, with `φ(s')` omitted if terminal.
+type Sample = (Array1<f64>, usize, f64, Option<Array1<f64>>);
+
+fn dot(x: ArrayView1<f64>, y: ArrayView1<f64>) -> f64 { x.iter().zip(y).map(|(a, b)| a * b).sum() }
+
+/// Least-squares policy iteration (LSPI).
+///
+/// Given a batch of transitions, LSPI alternates between evaluating the
+/// current greedy policy with LSTD-Q and making the policy greedy with respect
+/// to the resulting action-value function, until the policy is stable over
+/// the batch or `max_iterations` is reached. The action-value function is
+/// linear, `Q(s, a) = φ(s)ᵀ θ_a`, with one column of weights per action.
+///
+/// Each evaluation step solves the LSTD-Q system recursively, maintaining its
+/// inverse via the Sherman–Morrison formula from an initial value of `I / δ`.
+///
+/// # References
+/// - Lagoudakis, M. G., Parr, R. (2003). Least-squares policy iteration.
+///   Journal of Machine Learning Research, 4, 1107–1149.
+#[derive(Debug, Parameterised)]
+pub struct LSPI<B> {
+    pub basis: B,
+    #[weights]
+    pub theta: Array2<f64>,
+
+    pub gamma: f64,
+
+    /// Ridge regularisation applied to the LSTD-Q matrix, `δ`.
+    pub regularisation: f64,
+
+    /// Maximum number of policy iterations per batch.
+    pub max_iterations: usize,
+}
+
+impl<B: Space> LSPI<B> {
+    pub fn new(basis: B, n_actions: usize, gamma: f64) -> Self {
+        let n_features: usize = basis.dim().into();
+
+        LSPI {
+            basis,
+            theta: Array2::zeros((n_features, n_actions)),
+
+            gamma,
+            regularisation: 1e-5,
+            max_iterations: 20,
+        }
+    }
+}
+
+impl<B> LSPI<B> {
+    pub fn with_regularisation(mut self, delta: f64) -> Self {
+        self.regularisation = delta;
+        self
+    }
+
+    pub fn with_max_iterations(mut self, max_iterations: usize) -> Self {
+        self.max_iterations = max_iterations;
+        self
+    }
+
+    fn q_values(&self, phi: &Array1<f64>) -> Vec<f64> {
+        self.theta.gencolumns().into_iter().map(|w| dot(phi.view(), w)).collect()
+    }
+
+    fn greedy(&self, phi: &Array1<f64>) -> usize {
+        let qs = self.q_values(phi);
+
+        (0..qs.len()).fold(0, |b, a| if qs[a] > qs[b] { a } else { b })
+    }
+
+    /// Evaluate the greedy policy with respect to the current weights over a
+    /// batch of projected samples using recursive LSTD-Q.
+    fn lstdq(&self, samples: &[Sample]) -> Array2<f64> {
+        let (n_features, n_actions) = self.theta.dim();
+        let n = n_features * n_actions;
+
+        // Block features, φ(s, a), with φ(s) in the slot for action a:
+        let block = |phi: &Array1<f64>, a: usize| {
+            let mut x = Array1::zeros(n);
+
+            x.slice_mut(s![(a * n_features)..((a + 1) * n_features)]).assign(phi);
+            x
+        };
+
+        let mut c_mat = Array2::<f64>::eye(n) / self.regularisation;
+        let mut w = Array1::<f64>::zeros(n);
+
+        for (phi_s, a, reward, phi_ns) in samples {
+            let x = block(phi_s, *a);
+            let (pd, residual) = match phi_ns {
+                Some(phi_ns) => {
+                    let nx = block(phi_ns, self.greedy(phi_ns));
+
+                    (&x - &(self.gamma * &nx), reward + self.gamma * dot(nx.view(), w.view()))
+                },
+                None => (x.clone(), *reward),
+            };
+            let residual = residual - dot(x.view(), w.view());
+
+            let v: Array1<f64> = c_mat.outer_iter().map(|row| dot(row, x.view())).collect();
+            let g: Array1<f64> =
+                c_mat.gencolumns().into_iter().map(|col| dot(col, pd.view())).collect();
+
+            let denom = 1.0 + dot(pd.view(), v.view());
+
+            for (mut row, &vi) in c_mat.outer_iter_mut().zip(v.iter()) {
+                row.scaled_add(-vi / denom, &g);
+            }
+
+            w.scaled_add(residual / denom, &v);
+        }
+
+        w.into_shape((n_actions, n_features)).unwrap().reversed_axes()
+    }
+}
+
+impl<S, B> Function<(S,)> for LSPI<B>
+where B: Basis<S, Value = Features>
+{
+    type Output = Vec<f64>;
+
+    fn evaluate(&self, (s,): (S,)) -> Vec<f64> {
+        self.q_values(&self.basis.project(s).unwrap().into_dense())
+    }
+}
+
+impl<S, B> Enumerable<(S,)> for LSPI<B>
+where B: Basis<S, Value = Features>
+{
+    fn len(&self, _: (S,)) -> usize { self.theta.len_of(Axis(1)) }
+}
+
+impl<'m, S, B> Handler<&'m Batch<S, usize>> for LSPI<B>
+where B: Basis<&'m S, Value = Features>
+{
+    /// Number of policy iterations performed.
+    type Response = usize;
+    type Error = crate::fa::linear::Error;
+
+    fn handle(&mut self, batch: &'m Batch<S, usize>) -> Result<usize, Self::Error> {
+        let samples = batch
+            .iter()
+            .map(|t| {
+                let (s, ns) = t.states();
+                let phi_ns = if t.terminated() {
+                    None
+                } else {
+                    Some(self.basis.project(ns)?.into_dense())
+                };
+
+                Ok((self.basis.project(s)?.into_dense(), t.action, t.reward, phi_ns))
+            })
+            .collect::<Result<Vec<Sample>, Self::Error>>()?;
+
+        let policy = |lspi: &Self| -> Vec<usize> {
+            samples.iter().filter_map(|x| x.3.as_ref().map(|phi| lspi.greedy(phi))).collect()
+        };
+
+        let mut current = policy(self);
+
+        for i in 1..=self.max_iterations {
+            self.theta = self.lstdq(&samples);
+
+            let next = policy(self);
+
+            if next == current {
+                return Ok(i);
+            }
+
+            current = next;
+        }
+
+        Ok(self.max_iterations)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        domains::{Domain, GridWorld, Model},
+        fa::linear::basis::Closure,
+        planning::value_iteration,
+    };
+
+    #[test]
+    fn test_grid_world_optimal_policy() {
+        let walls = [[1, 1], [2, 1], [1, 2]];
+        let gw = GridWorld::new(4, 4, [0, 0], &[[3, 3]], 10.0).with_walls(&walls);
+
+        // Record one transition per state-action pair from non-terminal cells.
+        let mut batch = vec![];
+
+        for s in 0..16 {
+            let loc = [s % 4, s / 4];
+
+            if gw.is_wall(loc) || gw.is_goal(loc) {
+                continue;
+            }
+
+            for a in 0..4 {
+                let mut domain = GridWorld::new(4, 4, loc, &[[3, 3]], 10.0).with_walls(&walls);
+
+                batch.push(domain.transition(a));
+            }
+        }
+
+        let basis = Closure::new(16, |s: &usize| {
+            let mut phi = Array1::zeros(16);
+
+            phi[*s] = 1.0;
+
+            Ok(Features::Dense(phi))
+        });
+        let mut lspi = LSPI::new(basis, 4, 0.9).with_regularisation(1e-6);
+
+        let n_iters = lspi.handle(&batch).unwrap();
+
+        assert!(n_iters < lspi.max_iterations);
+
+        let optimum = value_iteration(&gw, 0.9, 1e-10, 10_000);
+
+        for s in 0..16 {
+            let loc = [s % 4, s / 4];
+
+            if gw.is_wall(loc) || gw.is_goal(loc) {
+                continue;
+            }
+
+            let qs = lspi.evaluate((&s,));
+            let a = (0..4).fold(0, |b, a| if qs[a] > qs[b] { a } else { b });
+            let (ns, r) = gw.predict(&s, &a);
+            let nv = if ns.is_terminal() { 0.0 } else { optimum.values[*ns.state()] };
+
+            assert!((r + 0.9 * nv - optimum.values[s]).abs() < 1e-6);
+            assert!((qs[a] - optimum.values[s]).abs() < 1e-3);
+        }
+    }
+}
@@ -1,7 +1,6 @@
 use crate::{
     domains::Batch,
     fa::StateActionUpdate,
-    policies::Policy,
     Function,
     Handler,
 };
@@ -33,18 +32,18 @@ impl<B, P> BaselineREINFORCE<B, P> {
     }
 }
 
-impl<'m, S, B, P> Handler<&'m Batch<S, P::Action>> for BaselineREINFORCE<B, P>
+impl<'m, S, A, B, P> Handler<&'m Batch<S, A>> for BaselineREINFORCE<B, P>
 where
-    P: Policy<S> + Handler<StateActionUpdate<&'m S, &'m <P as Policy<S>>::Action>>,
-    B: Function<(&'m S, &'m P::Action), Output = f64>,
+    P: Handler<StateActionUpdate<&'m S, &'m A>>,
+    B: Function<(&'m S, &'m A), Output = f64>,
 {
     type Response = Vec<P::Response>;
     type Error = P::Error;
 
-    fn handle(&mut self, batch: &'m Batch<S, P::Action>) -> Result<Self::Response, Self::Error> {
+    fn handle(&mut self, batch: &'m Batch<S, A>) -> Result<Self::Response, Self::Error> {
         let mut ret = 0.0;
 
-        batch.iter().map(|t| {
+        let mut responses = batch.iter().rev().map(|t| {
             let s = t.from.state();
             let baseline = self.baseline.evaluate((s, &t.action));
 
@@ -55,6 +54,10 @@ where
                 action: &t.action,
                 error: self.alpha * (ret - baseline),
             })
-        }).collect()
+        }).collect::<Result<Vec<_>, _>>()?;
+
+        responses.reverse();
+
+        Ok(responses)
     }
 }
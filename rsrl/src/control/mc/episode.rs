@@ -0,0 +1,35 @@
+use crate::domains::{Batch, Transition};
+
+/// Accumulates the transitions of a single episode for Monte-Carlo agents.
+///
+/// Transitions are stored in the order they are pushed; once a terminal
+/// transition is observed the whole episode is flushed as a `Batch` that can
+/// be passed directly to, e.g., `REINFORCE` or `BaselineREINFORCE`.
+#[derive(Clone, Debug)]
+pub struct EpisodeBuffer<S, A> {
+    batch: Batch<S, A>,
+}
+
+impl<S, A> EpisodeBuffer<S, A> {
+    pub fn new() -> Self { EpisodeBuffer { batch: vec![] } }
+
+    pub fn len(&self) -> usize { self.batch.len() }
+
+    pub fn is_empty(&self) -> bool { self.batch.is_empty() }
+
+    /// Store a transition, returning the completed episode if it was terminal.
+    pub fn push(&mut self, transition: Transition<S, A>) -> Option<Batch<S, A>> {
+        let terminal = transition.terminated();
+
+        self.batch.push(transition);
+
+        if terminal { Some(self.flush()) } else { None }
+    }
+
+    /// Drain all stored transitions, e.g. when an episode is truncated.
+    pub fn flush(&mut self) -> Batch<S, A> { std::mem::take(&mut self.batch) }
+}
+
+impl<S, A> Default for EpisodeBuffer<S, A> {
+    fn default() -> Self { EpisodeBuffer::new() }
+}
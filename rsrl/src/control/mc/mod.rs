@@ -1,5 +1,10 @@
 //! Monte-Carlo policy gradient algorithms.
 pub mod baseline_reinforce;
+pub mod episode;
 pub mod reinforce;
 
-pub use self::{baseline_reinforce::BaselineREINFORCE, reinforce::REINFORCE};
+pub use self::{
+    baseline_reinforce::BaselineREINFORCE,
+    episode::EpisodeBuffer,
+    reinforce::REINFORCE,
+};
@@ -1,4 +1,4 @@
-use crate::{domains::Batch, fa::StateActionUpdate, policies::Policy, Handler};
+use crate::{domains::Batch, fa::StateActionUpdate, Handler};
 
 #[derive(Clone, Debug, Parameterised)]
 #[cfg_attr(
@@ -25,16 +25,16 @@ impl<P> REINFORCE<P> {
     }
 }
 
-impl<'m, S, P> Handler<&'m Batch<S, P::Action>> for REINFORCE<P>
-where P: Policy<S> + Handler<StateActionUpdate<&'m S, &'m <P as Policy<S>>::Action>>
+impl<'m, S, A, P> Handler<&'m Batch<S, A>> for REINFORCE<P>
+where P: Handler<StateActionUpdate<&'m S, &'m A>>
 {
     type Response = Vec<P::Response>;
     type Error = P::Error;
 
-    fn handle(&mut self, batch: &'m Batch<S, P::Action>) -> Result<Self::Response, Self::Error> {
+    fn handle(&mut self, batch: &'m Batch<S, A>) -> Result<Self::Response, Self::Error> {
         let mut ret = 0.0;
 
-        batch.iter().map(|t| {
+        let mut responses = batch.iter().rev().map(|t| {
             ret = t.reward + self.gamma * ret;
 
             self.policy.handle(StateActionUpdate {
@@ -42,6 +42,61 @@ where P: Policy<S> + Handler<StateActionUpdate<&'m S, &'m <P as Policy<S>>::Acti
                 action: &t.action,
                 error: self.alpha * ret,
             })
-        }).collect()
+        }).collect::<Result<Vec<_>, _>>()?;
+
+        responses.reverse();
+
+        Ok(responses)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::REINFORCE;
+    use crate::{
+        control::mc::EpisodeBuffer,
+        domains::{Domain, GridWorld},
+        fa::tabular::Table,
+        policies::{Policy, Softmax},
+        Function,
+        Handler,
+    };
+    use ndarray::Array2;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn test_corridor_shifts_mass_right() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let policy = Softmax::standard(Table::dense(Array2::zeros((6, 4))));
+        let mut agent = REINFORCE::new(policy, 0.01, 0.9);
+        let mut buffer = EpisodeBuffer::new();
+
+        // Action 3 moves right, towards the goal at the end of the corridor.
+        let p_right = |agent: &REINFORCE<Softmax<_>>| agent.policy.evaluate((&0usize,))[3];
+        let p_initial = p_right(&agent);
+
+        for _ in 0..200 {
+            let mut domain = GridWorld::new(6, 1, [0, 0], &[[5, 0]], 0.0);
+
+            for _ in 0..100 {
+                let a = agent.policy.sample(&mut rng, domain.emit().state());
+
+                if let Some(episode) = buffer.push(domain.transition(a)) {
+                    agent.handle(&episode).unwrap();
+
+                    break;
+                }
+            }
+
+            let truncated = buffer.flush();
+
+            if !truncated.is_empty() {
+                agent.handle(&truncated).unwrap();
+            }
+        }
+
+        assert!(buffer.is_empty());
+        assert!(p_right(&agent) > p_initial);
+        assert!(p_right(&agent) > 0.4);
     }
 }
@@ -1,6 +1,7 @@
 //! Control agents module.
 // Critic-only:
 pub mod td;
+pub mod lspi;
 
 // Actor-only:
 pub mod mc;
@@ -10,6 +11,9 @@ pub mod ac;
 pub mod nac;
 pub mod cacla;
 
+// Hierarchical:
+pub mod options;
+
 // TODO
 // Proximal gradient-descent methods:
 // https://arxiv.org/pdf/1210.4893.pdf
@@ -0,0 +1,328 @@
+//! Temporally-extended actions and semi-Markov control.
+//!
+//! An option, `o = (I, π, β)`, is a closed-loop policy, `π`, that may only be
+//! initiated in states belonging to its initiation set, `I`, and which
+//! terminates in each state `s` with probability `β(s)`. Augmenting the
+//! primitive actions of a domain with a set of options yields a semi-Markov
+//! decision process (SMDP), in which each decision may span a variable number
+//! of time steps.
+use crate::{
+    domains::{Domain, Observation, State},
+    fa::StateActionUpdate,
+    spaces::Space,
+    Enumerable,
+    Function,
+    Handler,
+    Parameterised,
+};
+use rand::Rng;
+
+/// Trait for temporally-extended actions defined over states of type `S`.
+pub trait TemporallyExtended<S> {
+    /// Return true if the option may be initiated in state `s`.
+    fn can_initiate(&self, s: &S) -> bool;
+
+    /// Return the primitive action selected by the option's policy in `s`.
+    fn action(&self, s: &S) -> usize;
+
+    /// Return the probability that the option terminates in state `s`.
+    fn termination_probability(&self, s: &S) -> f64;
+}
+
+impl<S, T: TemporallyExtended<S> + ?Sized> TemporallyExtended<S> for Box<T> {
+    fn can_initiate(&self, s: &S) -> bool { (**self).can_initiate(s) }
+
+    fn action(&self, s: &S) -> usize { (**self).action(s) }
+
+    fn termination_probability(&self, s: &S) -> f64 { (**self).termination_probability(s) }
+}
+
+/// Markov option defined by an initiation set, a policy and a termination
+/// condition.
+///
+/// Each component is an arbitrary function of the state: the initiation set
+/// maps to `bool`, the policy to a primitive action index, and the termination
+/// condition to a probability in `[0, 1]`.
+#[derive(Clone, Debug)]
+pub struct MarkovOption<I, P, B> {
+    pub initiation_set: I,
+    pub policy: P,
+    pub termination: B,
+}
+
+impl<I, P, B> MarkovOption<I, P, B> {
+    pub fn new(initiation_set: I, policy: P, termination: B) -> Self {
+        MarkovOption {
+            initiation_set,
+            policy,
+            termination,
+        }
+    }
+}
+
+impl<S, I, P, B> TemporallyExtended<S> for MarkovOption<I, P, B>
+where
+    I: Fn(&S) -> bool,
+    P: Fn(&S) -> usize,
+    B: Fn(&S) -> f64,
+{
+    fn can_initiate(&self, s: &S) -> bool { (self.initiation_set)(s) }
+
+    fn action(&self, s: &S) -> usize { (self.policy)(s) }
+
+    fn termination_probability(&self, s: &S) -> f64 { (self.termination)(s) }
+}
+
+/// Transition spanning the (possibly multi-step) execution of an option.
+#[derive(Clone, Copy, Debug)]
+pub struct SMDPTransition<S> {
+    /// State in which the option was initiated, `s`.
+    pub from: Observation<S>,
+
+    /// Index of the primitive action or option that was executed.
+    pub action: usize,
+
+    /// Discounted sum of the rewards accrued during execution,
+    /// `Σ_{k=0}^{τ-1} γ^k r_{t+k+1}`.
+    pub reward: f64,
+
+    /// Number of primitive steps taken, `τ`.
+    pub duration: usize,
+
+    /// State in which the option terminated, `s'`.
+    pub to: Observation<S>,
+}
+
+impl<S> SMDPTransition<S> {
+    /// Returns true if the transition ends in a terminal state.
+    pub fn terminated(&self) -> bool { self.to.is_terminal() }
+}
+
+#[derive(Clone, Debug)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate")
+)]
+pub struct Response<R> {
+    pub q_res: R,
+    pub error: f64,
+}
+
+/// SMDP Q-learning over primitive actions and options.
+///
+/// The action-value function is defined over `n_primitives + options.len()`
+/// choices: indices below `n_primitives` denote primitive actions, which are
+/// always available, and the remainder denote the options in order. After
+/// executing choice `o` from `s` for `τ` steps, the update is
+///
+/// `Q(s, o) ← Q(s, o) + α [R + γ^τ max_{o'} Q(s', o') - Q(s, o)]`,
+///
+/// where `R` is the discounted reward accrued during execution and the
+/// maximum is taken over the choices available in `s'`. The step size, `α`, is
+/// that of `q_func`, which receives the unscaled TD error.
+///
+/// # References
+/// - Bradtke, S. J., Duff, M. O. (1995). Reinforcement learning methods for
+///   continuous-time Markov decision problems. In Advances in Neural
+///   Information Processing Systems, pp. 393–400.
+/// - Sutton, R. S., Precup, D., Singh, S. (1999). Between MDPs and semi-MDPs:
+///   A framework for temporal abstraction in reinforcement learning.
+///   Artificial Intelligence, 112(1-2), 181–211.
+#[derive(Clone, Debug, Parameterised)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate")
+)]
+pub struct SMDPQLearning<Q, O> {
+    #[weights]
+    pub q_func: Q,
+    pub options: Vec<O>,
+    pub n_primitives: usize,
+
+    pub gamma: f64,
+}
+
+impl<Q, O> SMDPQLearning<Q, O> {
+    pub fn new(q_func: Q, options: Vec<O>, n_primitives: usize, gamma: f64) -> Self {
+        SMDPQLearning {
+            q_func,
+            options,
+            n_primitives,
+
+            gamma,
+        }
+    }
+
+    /// Return the total number of choices, primitive and temporally-extended.
+    pub fn n_choices(&self) -> usize { self.n_primitives + self.options.len() }
+
+    /// Return true if the choice with the given index may be made in `s`.
+    pub fn is_available<S>(&self, s: &S, index: usize) -> bool
+    where O: TemporallyExtended<S> {
+        index < self.n_primitives || self.options[index - self.n_primitives].can_initiate(s)
+    }
+
+    /// Return the indices of all choices available in `s`.
+    pub fn available<S>(&self, s: &S) -> Vec<usize>
+    where O: TemporallyExtended<S> {
+        (0..self.n_choices()).filter(|&i| self.is_available(s, i)).collect()
+    }
+
+    /// Return the available choice with the highest value in `s`, along with
+    /// its value; ties are broken in favour of the lowest index.
+    pub fn greedy<'s, S>(&self, s: &'s S) -> (usize, f64)
+    where
+        Q: Function<(&'s S,), Output = Vec<f64>>,
+        O: TemporallyExtended<S>,
+    {
+        let qs = self.q_func.evaluate((s,));
+
+        self.available(s)
+            .into_iter()
+            .map(|i| (i, qs[i]))
+            .fold((0, f64::MIN), |acc, (i, q)| if q > acc.1 { (i, q) } else { acc })
+    }
+
+    /// Execute the choice with the given index in `domain`, from its current
+    /// state, until the option terminates or a terminal state is reached.
+    ///
+    /// Primitive actions always last a single step. Options whose termination
+    /// condition is never satisfied will run until the episode ends.
+    pub fn execute<D, R>(
+        &self,
+        rng: &mut R,
+        domain: &mut D,
+        index: usize,
+    ) -> SMDPTransition<State<D>>
+    where
+        D: Domain,
+        D::ActionSpace: Space<Value = usize>,
+        O: TemporallyExtended<State<D>>,
+        R: Rng + ?Sized,
+    {
+        let from = domain.emit();
+
+        if index < self.n_primitives {
+            let (to, reward) = domain.step(&index);
+
+            return SMDPTransition {
+                from,
+                action: index,
+                reward,
+                duration: 1,
+                to,
+            };
+        }
+
+        let option = &self.options[index - self.n_primitives];
+
+        let mut reward = 0.0;
+        let mut discount = 1.0;
+        let mut duration = 0;
+
+        loop {
+            let a = option.action(domain.emit().state());
+            let (to, r) = domain.step(&a);
+
+            reward += discount * r;
+            discount *= self.gamma;
+            duration += 1;
+
+            let beta = option.termination_probability(to.state()).clamp(0.0, 1.0);
+
+            if to.is_terminal() || rng.gen_bool(beta) {
+                return SMDPTransition {
+                    from,
+                    action: index,
+                    reward,
+                    duration,
+                    to,
+                };
+            }
+        }
+    }
+}
+
+impl<'m, S, Q, O> Handler<&'m SMDPTransition<S>> for SMDPQLearning<Q, O>
+where
+    Q: Enumerable<(&'m S,), Output = Vec<f64>> + Handler<StateActionUpdate<&'m S, usize, f64>>,
+    O: TemporallyExtended<S>,
+{
+    type Response = Response<Q::Response>;
+    type Error = Q::Error;
+
+    fn handle(&mut self, t: &'m SMDPTransition<S>) -> Result<Self::Response, Self::Error> {
+        let state = t.from.state();
+        let qsa = self.q_func.evaluate_index((state,), t.action);
+
+        let error = if t.terminated() {
+            t.reward - qsa
+        } else {
+            let (_, nqsna) = self.greedy(t.to.state());
+
+            t.reward + self.gamma.powi(t.duration as i32) * nqsna - qsa
+        };
+
+        self.q_func
+            .handle(StateActionUpdate {
+                state,
+                action: t.action,
+                error,
+            })
+            .map(|q_res| Response { q_res, error })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{domains::GridWorld, fa::tabular::Table};
+    use ndarray::Array2;
+    use rand::thread_rng;
+
+    #[test]
+    fn test_option_reaches_subgoal() {
+        // Corridor of 5 cells with the goal at the far right; the option moves
+        // right until it reaches the subgoal in cell 2.
+        let mut domain = GridWorld::new(5, 1, [0, 0], &[[4, 0]], 0.0);
+        let option = MarkovOption::new(
+            |s: &usize| *s < 2,
+            |_: &usize| 3,
+            |s: &usize| if *s == 2 { 1.0 } else { 0.0 },
+        );
+
+        let mut q = Array2::zeros((5, 5));
+
+        q[[2, 3]] = 5.0;
+        q[[2, 4]] = 10.0;
+
+        let mut agent = SMDPQLearning::new(Table::dense(q), vec![option], 4, 0.9);
+
+        assert_eq!(agent.n_choices(), 5);
+        assert_eq!(agent.available(&0), vec![0, 1, 2, 3, 4]);
+        assert_eq!(agent.available(&2), vec![0, 1, 2, 3]);
+
+        // The option is unavailable in cell 2, so its value is ignored.
+        assert_eq!(agent.greedy(&2), (3, 5.0));
+
+        let t = agent.execute(&mut thread_rng(), &mut domain, 4);
+
+        assert_eq!(*t.from.state(), 0);
+        assert_eq!(*t.to.state(), 2);
+        assert_eq!(t.duration, 2);
+        assert!((t.reward + 1.9).abs() < 1e-10);
+
+        let res = agent.handle(&t).unwrap();
+
+        // R + γ^2 max Q(s', .) = -1.9 + 0.81 * 5.
+        assert!((res.error - 2.15).abs() < 1e-10);
+        assert!((agent.q_func.evaluate_index((&0,), 4) - 2.15).abs() < 1e-10);
+
+        let t = agent.execute(&mut thread_rng(), &mut domain, 3);
+
+        assert_eq!(*t.to.state(), 3);
+        assert_eq!((t.duration, t.reward), (1, -1.0));
+    }
+}
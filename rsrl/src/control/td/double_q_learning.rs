@@ -0,0 +1,225 @@
+use super::q_learning::Response;
+use crate::{
+    domains::Transition,
+    fa::StateActionUpdate,
+    Enumerable,
+    Function,
+    Handler,
+    Parameterised,
+};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::ops::Index;
+
+/// Double Q-learning.
+///
+/// Maintains two action-value estimators; at each step one of the two is
+/// chosen uniformly at random to be updated, using its own greedy action in
+/// the successor state but the _other_ estimator's value for that action. This
+/// decoupling of action selection and evaluation removes the maximisation
+/// bias of standard Q-learning.
+///
+/// The agent itself evaluates to the average of the two estimators, so a
+/// shared instance can be passed directly to a policy such as `Greedy`.
+///
+/// # References
+/// - van Hasselt, H. (2010). Double Q-learning. In Advances in Neural
+///   Information Processing Systems, pp. 2613–2621.
+#[derive(Clone, Debug, Parameterised)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate")
+)]
+pub struct DoubleQLearning<Q> {
+    #[weights]
+    pub q_func_a: Q,
+    pub q_func_b: Q,
+
+    pub alpha: f64,
+    pub gamma: f64,
+
+    /// Random number generator used to choose which estimator to update.
+    #[cfg_attr(feature = "serde", serde(skip, default = "StdRng::from_entropy"))]
+    pub rng: StdRng,
+}
+
+impl<Q> DoubleQLearning<Q> {
+    pub fn new(q_func_a: Q, q_func_b: Q, alpha: f64, gamma: f64) -> Self {
+        DoubleQLearning {
+            q_func_a,
+            q_func_b,
+
+            alpha,
+            gamma,
+
+            rng: StdRng::from_entropy(),
+        }
+    }
+
+    /// Choose which estimator to update using the given `rng`, such that two
+    /// identically seeded agents make the same updates.
+    pub fn with_rng(mut self, rng: StdRng) -> Self {
+        self.rng = rng;
+        self
+    }
+}
+
+fn double_update<'m, S, Q>(
+    q_update: &mut Q,
+    q_target: &Q,
+    t: &'m Transition<S, usize>,
+    alpha: f64,
+    gamma: f64,
+) -> Result<Response<Q::Response>, Q::Error>
+where
+    Q: Enumerable<(&'m S,)> + Handler<StateActionUpdate<&'m S, usize, f64>>,
+    <Q as Function<(&'m S,)>>::Output: Index<usize, Output = f64> + IntoIterator<Item = f64>,
+    <<Q as Function<(&'m S,)>>::Output as IntoIterator>::IntoIter: ExactSizeIterator,
+{
+    let state = t.from.state();
+    let qsa = q_update.evaluate_index((state,), t.action);
+
+    let error = if t.terminated() {
+        t.reward - qsa
+    } else {
+        let ns = t.to.state();
+        let (na, _) = q_update.find_max((ns,));
+
+        t.reward + gamma * q_target.evaluate_index((ns,), na) - qsa
+    };
+
+    q_update
+        .handle(StateActionUpdate {
+            state,
+            action: t.action,
+            error: alpha * error,
+        })
+        .map(|q_res| Response { q_res, error })
+}
+
+impl<'m, S, Q> Handler<&'m Transition<S, usize>> for DoubleQLearning<Q>
+where
+    Q: Enumerable<(&'m S,)> + Handler<StateActionUpdate<&'m S, usize, f64>>,
+    <Q as Function<(&'m S,)>>::Output: Index<usize, Output = f64> + IntoIterator<Item = f64>,
+    <<Q as Function<(&'m S,)>>::Output as IntoIterator>::IntoIter: ExactSizeIterator,
+{
+    type Response = Response<Q::Response>;
+    type Error = Q::Error;
+
+    fn handle(&mut self, t: &'m Transition<S, usize>) -> Result<Self::Response, Self::Error> {
+        if self.rng.gen_bool(0.5) {
+            double_update(&mut self.q_func_a, &self.q_func_b, t, self.alpha, self.gamma)
+        } else {
+            double_update(&mut self.q_func_b, &self.q_func_a, t, self.alpha, self.gamma)
+        }
+    }
+}
+
+impl<'s, S, Q> Function<(&'s S,)> for DoubleQLearning<Q>
+where Q: Function<(&'s S,), Output = Vec<f64>>
+{
+    type Output = Vec<f64>;
+
+    fn evaluate(&self, (s,): (&'s S,)) -> Vec<f64> {
+        let qa = self.q_func_a.evaluate((s,));
+        let qb = self.q_func_b.evaluate((s,));
+
+        qa.into_iter().zip(qb).map(|(a, b)| (a + b) / 2.0).collect()
+    }
+}
+
+impl<'s, S, Q> Enumerable<(&'s S,)> for DoubleQLearning<Q>
+where Q: Function<(&'s S,), Output = Vec<f64>>
+{
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        control::td::QLearning,
+        domains::Observation,
+        fa::{
+            linear::{basis::Closure, Features},
+            tabular::Table,
+            SimpleQLFA,
+        },
+        params::Parameterised,
+    };
+    use ndarray::Array2;
+    use rand::{rngs::StdRng, SeedableRng};
+    use rand_distr::{Distribution, Normal};
+
+    // Maximisation bias example (Sutton & Barto, 2018, Example 6.7): from state
+    // 0 the agent may move to state 1, from which every one of 10 actions
+    // terminates with a reward drawn from N(-0.1, 1). The true value of moving
+    // to state 1 is therefore -0.1.
+    fn transitions(rng: &mut StdRng) -> Vec<Transition<usize, usize>> {
+        let noise = Normal::new(-0.1, 1.0).unwrap();
+        let mut ts = vec![Transition {
+            from: Observation::Full(0),
+            action: 0,
+            reward: 0.0,
+            to: Observation::Full(1),
+        }];
+
+        ts.extend((0..10).map(|a| Transition {
+            from: Observation::Full(1),
+            action: a,
+            reward: noise.sample(rng),
+            to: Observation::Terminal(1),
+        }));
+
+        ts
+    }
+
+    #[test]
+    fn test_reduced_maximisation_bias() {
+        let mut rng = StdRng::seed_from_u64(0);
+
+        // A one-hot basis gives tabular Q-learning with the same step size.
+        let one_hot = Closure::new(2, |s: &usize| Ok(Features::sparse(2, vec![(*s, 1.0)])));
+        let mut ql = QLearning::new(SimpleQLFA::new(one_hot, 0.1, 10), 1.0);
+        let mut dql = DoubleQLearning::new(
+            Table::dense(Array2::zeros((2, 10))),
+            Table::dense(Array2::zeros((2, 10))),
+            0.1,
+            1.0,
+        )
+        .with_rng(StdRng::seed_from_u64(1));
+
+        for _ in 0..1000 {
+            for t in transitions(&mut rng).iter().rev() {
+                ql.handle(t).unwrap();
+                dql.handle(t).unwrap();
+            }
+        }
+
+        let ql_err = (ql.q_func.evaluate_index((&0,), 0) + 0.1).abs();
+        let dql_err = (dql.evaluate_index((&0,), 0) + 0.1).abs();
+
+        assert!(ql.q_func.evaluate_index((&0,), 0) > -0.1);
+        assert!(dql_err < ql_err);
+    }
+
+    #[test]
+    fn test_seeded_updates() {
+        let agent = || {
+            let q = || Table::dense(Array2::zeros((2, 10)));
+
+            DoubleQLearning::new(q(), q(), 0.1, 1.0).with_rng(StdRng::seed_from_u64(1))
+        };
+        let (mut dql1, mut dql2) = (agent(), agent());
+        let mut rng = StdRng::seed_from_u64(0);
+
+        for _ in 0..100 {
+            for t in transitions(&mut rng).iter().rev() {
+                dql1.handle(t).unwrap();
+                dql2.handle(t).unwrap();
+            }
+        }
+
+        assert_eq!(dql1.q_func_a.weights_view(), dql2.q_func_a.weights_view());
+        assert_eq!(dql1.q_func_b.weights_view(), dql2.q_func_b.weights_view());
+    }
+}
@@ -0,0 +1,373 @@
+use crate::{
+    domains::{Domain, Model, Observation, Reward, Transition},
+    fa::StateActionUpdate,
+    spaces::discrete::Ordinal,
+    utils::clip_error,
+    Enumerable,
+    Handler,
+    Parameterised,
+};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::collections::HashMap;
+
+#[derive(Clone, Debug)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate")
+)]
+pub struct Response<R> {
+    pub q_res: R,
+    pub error: f64,
+}
+
+/// Deterministic tabular model learned from experience.
+///
+/// The model records the most recent outcome observed for each state-action
+/// pair, and can replay any of the pairs seen so far. Pairs that have not yet
+/// been observed are predicted to leave the state unchanged with zero reward.
+///
+/// As a [`Domain`], the model simulates episodes starting from the first state
+/// it recorded.
+#[derive(Clone, Debug)]
+pub struct TabularModel {
+    n_states: usize,
+    n_actions: usize,
+
+    transitions: Vec<Transition<usize, usize>>,
+    index: HashMap<(usize, usize), usize>,
+
+    start: Option<usize>,
+    state: Observation<usize>,
+}
+
+impl TabularModel {
+    pub fn new(n_states: usize, n_actions: usize) -> Self {
+        TabularModel {
+            n_states,
+            n_actions,
+
+            transitions: vec![],
+            index: HashMap::new(),
+
+            start: None,
+            state: Observation::Full(0),
+        }
+    }
+
+    /// Return the number of distinct state-action pairs in the model.
+    pub fn len(&self) -> usize { self.transitions.len() }
+
+    pub fn is_empty(&self) -> bool { self.transitions.is_empty() }
+
+    /// Return true if the outcome of taking `action` in state `s` has been
+    /// observed.
+    pub fn contains(&self, s: usize, action: usize) -> bool {
+        self.index.contains_key(&(s, action))
+    }
+
+    /// Record the outcome of a transition, replacing any previous outcome for
+    /// the same state-action pair.
+    pub fn record(&mut self, t: &Transition<usize, usize>) {
+        let key = (*t.from.state(), t.action);
+
+        if self.start.is_none() {
+            self.start = Some(key.0);
+            self.state = t.from;
+        }
+
+        match self.index.get(&key) {
+            Some(&i) => self.transitions[i] = *t,
+            None => {
+                self.index.insert(key, self.transitions.len());
+                self.transitions.push(*t);
+            },
+        }
+    }
+
+    /// Sample a previously observed transition uniformly at random.
+    ///
+    /// # Panics
+    /// If the model is empty.
+    pub fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> &Transition<usize, usize> {
+        &self.transitions[rng.gen_range(0, self.transitions.len())]
+    }
+}
+
+impl Domain for TabularModel {
+    type StateSpace = Ordinal;
+    type ActionSpace = Ordinal;
+
+    fn state_space(&self) -> Ordinal { Ordinal::new(self.n_states) }
+
+    fn action_space(&self) -> Ordinal { Ordinal::new(self.n_actions) }
+
+    fn emit(&self) -> Observation<usize> { self.state }
+
+    fn reset(&mut self) { self.state = Observation::Full(self.start.unwrap_or(0)); }
+
+    fn step(&mut self, action: &usize) -> (Observation<usize>, Reward) {
+        let (to, reward) = self.predict(self.state.state(), action);
+
+        self.state = to;
+
+        (to, reward)
+    }
+}
+
+impl Model for TabularModel {
+    fn predict(&self, s: &usize, action: &usize) -> (Observation<usize>, Reward) {
+        match self.index.get(&(*s, *action)) {
+            Some(&i) => (self.transitions[i].to, self.transitions[i].reward),
+            None => (Observation::Full(*s), 0.0),
+        }
+    }
+}
+
+/// Dyna-Q: Q-learning interleaved with planning over a learned model.
+///
+/// Each real transition is used for a standard Q-learning update and recorded
+/// in a deterministic tabular model. The agent then performs
+/// `n_planning_steps` further Q-learning updates on transitions replayed from
+/// state-action pairs sampled uniformly from those seen so far.
+///
+/// # References
+/// - Sutton, R. S. (1990). Integrated architectures for learning, planning,
+///   and reacting based on approximating dynamic programming. In Proceedings of
+///   the Seventh International Conference on Machine Learning, pp. 216–224.
+#[derive(Clone, Debug, Parameterised)]
+pub struct DynaQ<Q> {
+    #[weights]
+    pub q_func: Q,
+    pub model: TabularModel,
+
+    pub gamma: f64,
+    pub n_planning_steps: usize,
+
+    /// Optional bound, `δ`, on the magnitude of the TD error used in updates.
+    pub error_clip: Option<f64>,
+
+    /// Random number generator used to sample planning transitions.
+    pub rng: StdRng,
+}
+
+impl<Q> DynaQ<Q> {
+    pub fn new(q_func: Q, model: TabularModel, gamma: f64, n_planning_steps: usize) -> Self {
+        DynaQ {
+            q_func,
+            model,
+
+            gamma,
+            n_planning_steps,
+
+            error_clip: None,
+            rng: StdRng::from_entropy(),
+        }
+    }
+
+    /// Clip the TD error to `[-delta, delta]` before updating the Q-function;
+    /// the reported `error` is left unclipped.
+    pub fn with_error_clip(mut self, delta: f64) -> Self {
+        self.error_clip = Some(delta);
+        self
+    }
+
+    /// Sample planning transitions using the given `rng`, such that two
+    /// identically seeded agents make the same updates.
+    pub fn with_rng(mut self, rng: StdRng) -> Self {
+        self.rng = rng;
+        self
+    }
+
+    /// Perform `n` planning updates using transitions sampled from the model.
+    pub fn plan<E>(&mut self, n: usize) -> Result<(), E>
+    where
+        Q: for<'a> Enumerable<(&'a usize,), Output = Vec<f64>>
+            + for<'a> Handler<StateActionUpdate<&'a usize, usize, f64>, Error = E>,
+    {
+        if self.model.is_empty() {
+            return Ok(());
+        }
+
+        for _ in 0..n {
+            let t = self.model.sample(&mut self.rng);
+
+            q_update(&mut self.q_func, self.gamma, self.error_clip, t)?;
+        }
+
+        Ok(())
+    }
+}
+
+fn q_update<'m, S, Q>(
+    q_func: &mut Q,
+    gamma: f64,
+    error_clip: Option<f64>,
+    t: &'m Transition<S, usize>,
+) -> Result<(Q::Response, f64), Q::Error>
+where
+    Q: Enumerable<(&'m S,), Output = Vec<f64>> + Handler<StateActionUpdate<&'m S, usize, f64>>,
+{
+    let state = t.from.state();
+    let qsa = q_func.evaluate_index((state,), t.action);
+
+    let error = if t.terminated() {
+        t.reward - qsa
+    } else {
+        let (_, nqsna) = q_func.find_max((t.to.state(),));
+
+        t.reward + gamma * nqsna - qsa
+    };
+
+    q_func
+        .handle(StateActionUpdate {
+            state,
+            action: t.action,
+            error: clip_error(error, error_clip),
+        })
+        .map(|q_res| (q_res, error))
+}
+
+impl<'m, Q, E> Handler<&'m Transition<usize, usize>> for DynaQ<Q>
+where
+    Q: for<'a> Enumerable<(&'a usize,), Output = Vec<f64>>
+        + for<'a> Handler<StateActionUpdate<&'a usize, usize, f64>, Error = E>,
+{
+    type Response = Response<<Q as Handler<StateActionUpdate<&'m usize, usize, f64>>>::Response>;
+    type Error = E;
+
+    fn handle(&mut self, t: &'m Transition<usize, usize>) -> Result<Self::Response, Self::Error> {
+        let (q_res, error) = q_update(&mut self.q_func, self.gamma, self.error_clip, t)?;
+
+        self.model.record(t);
+        self.plan(self.n_planning_steps)?;
+
+        Ok(Response { q_res, error })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        domains::GridWorld,
+        fa::tabular::Table,
+        params::Parameterised,
+        Function,
+    };
+    use ndarray::Array2;
+
+    fn model() -> TabularModel { TabularModel::new(6, 4) }
+
+    fn steps_to_learn<H>(agent: &mut H, q: impl Fn(&H, usize) -> Vec<f64>) -> usize
+    where H: for<'m> Handler<&'m Transition<usize, usize>> {
+        let mut domain = GridWorld::new(6, 1, [0, 0], &[[5, 0]], 0.0);
+        let mut total = 0;
+
+        for _ in 0..10 {
+            domain.reset();
+
+            loop {
+                let s = *domain.emit().state();
+                let qs = q(agent, s);
+                let a = (0..4).fold(0, |b, a| if qs[a] > qs[b] { a } else { b });
+                let t = domain.transition(a);
+
+                agent.handle(&t).ok().unwrap();
+                total += 1;
+
+                if t.terminated() {
+                    break;
+                }
+            }
+        }
+
+        total
+    }
+
+    #[test]
+    fn test_model() {
+        let mut model = TabularModel::new(2, 2);
+        let t = |action, reward| Transition {
+            from: Observation::Full(0usize),
+            action,
+            reward,
+            to: Observation::Full(1),
+        };
+
+        model.record(&t(0, 1.0));
+        model.record(&t(1, 2.0));
+        model.record(&t(0, 3.0));
+
+        assert_eq!(model.len(), 2);
+        assert_eq!(model.predict(&0, &0), (Observation::Full(1), 3.0));
+
+        // Unobserved pairs leave the state unchanged with zero reward.
+        assert!(!model.contains(1, 0));
+        assert_eq!(model.predict(&1, &0), (Observation::Full(1), 0.0));
+    }
+
+    #[test]
+    fn test_model_domain() {
+        let mut model = TabularModel::new(3, 1);
+
+        model.record(&Transition {
+            from: Observation::Full(0usize),
+            action: 0,
+            reward: -1.0,
+            to: Observation::Full(1),
+        });
+        model.record(&Transition {
+            from: Observation::Full(1usize),
+            action: 0,
+            reward: 5.0,
+            to: Observation::Terminal(2),
+        });
+
+        assert_eq!(model.state_space(), Ordinal::new(3));
+        assert_eq!(model.action_space(), Ordinal::new(1));
+
+        model.reset();
+        assert_eq!(model.emit(), Observation::Full(0));
+
+        let t = model.transition(0);
+        assert_eq!((t.reward, t.to), (-1.0, Observation::Full(1)));
+
+        let t = model.transition(0);
+        assert_eq!((t.reward, t.to), (5.0, Observation::Terminal(2)));
+
+        model.reset();
+        assert_eq!(model.emit(), Observation::Full(0));
+    }
+
+    #[test]
+    fn test_planning_improves_sample_efficiency() {
+        let mut dyna = DynaQ::new(Table::dense(Array2::zeros((6, 4))), model(), 1.0, 50)
+            .with_rng(StdRng::seed_from_u64(0));
+        let dyna_steps = steps_to_learn(&mut dyna, |d, s| d.q_func.evaluate((s,)));
+
+        let mut plain = DynaQ::new(Table::dense(Array2::zeros((6, 4))), model(), 1.0, 0);
+        let plain_steps = steps_to_learn(&mut plain, |d, s| d.q_func.evaluate((s,)));
+
+        assert!(dyna_steps < plain_steps);
+
+        // Planning recovers the optimal values along the corridor.
+        for s in 0..5 {
+            assert_eq!(dyna.q_func.evaluate((s, 3)), -((4 - s) as f64));
+        }
+    }
+
+    #[test]
+    fn test_seeded_planning() {
+        let agent = || {
+            DynaQ::new(Table::dense(Array2::zeros((6, 4))), model(), 0.9, 5)
+                .with_rng(StdRng::seed_from_u64(1))
+        };
+        let (mut a, mut b) = (agent(), agent());
+
+        steps_to_learn(&mut a, |d, s| d.q_func.evaluate((s,)));
+        steps_to_learn(&mut b, |d, s| d.q_func.evaluate((s,)));
+
+        assert_eq!(a.q_func.weights_view(), b.q_func.weights_view());
+    }
+}
@@ -1,12 +1,13 @@
 use crate::{
     domains::Transition,
     fa::StateActionUpdate,
-    policies::EnumerablePolicy,
+    policies::{EnumerablePolicy, Exploratory, Policy},
     Enumerable,
     Function,
     Handler,
     Parameterised,
 };
+use rand::Rng;
 use std::ops::Index;
 
 /// Action probability-weighted variant of SARSA (aka "summation Q-learning").
@@ -26,6 +27,58 @@ pub struct ExpectedSARSA<Q, P> {
 
     pub alpha: f64,
     pub gamma: f64,
+
+    /// If true, [`act`](ExpectedSARSA::act) returns the mode of `policy`
+    /// instead of sampling from it; learning is unaffected.
+    pub greedy: bool,
+}
+
+impl<Q, P> ExpectedSARSA<Q, P> {
+    pub fn new(q_func: Q, policy: P, alpha: f64, gamma: f64) -> Self {
+        ExpectedSARSA {
+            q_func,
+            policy,
+
+            alpha,
+            gamma,
+
+            greedy: false,
+        }
+    }
+
+    /// Enable or disable greedy action selection in
+    /// [`act`](ExpectedSARSA::act).
+    pub fn set_greedy(&mut self, greedy: bool) { self.greedy = greedy; }
+
+    /// Select an action in state `s`: the mode of the policy in greedy mode,
+    /// and a sample from it otherwise.
+    pub fn act<S, R>(&self, rng: &mut R, s: S) -> P::Action
+    where
+        P: Policy<S>,
+        R: Rng + ?Sized,
+    {
+        if self.greedy {
+            self.policy.mode(s)
+        } else {
+            self.policy.sample(rng, s)
+        }
+    }
+
+    /// Return a copy of the agent whose policy acts greedily, for evaluation.
+    ///
+    /// See [`Exploratory::clone_for_eval`] for how the weights are shared.
+    pub fn clone_for_eval(&self) -> Self
+    where
+        Q: Clone,
+        P: Exploratory,
+    {
+        ExpectedSARSA::new(
+            self.q_func.clone(),
+            self.policy.clone_for_eval(),
+            self.alpha,
+            self.gamma,
+        )
+    }
 }
 
 impl<'m, S, Q, P> Handler<&'m Transition<S, usize>> for ExpectedSARSA<Q, P>
@@ -49,11 +102,7 @@ where
             t.reward - qsa
         } else {
             let ns = t.to.state();
-            let exp_nv = self.q_func
-                .evaluate((ns,))
-                .into_iter()
-                .zip(self.policy.evaluate((ns,)).into_iter())
-                .fold(0.0, |acc, (q, p)| acc + q * p);
+            let exp_nv = self.q_func.expected_value((ns,), self.policy.probabilities(ns));
 
             t.reward + self.gamma * exp_nv - qsa
         };
@@ -65,3 +114,88 @@ where
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        control::td::QLearning,
+        domains::Observation,
+        fa::tabular::Table,
+        make_shared,
+        policies::{EpsilonGreedy, Greedy, Random},
+    };
+    use ndarray::array;
+    use rand::{rngs::StdRng, SeedableRng};
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_greedy_matches_q_learning() {
+        let q_init = array![[0.5, -1.0, 2.0], [1.0, 3.0, -2.0]];
+
+        let mut ql = QLearning::new(Table::dense(q_init.clone()), 0.9);
+        let mut es = {
+            let q_func = make_shared(Table::dense(q_init));
+
+            ExpectedSARSA::new(q_func.clone(), Greedy::new(q_func), 1.0, 0.9)
+        };
+
+        let t = Transition {
+            from: Observation::Full(0usize),
+            action: 2,
+            reward: 1.5,
+            to: Observation::Full(1),
+        };
+
+        ql.handle(&t).unwrap();
+        es.handle(&t).unwrap();
+
+        assert_eq!(ql.q_func.evaluate((0, 2)), 1.5 + 0.9 * 3.0);
+        assert_eq!(ql.q_func.evaluate((0, 2)), es.q_func.evaluate((0, 2)));
+    }
+
+    #[test]
+    fn test_greedy_mode() {
+        let q_init = array![[0.5, -1.0, 2.0], [1.0, 3.0, -2.0]];
+        let agent = |greedy| {
+            let q_func = make_shared(Table::dense(q_init.clone()));
+            let policy = EpsilonGreedy::new(Greedy::new(q_func.clone()), Random::new(3), 0.5);
+
+            let mut agent = ExpectedSARSA::new(q_func, policy, 0.5, 0.9);
+
+            agent.set_greedy(greedy);
+            agent
+        };
+
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut explorer = agent(false);
+        let mut exploiter = agent(true);
+
+        let explored: HashSet<usize> = (0..100).map(|_| explorer.act(&mut rng, &0)).collect();
+
+        assert_eq!(explored.len(), 3);
+        assert!((0..100).all(|_| exploiter.act(&mut rng, &0) == 2));
+
+        let t = Transition {
+            from: Observation::Full(0usize),
+            action: 1,
+            reward: 1.5,
+            to: Observation::Full(1),
+        };
+
+        explorer.handle(&t).unwrap();
+        exploiter.handle(&t).unwrap();
+
+        // Both updates take the expectation under the ε-greedy policy:
+        // -1 + 0.5 (1.5 + 0.9 (1/6 + 2 - 1/3) + 1) = 1.075.
+        assert!((exploiter.q_func.evaluate((0, 1)) - 1.075).abs() < 1e-10);
+        assert_eq!(explorer.q_func.evaluate((0, 1)), exploiter.q_func.evaluate((0, 1)));
+
+        // Greedy mode can be switched off again.
+        exploiter.set_greedy(false);
+
+        let explored: HashSet<usize> = (0..100).map(|_| exploiter.act(&mut rng, &0)).collect();
+
+        assert_eq!(explored.len(), 3);
+    }
+}
@@ -1,16 +1,36 @@
 //! Temporal-difference control algorithms.
+use crate::domains::Transition;
+
+/// Transition paired with the action selected by the behaviour policy in the
+/// successor state, `a'`.
+///
+/// The `next_action` is ignored if the transition is terminal.
+#[derive(Clone, Copy, Debug)]
+pub struct OnPolicyTransition<'m, S, A> {
+    pub transition: &'m Transition<S, A>,
+    pub next_action: A,
+}
+
 // Off-policy:
+pub mod double_q_learning;
+pub mod dyna_q;
 pub mod greedy_gq;
 pub mod pal;
+pub mod peng_q_lambda;
+pub mod prioritized_sweeping;
 pub mod q_lambda;
 pub mod q_learning;
 pub mod q_sigma;
 
 pub use self::{
+    double_q_learning::DoubleQLearning,
+    dyna_q::{DynaQ, TabularModel},
     greedy_gq::GreedyGQ,
     pal::PAL,
 
-    q_lambda::QLambda,
+    peng_q_lambda::PengQLambda,
+    prioritized_sweeping::PrioritizedSweeping,
+    q_lambda::{QLambda, WatkinsQLambda},
     q_learning::QLearning,
     q_sigma::QSigma,
 };
@@ -22,5 +42,75 @@ pub mod sarsa_lambda;
 
 pub use self::{expected_sarsa::ExpectedSARSA, sarsa::SARSA, sarsa_lambda::SARSALambda};
 
+#[cfg(test)]
+pub(crate) mod test_utils {
+    use crate::{
+        fa::{tabular::Table, ScaledGradientUpdate},
+        params::{Buffer, Parameterised, WeightsView, WeightsViewMut},
+        Differentiable,
+        Enumerable,
+        Function,
+        Handler,
+    };
+    use ndarray::{Array2, Ix2};
+
+    /// Tabular action-value function with dense gradients.
+    #[derive(Clone, Debug)]
+    pub struct DenseQ(pub Table<Array2<f64>>);
+
+    impl Parameterised for DenseQ {
+        fn weights_view(&self) -> WeightsView<'_> { self.0.weights_view() }
+
+        fn weights_view_mut(&mut self) -> WeightsViewMut<'_> { self.0.weights_view_mut() }
+    }
+
+    impl<'s> Function<(&'s usize,)> for DenseQ {
+        type Output = Vec<f64>;
+
+        fn evaluate(&self, args: (&'s usize,)) -> Vec<f64> { self.0.evaluate(args) }
+    }
+
+    impl Enumerable<(&usize,)> for DenseQ {}
+
+    impl<'s> Function<(&'s usize, usize)> for DenseQ {
+        type Output = f64;
+
+        fn evaluate(&self, args: (&'s usize, usize)) -> f64 { self.0.evaluate(args) }
+    }
+
+    impl<'s> Differentiable<(&'s usize, usize)> for DenseQ {
+        type Jacobian = Array2<f64>;
+
+        fn grad(&self, args: (&'s usize, usize)) -> Array2<f64> { self.0.grad(args).into_dense() }
+
+        fn grad_log(&self, _: (&'s usize, usize)) -> Array2<f64> { unimplemented!() }
+    }
+
+    impl<'s, 'a> Function<(&'s usize, &'a usize)> for DenseQ {
+        type Output = f64;
+
+        fn evaluate(&self, args: (&'s usize, &'a usize)) -> f64 { self.0.evaluate(args) }
+    }
+
+    impl<'s, 'a> Differentiable<(&'s usize, &'a usize)> for DenseQ {
+        type Jacobian = Array2<f64>;
+
+        fn grad(&self, args: (&'s usize, &'a usize)) -> Array2<f64> {
+            self.0.grad(args).into_dense()
+        }
+
+        fn grad_log(&self, _: (&'s usize, &'a usize)) -> Array2<f64> { unimplemented!() }
+    }
+
+    impl<J: Buffer<Dim = Ix2>> Handler<ScaledGradientUpdate<J>> for DenseQ {
+        type Response = ();
+        type Error = ();
+
+        fn handle(&mut self, msg: ScaledGradientUpdate<J>) -> Result<(), ()> {
+            self.0.handle(msg).map(|_| ()).map_err(|_| ())
+        }
+    }
+}
+
 // TODO:
 // PQ(lambda) - http://proceedings.mlr.press/v32/sutton14.pdf
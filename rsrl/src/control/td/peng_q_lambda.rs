@@ -0,0 +1,211 @@
+use crate::{
+    domains::Transition,
+    fa::ScaledGradientUpdate,
+    traces,
+    Differentiable,
+    Enumerable,
+    Function,
+    Handler,
+    Parameterised,
+};
+use std::ops::Index;
+
+#[derive(Clone, Debug)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate")
+)]
+pub struct Response {
+    pub td_error: f64,
+}
+
+/// Peng's Q-learning with eligibility traces.
+///
+/// Unlike Watkins' variant (see [`QLambda`](super::QLambda)), the trace is
+/// never cut on exploratory actions. Past state-action pairs are instead
+/// updated towards the greedy value of the current state,
+/// `δ = r + γ max_a' Q(s', a') - max_a Q(s, a)`, while the pair just visited
+/// receives the usual one-step Q-learning error. The resulting target blends
+/// on- and off-policy returns; it is biased whenever exploration occurs, but
+/// typically has much lower variance than Watkins' Q(λ) since credit is
+/// propagated over longer sequences.
+///
+/// # References
+/// - Peng, J., Williams, R. J. (1996). Incremental multi-step Q-learning.
+///   Machine Learning, 22(1-3), 283–290.
+#[derive(Clone, Debug, Parameterised)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate")
+)]
+pub struct PengQLambda<F, T> {
+    #[weights]
+    pub fa_theta: F,
+    pub trace: T,
+
+    pub alpha: f64,
+    pub gamma: f64,
+}
+
+type Jac<S, A, Q> = <Q as Differentiable<(S, A)>>::Jacobian;
+type Tr<S, A, Q, R> = traces::Trace<Jac<S, A, Q>, R>;
+
+impl<'m, S, Q, R> Handler<&'m Transition<S, usize>> for PengQLambda<Q, Tr<&'m S, usize, Q, R>>
+where
+    Q: Enumerable<(&'m S,)> + Differentiable<(&'m S, usize)> +
+        for<'j> Handler<ScaledGradientUpdate<&'j Tr<&'m S, usize, Q, R>>> +
+        for<'j> Handler<ScaledGradientUpdate<&'j Jac<&'m S, usize, Q>>>,
+    R: traces::UpdateRule<Jac<&'m S, usize, Q>>,
+
+    <Q as Function<(&'m S,)>>::Output: Index<usize, Output = f64> + IntoIterator<Item = f64>,
+    <<Q as Function<(&'m S,)>>::Output as IntoIterator>::IntoIter: ExactSizeIterator,
+{
+    type Response = Response;
+    type Error = ();
+
+    fn handle(&mut self, t: &'m Transition<S, usize>) -> Result<Self::Response, Self::Error> {
+        let s = t.from.state();
+
+        let qs = self.fa_theta.evaluate((s,));
+        let qsa = qs[t.action];
+        let vs = qs.into_iter().fold(f64::MIN, f64::max);
+        let grad_s = self.fa_theta.grad((s, t.action));
+
+        // Update trace; never cut on exploratory actions:
+        self.trace.update(&grad_s);
+
+        let nvs = if t.terminated() {
+            0.0
+        } else {
+            self.fa_theta.find_max((t.to.state(),)).1
+        };
+
+        // Past pairs are updated with r + γV(s') - V(s), and the current pair
+        // with r + γV(s') - Q(s, a); since the trace already includes the
+        // current gradient, the latter is applied as a correction.
+        let td_error = t.reward + self.gamma * nvs - qsa;
+        let td_error_v = t.reward + self.gamma * nvs - vs;
+
+        self.fa_theta.handle(ScaledGradientUpdate {
+            alpha: self.alpha * td_error_v,
+            jacobian: &self.trace,
+        }).map_err(|_| ())?;
+
+        self.fa_theta.handle(ScaledGradientUpdate {
+            alpha: self.alpha * (td_error - td_error_v),
+            jacobian: &grad_s,
+        }).map_err(|_| ())?;
+
+        if t.terminated() { self.trace.reset(); }
+
+        Ok(Response { td_error, })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        control::td::{test_utils::DenseQ, QLambda},
+        domains::Observation,
+        fa::tabular::Table,
+        traces::{Accumulate, Trace},
+    };
+    use ndarray::Array2;
+
+    fn initial_q() -> DenseQ {
+        let mut q = Array2::zeros((2, 2));
+
+        q[[0, 0]] = 1.0;
+        q[[1, 0]] = 1.0;
+
+        DenseQ(Table::dense(q))
+    }
+
+    fn trace() -> Trace<Array2<f64>, Accumulate> {
+        Trace::new(Array2::zeros((2, 2)), Accumulate {
+            gamma: 0.9,
+            lambda: 0.8,
+        })
+    }
+
+    #[test]
+    fn test_trace_vs_watkins() {
+        let mut peng = PengQLambda {
+            fa_theta: initial_q(),
+            trace: trace(),
+
+            alpha: 0.1,
+            gamma: 0.9,
+        };
+        let mut watkins = QLambda {
+            fa_theta: initial_q(),
+            trace: trace(),
+
+            alpha: 0.1,
+            gamma: 0.9,
+        };
+
+        let t = |from, action, to| Transition {
+            from: Observation::Full(from),
+            action,
+            reward: 0.0,
+            to: Observation::Full(to),
+        };
+
+        // Greedy, greedy, exploratory, greedy.
+        let ts = [t(0usize, 0, 1), t(1, 0, 0), t(0, 1, 1), t(1, 0, 0)];
+
+        for (i, t) in ts.iter().enumerate() {
+            let e_peng = peng.trace.buffer.clone();
+
+            peng.handle(t).unwrap();
+            watkins.handle(t).unwrap();
+
+            let grad = peng.fa_theta.grad((t.from.state(), t.action));
+
+            // Peng's trace always decays and accumulates.
+            assert_eq!(peng.trace.buffer, e_peng * (0.9 * 0.8) + &grad);
+
+            if i < 2 {
+                assert_eq!(peng.trace.buffer, watkins.trace.buffer);
+            } else if i == 2 {
+                // Watkins' trace is cut by the exploratory action.
+                assert_eq!(watkins.trace.buffer, grad);
+                assert!(peng.trace.buffer[[0, 0]] > 0.0);
+                assert!(peng.trace.buffer[[1, 0]] > 0.0);
+            }
+        }
+
+        assert!(watkins.trace.buffer[[0, 0]] == 0.0);
+        assert!(peng.trace.buffer[[0, 0]] > 0.0);
+    }
+
+    #[test]
+    fn test_current_pair_uses_q_learning_error() {
+        let mut peng = PengQLambda {
+            fa_theta: initial_q(),
+            trace: trace(),
+
+            alpha: 0.5,
+            gamma: 0.9,
+        };
+
+        // Exploratory action with reward 1 into a terminal state.
+        let res = peng
+            .handle(&Transition {
+                from: Observation::Full(0usize),
+                action: 1,
+                reward: 1.0,
+                to: Observation::Terminal(1),
+            })
+            .unwrap();
+
+        assert_eq!(res.td_error, 1.0);
+        assert_eq!(peng.fa_theta.evaluate((&0, 1)), 0.5);
+        assert_eq!(peng.fa_theta.evaluate((&0, 0)), 1.0);
+        assert!(peng.trace.buffer.iter().all(|&e| e == 0.0));
+    }
+}
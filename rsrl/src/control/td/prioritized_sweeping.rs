@@ -0,0 +1,327 @@
+use crate::{
+    domains::{Model, Transition},
+    fa::StateActionUpdate,
+    spaces::{discrete::Ordinal, FiniteSpace},
+    Enumerable,
+    Handler,
+    Parameterised,
+};
+use std::{
+    cmp::Ordering,
+    collections::{BinaryHeap, HashMap},
+};
+
+#[derive(Clone, Debug)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate")
+)]
+pub struct Response {
+    /// Priority assigned to the observed state-action pair.
+    pub priority: f64,
+
+    /// Number of planning backups that were performed.
+    pub n_backups: usize,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Entry {
+    priority: f64,
+    state: usize,
+    action: usize,
+}
+
+impl Eq for Entry {}
+
+impl PartialOrd for Entry {
+    fn partial_cmp(&self, other: &Entry) -> Option<Ordering> { Some(self.cmp(other)) }
+}
+
+impl Ord for Entry {
+    fn cmp(&self, other: &Entry) -> Ordering {
+        self.priority.partial_cmp(&other.priority).unwrap_or(Ordering::Equal)
+    }
+}
+
+/// Prioritized sweeping over a known model of the domain.
+///
+/// Rather than replaying state-action pairs uniformly, as in
+/// [`DynaQ`](super::DynaQ), the agent maintains a priority queue of pairs
+/// ordered by the magnitude of their pending Bellman update,
+///
+/// `P(s, a) = |Σ_{s', r} p(s', r | s, a) [r + γ max_{a'} Q(s', a')] - Q(s, a)|`,
+///
+/// and always performs the most impactful backup first. After backing up
+/// `(s, a)`, every predecessor of `s` under the model is re-prioritised, such
+/// that changes in value propagate backwards from where they originate. Only
+/// pairs whose priority exceeds `theta` are queued.
+///
+/// Each real transition queues its state-action pair and is followed by up to
+/// `n_planning_steps` backups. The backups send the full Bellman error to the
+/// Q-function, so that tabular approximators perform exact expected updates.
+///
+/// # References
+/// - Moore, A. W., Atkeson, C. G. (1993). Prioritized sweeping: Reinforcement
+///   learning with less data and less time. Machine Learning, 13(1), 103–130.
+/// - Peng, J., Williams, R. J. (1993). Efficient learning and planning within
+///   the Dyna framework. Adaptive Behavior, 1(4), 437–454.
+#[derive(Clone, Debug, Parameterised)]
+pub struct PrioritizedSweeping<M, Q> {
+    #[weights]
+    pub q_func: Q,
+    pub model: M,
+
+    pub gamma: f64,
+
+    /// Minimum priority, `θ`, for a state-action pair to be queued.
+    pub theta: f64,
+    pub n_planning_steps: usize,
+
+    predecessors: Vec<Vec<(usize, usize)>>,
+    queue: BinaryHeap<Entry>,
+    priorities: HashMap<(usize, usize), f64>,
+}
+
+impl<M, Q> PrioritizedSweeping<M, Q>
+where M: Model<StateSpace = Ordinal, ActionSpace = Ordinal>
+{
+    pub fn new(q_func: Q, model: M, gamma: f64, theta: f64, n_planning_steps: usize) -> Self {
+        let mut predecessors = vec![vec![]; model.state_space().range().len()];
+
+        for s in model.state_space().range() {
+            for a in model.action_space().range() {
+                for (p, ns, _) in model.possible_transitions(&s, &a) {
+                    let preds = &mut predecessors[*ns.state()];
+
+                    if p > 0.0 && !preds.contains(&(s, a)) {
+                        preds.push((s, a));
+                    }
+                }
+            }
+        }
+
+        PrioritizedSweeping {
+            q_func,
+            model,
+
+            gamma,
+            theta,
+            n_planning_steps,
+
+            predecessors,
+            queue: BinaryHeap::new(),
+            priorities: HashMap::new(),
+        }
+    }
+
+    /// Return the number of state-action pairs awaiting a backup.
+    pub fn n_queued(&self) -> usize { self.priorities.len() }
+
+    /// Return the expected Bellman error of the pair `(s, a)` under the model.
+    pub fn bellman_error(&self, s: usize, a: usize) -> f64
+    where Q: for<'a> Enumerable<(&'a usize,), Output = Vec<f64>> {
+        let target: f64 = self
+            .model
+            .possible_transitions(&s, &a)
+            .into_iter()
+            .map(|(p, ns, r)| {
+                let nv = if ns.is_terminal() {
+                    0.0
+                } else {
+                    self.q_func.find_max((ns.state(),)).1
+                };
+
+                p * (r + self.gamma * nv)
+            })
+            .sum();
+
+        target - self.q_func.evaluate_index((&s,), a)
+    }
+
+    fn enqueue(&mut self, state: usize, action: usize, priority: f64) {
+        if priority <= self.theta {
+            return;
+        }
+
+        let current = self.priorities.entry((state, action)).or_insert(0.0);
+
+        if priority > *current {
+            *current = priority;
+
+            self.queue.push(Entry {
+                priority,
+                state,
+                action,
+            });
+        }
+    }
+
+    /// Perform up to `n` backups, in order of priority, returning the number
+    /// that were carried out before the queue was exhausted.
+    pub fn plan<E>(&mut self, n: usize) -> Result<usize, E>
+    where Q: for<'a> Enumerable<(&'a usize,), Output = Vec<f64>>
+            + for<'a> Handler<StateActionUpdate<&'a usize, usize, f64>, Error = E> {
+        let mut n_backups = 0;
+
+        while n_backups < n {
+            let entry = match self.queue.pop() {
+                Some(entry) => entry,
+                None => break,
+            };
+            let key = (entry.state, entry.action);
+
+            // Skip entries superseded by a later, higher priority:
+            if self.priorities.get(&key) != Some(&entry.priority) {
+                continue;
+            }
+
+            self.priorities.remove(&key);
+
+            let error = self.bellman_error(entry.state, entry.action);
+
+            self.q_func.handle(StateActionUpdate {
+                state: &entry.state,
+                action: entry.action,
+                error,
+            })?;
+
+            n_backups += 1;
+
+            for i in 0..self.predecessors[entry.state].len() {
+                let (ps, pa) = self.predecessors[entry.state][i];
+                let priority = self.bellman_error(ps, pa).abs();
+
+                self.enqueue(ps, pa, priority);
+            }
+        }
+
+        Ok(n_backups)
+    }
+}
+
+impl<'m, M, Q, E> Handler<&'m Transition<usize, usize>> for PrioritizedSweeping<M, Q>
+where
+    M: Model<StateSpace = Ordinal, ActionSpace = Ordinal>,
+    Q: for<'a> Enumerable<(&'a usize,), Output = Vec<f64>>
+        + for<'a> Handler<StateActionUpdate<&'a usize, usize, f64>, Error = E>,
+{
+    type Response = Response;
+    type Error = E;
+
+    fn handle(&mut self, t: &'m Transition<usize, usize>) -> Result<Response, E> {
+        let s = *t.from.state();
+        let priority = self.bellman_error(s, t.action).abs();
+
+        self.enqueue(s, t.action, priority);
+
+        let n_backups = self.plan(self.n_planning_steps)?;
+
+        Ok(Response {
+            priority,
+            n_backups,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        control::td::{DynaQ, TabularModel},
+        domains::{GridWorld, Observation},
+        fa::tabular::Table,
+        planning::value_iteration,
+        Function,
+    };
+    use ndarray::Array2;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    const N: usize = 10;
+
+    fn corridor() -> GridWorld { GridWorld::new(N, 1, [0, 0], &[[N - 1, 0]], 0.0) }
+
+    fn transitions(model: &GridWorld) -> Vec<Transition<usize, usize>> {
+        (0..N - 1)
+            .flat_map(|s| (0..4).map(move |a| (s, a)))
+            .map(|(s, a)| {
+                let (to, reward) = model.predict(&s, &a);
+
+                Transition {
+                    from: Observation::Full(s),
+                    action: a,
+                    reward,
+                    to,
+                }
+            })
+            .collect()
+    }
+
+    fn is_optimal<Q>(q: &Q, ts: &[Transition<usize, usize>]) -> bool
+    where Q: Function<(usize, usize), Output = f64> {
+        let values = value_iteration(&corridor(), 1.0, 1e-9, 10_000).values;
+
+        ts.iter().all(|t| {
+            let nv = if t.terminated() { 0.0 } else { values[*t.to.state()] };
+
+            q.evaluate((*t.from.state(), t.action)) == t.reward + nv
+        })
+    }
+
+    #[test]
+    fn test_converges_to_optimal() {
+        let model = corridor();
+        let ts = transitions(&model);
+        let mut agent =
+            PrioritizedSweeping::new(Table::dense(Array2::zeros((N, 4))), model, 1.0, 1e-9, 0);
+
+        for t in ts.iter() {
+            let res: Response = agent.handle(t).ok().unwrap();
+
+            assert_eq!(res.n_backups, 0);
+        }
+
+        assert!(agent.n_queued() > 0);
+
+        let n_backups: usize = agent.plan(usize::MAX).ok().unwrap();
+
+        assert!(n_backups > 0);
+        assert_eq!(agent.n_queued(), 0);
+        assert!(is_optimal(&agent.q_func, &ts));
+    }
+
+    #[test]
+    fn test_fewer_updates_than_dyna_q() {
+        let model = corridor();
+        let ts = transitions(&model);
+
+        let mut ps =
+            PrioritizedSweeping::new(Table::dense(Array2::zeros((N, 4))), model, 1.0, 1e-9, 0);
+
+        for t in ts.iter() {
+            ps.handle(t).ok().unwrap();
+        }
+
+        let ps_backups = ps.plan(usize::MAX).ok().unwrap();
+
+        // Dyna-Q is given the same complete model, and plans from it uniformly
+        // until its Q-function is also optimal.
+        let mut dyna =
+            DynaQ::new(Table::dense(Array2::zeros((N, 4))), TabularModel::new(N, 4), 1.0, 0)
+                .with_rng(StdRng::seed_from_u64(0));
+        let mut dyna_backups = 0;
+
+        for t in ts.iter() {
+            dyna.model.record(t);
+        }
+
+        while !is_optimal(&dyna.q_func, &ts) && dyna_backups < 100_000 {
+            dyna.plan(1).ok().unwrap();
+            dyna_backups += 1;
+        }
+
+        assert!(is_optimal(&ps.q_func, &ts));
+        assert!(is_optimal(&dyna.q_func, &ts));
+        assert!(ps_backups * 3 < dyna_backups, "{} vs {}", ps_backups, dyna_backups);
+    }
+}
@@ -23,6 +23,13 @@ pub struct Response {
 
 /// Watkins' Q-learning with eligibility traces.
 ///
+/// Since the target policy is greedy, the eligibility of past state-action
+/// pairs is only meaningful while the behaviour policy agrees with it. The
+/// trace is therefore cut, i.e. reset to zero, whenever the action taken is
+/// not greedy with respect to the current action-value estimates; greediness
+/// is determined from `fa_theta` itself, so no cooperation from the behaviour
+/// policy is required.
+///
 /// # References
 /// - Watkins, C. J. C. H. (1989). Learning from Delayed Rewards. Ph.D. thesis,
 /// Cambridge University.
@@ -43,6 +50,9 @@ pub struct QLambda<F, T> {
     pub gamma: f64,
 }
 
+/// Alias for [`QLambda`], which implements Watkins' variant of Q(λ).
+pub type WatkinsQLambda<F, T> = QLambda<F, T>;
+
 type Tr<S, A, Q, R> = traces::Trace<<Q as Differentiable<(S, A)>>::Jacobian, R>;
 
 impl<'m, S, Q, R> Handler<&'m Transition<S, usize>> for QLambda<Q, Tr<&'m S, usize, Q, R>>
@@ -97,3 +107,56 @@ where
         Ok(Response { td_error, })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        control::td::test_utils::DenseQ,
+        domains::Observation,
+        fa::tabular::Table,
+        traces::{Accumulate, Trace},
+    };
+    use ndarray::Array2;
+
+    #[test]
+    fn test_trace_cut_on_exploration() {
+        let mut q = Array2::zeros((2, 2));
+
+        q[[0, 0]] = 1.0;
+        q[[1, 0]] = 1.0;
+
+        let mut agent: WatkinsQLambda<_, _> = QLambda {
+            fa_theta: DenseQ(Table::dense(q)),
+            trace: Trace::new(Array2::zeros((2, 2)), Accumulate {
+                gamma: 0.9,
+                lambda: 0.8,
+            }),
+
+            alpha: 0.1,
+            gamma: 0.9,
+        };
+
+        let t = |from, action, to| Transition {
+            from: Observation::Full(from),
+            action,
+            reward: 0.0,
+            to: Observation::Full(to),
+        };
+
+        // Greedy actions accumulate eligibility.
+        agent.handle(&t(0usize, 0, 1)).unwrap();
+        agent.handle(&t(1, 0, 0)).unwrap();
+
+        assert!(agent.trace.buffer[[0, 0]] > 0.0);
+        assert!(agent.trace.buffer[[1, 0]] > 0.0);
+
+        // The exploratory action clears all prior eligibility, leaving only
+        // that of the pair just visited.
+        let grad = agent.fa_theta.grad((&0, 1));
+
+        agent.handle(&t(0, 1, 1)).unwrap();
+
+        assert_eq!(agent.trace.buffer, grad);
+    }
+}
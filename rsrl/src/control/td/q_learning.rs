@@ -1,10 +1,10 @@
 use crate::{
     domains::Transition,
-    fa::StateActionUpdate,
-    Enumerable,
+    utils::clip_error,
     Function,
     Handler,
     Parameterised,
+    QFunction,
 };
 use std::ops::Index;
 
@@ -37,11 +37,31 @@ pub struct QLearning<Q> {
     pub q_func: Q,
 
     pub gamma: f64,
+
+    /// Optional bound, `δ`, on the magnitude of the TD error used in updates.
+    pub error_clip: Option<f64>,
+}
+
+impl<Q> QLearning<Q> {
+    pub fn new(q_func: Q, gamma: f64) -> Self {
+        QLearning {
+            q_func,
+            gamma,
+            error_clip: None,
+        }
+    }
+
+    /// Clip the TD error to `[-delta, delta]` before updating the Q-function;
+    /// the reported `error` is left unclipped.
+    pub fn with_error_clip(mut self, delta: f64) -> Self {
+        self.error_clip = Some(delta);
+        self
+    }
 }
 
 impl<'m, S, Q> Handler<&'m Transition<S, usize>> for QLearning<Q>
 where
-    Q: Enumerable<(&'m S,)> + Handler<StateActionUpdate<&'m S, usize, f64>>,
+    Q: QFunction<&'m S>,
     <Q as Function<(&'m S,)>>::Output: Index<usize, Output = f64> + IntoIterator<Item = f64>,
     <<Q as Function<(&'m S,)>>::Output as IntoIterator>::IntoIter: ExactSizeIterator,
 {
@@ -56,17 +76,85 @@ where
             t.reward - qsa
         } else {
             let ns = t.to.state();
-            let (_, nqsna) = self.q_func.find_max((ns,));
+            let nqsna = self.q_func.max_value(ns);
 
             t.reward + self.gamma * nqsna - qsa
         };
 
         self.q_func
-            .handle(StateActionUpdate {
-                state,
-                action: t.action,
-                error,
-            })
+            .update(state, t.action, clip_error(error, self.error_clip))
             .map(|q_res| Response { q_res, error })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        domains::{Observation, Transition},
+        fa::tabular::Table,
+    };
+    use ndarray::Array2;
+
+    #[test]
+    fn test_two_state_chain() {
+        // s0 --(a0, r=0)--> s1 --(a0, r=1)--> terminal; a1 loops with r=0.
+        let mut ql = QLearning::new(Table::dense(Array2::zeros((2, 2))), 0.5);
+
+        let transitions = [
+            Transition {
+                from: Observation::Full(0usize),
+                action: 1,
+                reward: 0.0,
+                to: Observation::Full(0),
+            },
+            Transition {
+                from: Observation::Full(0),
+                action: 0,
+                reward: 0.0,
+                to: Observation::Full(1),
+            },
+            Transition {
+                from: Observation::Full(1),
+                action: 1,
+                reward: 0.0,
+                to: Observation::Full(1),
+            },
+            Transition {
+                from: Observation::Full(1),
+                action: 0,
+                reward: 1.0,
+                to: Observation::Terminal(1),
+            },
+        ];
+
+        for _ in 0..50 {
+            for t in transitions.iter() {
+                ql.handle(t).unwrap();
+            }
+        }
+
+        assert!((ql.q_func.evaluate((0, 0)) - 0.5).abs() < 1e-6);
+        assert!((ql.q_func.evaluate((0, 1)) - 0.25).abs() < 1e-6);
+        assert!((ql.q_func.evaluate((1, 0)) - 1.0).abs() < 1e-6);
+        assert!((ql.q_func.evaluate((1, 1)) - 0.5).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_error_clip() {
+        let mut ql = QLearning::new(Table::dense(Array2::zeros((1, 2))), 0.9).with_error_clip(0.5);
+
+        let t = |action, reward| Transition {
+            from: Observation::Full(0usize),
+            action,
+            reward,
+            to: Observation::Terminal(0),
+        };
+
+        assert_eq!(ql.handle(&t(0, 2.0)).unwrap().error, 2.0);
+        assert_eq!(ql.q_func.evaluate((0, 0)), 0.5);
+
+        assert_eq!(ql.handle(&t(1, 0.25)).unwrap().error, 0.25);
+        assert_eq!(ql.q_func.evaluate((0, 1)), 0.25);
+    }
+}
@@ -8,7 +8,7 @@ use crate::{
     Handler,
     Parameterised,
 };
-use rand::thread_rng;
+use rand::{rngs::StdRng, SeedableRng};
 use std::{collections::VecDeque, ops::Index};
 
 struct BackupEntry<S> {
@@ -86,6 +86,9 @@ pub struct QSigma<S, Q, P> {
     pub gamma: f64,
     pub sigma: f64,
 
+    /// Random number generator used to sample the next action, `a'`.
+    pub rng: StdRng,
+
     backup: Backup<S>,
 }
 
@@ -99,9 +102,18 @@ impl<S, Q, P> QSigma<S, Q, P> {
             gamma,
             sigma,
 
+            rng: StdRng::from_entropy(),
+
             backup: Backup::new(n_steps),
         }
     }
+
+    /// Sample the next action, `a'`, using the given `rng`, such that two
+    /// identically seeded agents make the same updates.
+    pub fn with_rng(mut self, rng: StdRng) -> Self {
+        self.rng = rng;
+        self
+    }
 }
 
 impl<S, Q, P> QSigma<S, Q, P> {
@@ -168,7 +180,7 @@ where
             res
         } else {
             let ns = t.to.state();
-            let na = self.policy.sample(&mut thread_rng(), ns);
+            let na = self.policy.sample(&mut self.rng, ns);
             let nqs = self.q_func.evaluate((ns,));
             let nqsna = nqs[na];
 
@@ -1,12 +1,13 @@
 use crate::{
+    control::td::OnPolicyTransition,
     domains::Transition,
     fa::StateActionUpdate,
-    policies::Policy,
+    policies::{Exploratory, Policy},
     Function,
     Handler,
     Parameterised,
 };
-use rand::thread_rng;
+use rand::{rngs::StdRng, Rng};
 
 #[derive(Clone, Debug)]
 #[cfg_attr(
@@ -38,9 +39,55 @@ pub struct SARSA<Q, P> {
     pub policy: P,
 
     pub gamma: f64,
+
+    /// If true, [`act`](SARSA::act) returns the mode of `policy`
+    /// instead of sampling from it; learning is unaffected.
+    pub greedy: bool,
+
+    /// Random number generator used to sample the next action, `a'`, when
+    /// handling a bare `Transition`.
+    #[cfg_attr(
+        feature = "serde",
+        serde(skip, default = "<StdRng as rand::SeedableRng>::from_entropy")
+    )]
+    pub rng: StdRng,
 }
 
-impl<'m, S, Q, P> Handler<&'m Transition<S, P::Action>> for SARSA<Q, P>
+impl<Q, P> SARSA<Q, P> {
+    /// Enable or disable greedy action selection in
+    /// [`act`](SARSA::act).
+    pub fn set_greedy(&mut self, greedy: bool) { self.greedy = greedy; }
+
+    /// Select an action in state `s`: the mode of the policy in greedy mode,
+    /// and a sample from it otherwise.
+    pub fn act<S, R>(&self, rng: &mut R, s: S) -> P::Action
+    where
+        P: Policy<S>,
+        R: Rng + ?Sized,
+    {
+        if self.greedy {
+            self.policy.mode(s)
+        } else {
+            self.policy.sample(rng, s)
+        }
+    }
+
+    /// Return a copy of the agent whose policy acts greedily, for evaluation.
+    ///
+    /// See [`Exploratory::clone_for_eval`] for how the weights are shared.
+    pub fn clone_for_eval(&self) -> Self
+    where
+        Self: Clone,
+        P: Exploratory,
+    {
+        SARSA {
+            policy: self.policy.clone_for_eval(),
+            ..self.clone()
+        }
+    }
+}
+
+impl<'m, S, Q, P> Handler<OnPolicyTransition<'m, S, P::Action>> for SARSA<Q, P>
 where
     Q: Function<(&'m S, P::Action), Output = f64>
         + for<'a> Function<(&'m S, &'a P::Action), Output = f64>
@@ -50,16 +97,18 @@ where
     type Response = Response<Q::Response>;
     type Error = Q::Error;
 
-    fn handle(&mut self, t: &'m Transition<S, P::Action>) -> Result<Self::Response, Self::Error> {
+    fn handle(
+        &mut self,
+        msg: OnPolicyTransition<'m, S, P::Action>,
+    ) -> Result<Self::Response, Self::Error> {
+        let t = msg.transition;
         let s = t.from.state();
         let qsa = self.q_func.evaluate((s, &t.action));
 
         let residual = if t.terminated() {
             t.reward - qsa
         } else {
-            let ns = t.to.state();
-            let na = self.policy.sample(&mut thread_rng(), ns);
-            let nqsna = self.q_func.evaluate((ns, na));
+            let nqsna = self.q_func.evaluate((t.to.state(), msg.next_action));
 
             t.reward + self.gamma * nqsna - qsa
         };
@@ -74,3 +123,121 @@ where
         })
     }
 }
+
+impl<'m, S, Q, P> Handler<&'m Transition<S, P::Action>> for SARSA<Q, P>
+where
+    Q: Function<(&'m S, P::Action), Output = f64>
+        + for<'a> Function<(&'m S, &'a P::Action), Output = f64>
+        + Handler<StateActionUpdate<&'m S, &'m P::Action>>,
+    P: Policy<&'m S>,
+{
+    type Response = Response<Q::Response>;
+    type Error = Q::Error;
+
+    fn handle(&mut self, t: &'m Transition<S, P::Action>) -> Result<Self::Response, Self::Error> {
+        let next_action = self.policy.sample(&mut self.rng, t.to.state());
+
+        self.handle(OnPolicyTransition {
+            transition: t,
+            next_action,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        domains::Observation,
+        fa::tabular::Table,
+        make_shared,
+        params::Parameterised,
+        policies::{EpsilonGreedy, Greedy, Random},
+    };
+    use ndarray::Array2;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn test_clone_for_eval() {
+        let q = make_shared(Table::dense(Array2::zeros((2, 3))));
+        let policy = EpsilonGreedy::new(Greedy::new(q.clone()), Random::new(3), 1.0);
+        let agent = SARSA {
+            q_func: q.clone(),
+            policy,
+
+            gamma: 0.9,
+            greedy: false,
+            rng: StdRng::seed_from_u64(0),
+        };
+
+        let set = |state: usize, action: usize, error: f64| {
+            q.borrow_mut()
+                .handle(StateActionUpdate {
+                    state,
+                    action,
+                    error,
+                })
+                .unwrap()
+        };
+
+        set(0, 2, 1.0);
+        set(1, 1, 1.0);
+
+        let eval = agent.clone_for_eval();
+        let mut rng = StdRng::seed_from_u64(0);
+
+        assert_eq!(agent.policy.epsilon.value(), 1.0);
+        assert_eq!(eval.policy.epsilon.value(), 0.0);
+
+        for _ in 0..100 {
+            assert_eq!(eval.policy.sample(&mut rng, &0), 2);
+            assert_eq!(eval.policy.sample(&mut rng, &1), 1);
+        }
+
+        // The learned weights are shared with the training agent.
+        set(0, 0, 2.0);
+
+        assert_eq!(eval.policy.sample(&mut rng, &0), 0);
+    }
+
+    #[test]
+    fn test_seeded_updates() {
+        let agent = |seed| {
+            let q = make_shared(Table::dense(Array2::zeros((2, 3))));
+            let policy = EpsilonGreedy::new(Greedy::new(q.clone()), Random::new(3), 0.5);
+
+            SARSA {
+                q_func: q,
+                policy,
+
+                gamma: 0.9,
+                greedy: false,
+                rng: StdRng::seed_from_u64(seed),
+            }
+        };
+        let transitions: Vec<_> = (0..100)
+            .map(|i| Transition {
+                from: Observation::Full(i % 2),
+                action: i % 3,
+                reward: (i % 5) as f64,
+                to: Observation::Full((i + 1) % 2),
+            })
+            .collect();
+
+        let mut a1 = agent(0);
+        let mut a2 = agent(0);
+        let mut rng1 = StdRng::seed_from_u64(1);
+        let mut rng2 = StdRng::seed_from_u64(1);
+
+        // The next action of each update is sampled from the agent's own rng,
+        // so identically seeded agents learn, and hence act, identically.
+        for t in transitions.iter() {
+            a1.handle(t).unwrap();
+            a2.handle(t).unwrap();
+
+            assert_eq!(a1.act(&mut rng1, t.from.state()), a2.act(&mut rng2, t.from.state()));
+        }
+
+        assert_eq!(a1.weights(), a2.weights());
+    }
+}
@@ -1,14 +1,16 @@
 use crate::{
+    control::td::OnPolicyTransition,
     domains::Transition,
     fa::ScaledGradientUpdate,
-    policies::Policy,
+    policies::{Exploratory, Policy},
     traces,
+    utils::clip_grad_norm,
     Differentiable,
     Function,
     Handler,
     Parameterised,
 };
-use rand::thread_rng;
+use rand::{rngs::StdRng, Rng};
 
 #[derive(Clone, Debug)]
 #[cfg_attr(
@@ -42,12 +44,67 @@ pub struct SARSALambda<Q, P, T> {
 
     pub alpha: f64,
     pub gamma: f64,
+
+    /// Optional bound on the L2 norm of each weight update.
+    pub grad_clip_norm: Option<f64>,
+
+    /// If true, [`act`](SARSALambda::act) returns the mode of `policy`
+    /// instead of sampling from it; learning is unaffected.
+    pub greedy: bool,
+
+    /// Random number generator used to sample the next action, `a'`, when
+    /// handling a bare `Transition`.
+    #[cfg_attr(
+        feature = "serde",
+        serde(skip, default = "<StdRng as rand::SeedableRng>::from_entropy")
+    )]
+    pub rng: StdRng,
 }
 
-type Tr<S, A, Q, R> = traces::Trace<<Q as Differentiable<(S, A)>>::Jacobian, R>;
+impl<Q, P, T> SARSALambda<Q, P, T> {
+    /// Rescale each weight update, `α δ e`, such that its global L2 norm is at
+    /// most `max_norm`; the reported `td_error` is left unscaled.
+    pub fn with_grad_clip_norm(mut self, max_norm: f64) -> Self {
+        self.grad_clip_norm = Some(max_norm);
+        self
+    }
 
+    /// Enable or disable greedy action selection in
+    /// [`act`](SARSALambda::act).
+    pub fn set_greedy(&mut self, greedy: bool) { self.greedy = greedy; }
 
-impl<'m, S, Q, P, R> Handler<&'m Transition<S, P::Action>> for SARSALambda<
+    /// Select an action in state `s`: the mode of the policy in greedy mode,
+    /// and a sample from it otherwise.
+    pub fn act<S, R>(&self, rng: &mut R, s: S) -> P::Action
+    where
+        P: Policy<S>,
+        R: Rng + ?Sized,
+    {
+        if self.greedy {
+            self.policy.mode(s)
+        } else {
+            self.policy.sample(rng, s)
+        }
+    }
+
+    /// Return a copy of the agent whose policy acts greedily, for evaluation.
+    ///
+    /// See [`Exploratory::clone_for_eval`] for how the weights are shared.
+    pub fn clone_for_eval(&self) -> Self
+    where
+        Self: Clone,
+        P: Exploratory,
+    {
+        SARSALambda {
+            policy: self.policy.clone_for_eval(),
+            ..self.clone()
+        }
+    }
+}
+
+type Tr<S, A, Q, R> = traces::Trace<<Q as Differentiable<(S, A)>>::Jacobian, R>;
+
+impl<'m, S, Q, P, R> Handler<OnPolicyTransition<'m, S, P::Action>> for SARSALambda<
     Q, P, Tr<&'m S, &'m P::Action, Q, R>
 >
 where
@@ -60,7 +117,11 @@ where
     type Response = Response;
     type Error = ();
 
-    fn handle(&mut self, t: &'m Transition<S, P::Action>) -> Result<Self::Response, Self::Error> {
+    fn handle(
+        &mut self,
+        msg: OnPolicyTransition<'m, S, P::Action>,
+    ) -> Result<Self::Response, Self::Error> {
+        let t = msg.transition;
         let s = t.from.state();
         let qsa = self.fa_theta.evaluate((s, &t.action));
 
@@ -72,7 +133,7 @@ where
             let residual = t.reward - qsa;
 
             self.fa_theta.handle(ScaledGradientUpdate {
-                alpha: self.alpha * residual,
+                alpha: clip_grad_norm(self.alpha * residual, &self.trace, self.grad_clip_norm),
                 jacobian: &self.trace,
             }).map_err(|_| ())?;
             self.trace.reset();
@@ -80,13 +141,12 @@ where
             residual
         } else {
             let ns = t.to.state();
-            let na = self.policy.sample(&mut thread_rng(), ns);
-            let nqsna = self.fa_theta.evaluate((ns, na));
+            let nqsna = self.fa_theta.evaluate((ns, msg.next_action));
 
             let residual = t.reward + self.gamma * nqsna - qsa;
 
             self.fa_theta.handle(ScaledGradientUpdate {
-                alpha: self.alpha * residual,
+                alpha: clip_grad_norm(self.alpha * residual, &self.trace, self.grad_clip_norm),
                 jacobian: &self.trace,
             }).map_err(|_| ())?;
 
@@ -96,3 +156,92 @@ where
         Ok(Response { td_error, })
     }
 }
+
+impl<'m, S, Q, P, R> Handler<&'m Transition<S, P::Action>> for SARSALambda<
+    Q, P, Tr<&'m S, &'m P::Action, Q, R>
+>
+where
+    Q: Function<(&'m S, P::Action), Output = f64> +
+        Differentiable<(&'m S, &'m P::Action), Output = f64> +
+        for<'j> Handler<ScaledGradientUpdate<&'j Tr<&'m S, &'m P::Action, Q, R>>>,
+    P: Policy<&'m S>,
+    R: traces::UpdateRule<<Q as Differentiable<(&'m S, &'m P::Action)>>::Jacobian>,
+{
+    type Response = Response;
+    type Error = ();
+
+    fn handle(&mut self, t: &'m Transition<S, P::Action>) -> Result<Self::Response, Self::Error> {
+        let next_action = self.policy.sample(&mut self.rng, t.to.state());
+
+        self.handle(OnPolicyTransition {
+            transition: t,
+            next_action,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        control::td::test_utils::DenseQ,
+        domains::{Discretize, Domain, MountainCar},
+        fa::tabular::Table,
+        make_shared,
+        policies::{EpsilonGreedy, Greedy, Random},
+        traces::Trace,
+    };
+    use ndarray::Array2;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_mountain_car_episodes_shorten() {
+        let mut rng = StdRng::seed_from_u64(0);
+
+        let q = make_shared(DenseQ(Table::dense(Array2::zeros((16 * 16, 3)))));
+        let policy = EpsilonGreedy::new(Greedy::new(q.clone()), Random::new(3), 0.01);
+
+        let mut agent = SARSALambda {
+            fa_theta: q,
+            policy,
+            trace: Trace::replacing((16 * 16, 3), 1.0, 0.9),
+
+            alpha: 0.1,
+            gamma: 1.0,
+
+            grad_clip_norm: None,
+            greedy: false,
+            rng: StdRng::seed_from_u64(1),
+        };
+
+        let episode_lengths: Vec<usize> = (0..100)
+            .map(|_| {
+                let mut domain = Discretize::uniform(MountainCar::default(), 16);
+                let mut action = agent.act(&mut rng, domain.emit().state());
+
+                for i in 1..=5000 {
+                    let t = domain.transition(action);
+
+                    action = agent.act(&mut rng, t.to.state());
+                    agent
+                        .handle(OnPolicyTransition {
+                            transition: &t,
+                            next_action: action,
+                        })
+                        .unwrap();
+
+                    if t.terminated() {
+                        return i;
+                    }
+                }
+
+                5000
+            })
+            .collect();
+
+        let first: usize = episode_lengths[..10].iter().sum();
+        let last: usize = episode_lengths[90..].iter().sum();
+
+        assert!(2 * last < first, "{:?}", episode_lengths);
+    }
+}
@@ -8,7 +8,7 @@ use crate::{
     traces::Trace,
 };
 use ndarray::{Array1, Ix1, linalg::Dot};
-use rand::thread_rng;
+use rand::{rngs::StdRng, SeedableRng};
 use std::f64;
 
 /// True online variant of the SARSA(lambda) algorithm.
@@ -29,6 +29,9 @@ pub struct TOSARSALambda<B: Space, P, T> {
     pub gamma: f64,
     pub lambda: f64,
 
+    /// Random number generator used to sample the next action, `a'`.
+    pub rng: StdRng,
+
     q_old: f64,
 }
 
@@ -57,10 +60,19 @@ where
             gamma: gamma.into(),
             lambda: lambda.into(),
 
+            rng: StdRng::from_entropy(),
+
             q_old: 0.0,
         }
     }
 
+    /// Sample the next action, `a'`, using the given `rng`, such that two
+    /// identically seeded agents make the same updates.
+    pub fn with_rng(mut self, rng: StdRng) -> Self {
+        self.rng = rng;
+        self
+    }
+
     pub fn zeros(
         basis: B,
         policy: P,
@@ -121,7 +133,7 @@ where
             self.trace.reset();
         } else {
             let ns = t.to.state();
-            let na = self.policy.sample(&mut thread_rng(), ns);
+            let na = self.policy.sample(&mut self.rng, ns);
 
             let phi_ns_na = self.basis.project((ns, na)).unwrap();
             let qnsna = phi_ns_na.dot(&self.theta);
@@ -1,3 +1,4 @@
+use crate::{fa::StateActionUpdate, utils::argmax_first};
 use std::{
     cell::{Ref, RefCell, RefMut},
     fmt,
@@ -5,6 +6,9 @@ use std::{
     rc::Rc,
 };
 
+#[cfg(feature = "json")]
+use std::{fs::File, io, path::Path};
+
 #[macro_export]
 macro_rules! shared {
     ($id:expr) => { make_shared($id) }
@@ -135,6 +139,73 @@ where
 {
 }
 
+/// Action-value function, `Q(s, ·)`, over a finite set of actions.
+///
+/// This trait is implemented automatically for every enumerable function of
+/// the state that accepts `StateActionUpdate` messages with `usize` actions,
+/// such as tabular and linear (e.g. [`SimpleQLFA`](crate::fa::SimpleQLFA))
+/// approximators, and is the interface on which value-based control agents
+/// are built.
+pub trait QFunction<S>: Enumerable<(S,)> + Handler<StateActionUpdate<S, usize>>
+where
+    Self::Output: Index<usize, Output = f64> + IntoIterator<Item = f64>,
+    <Self::Output as IntoIterator>::IntoIter: ExactSizeIterator,
+{
+    /// Return the value of each action in state `s`.
+    fn action_values(&self, s: S) -> Vec<f64> { self.evaluate((s,)).into_iter().collect() }
+
+    /// Return the action with the highest value in state `s`; ties are broken
+    /// in favour of the lowest index.
+    fn greedy_action(&self, s: S) -> usize { argmax_first(self.evaluate((s,))).0 }
+
+    /// Return the highest action value in state `s`.
+    fn max_value(&self, s: S) -> f64 { argmax_first(self.evaluate((s,))).1 }
+
+    /// Return the action with the highest value in state `s` among the
+    /// `legal` actions (e.g. those left unmasked by the domain); ties are
+    /// broken in favour of the action listed first.
+    ///
+    /// # Panics
+    /// If `legal` is empty.
+    fn greedy_action_among(&self, s: S, legal: &[usize]) -> usize {
+        assert!(!legal.is_empty(), "At least one legal action is required.");
+
+        let values = self.evaluate((s,));
+
+        legal[argmax_first(legal.iter().map(|&a| values[a])).0]
+    }
+
+    /// Return the highest action value in state `s` among the `legal` actions.
+    ///
+    /// # Panics
+    /// If `legal` is empty.
+    fn max_value_among(&self, s: S, legal: &[usize]) -> f64 {
+        assert!(!legal.is_empty(), "At least one legal action is required.");
+
+        let values = self.evaluate((s,));
+
+        argmax_first(legal.iter().map(|&a| values[a])).1
+    }
+
+    /// Move the value of action `a` in state `s` by the (unscaled) error
+    /// `delta`.
+    fn update(&mut self, s: S, a: usize, delta: f64) -> Result<Self::Response, Self::Error> {
+        self.handle(StateActionUpdate {
+            state: s,
+            action: a,
+            error: delta,
+        })
+    }
+}
+
+impl<S, Q> QFunction<S> for Q
+where
+    Q: Enumerable<(S,)> + Handler<StateActionUpdate<S, usize>>,
+    Q::Output: Index<usize, Output = f64> + IntoIterator<Item = f64>,
+    <Q::Output as IntoIterator>::IntoIter: ExactSizeIterator,
+{
+}
+
 pub trait Differentiable<Args>: Function<Args> + crate::params::Parameterised {
     type Jacobian: crate::params::BufferMut;
 
@@ -176,3 +247,118 @@ impl<M: Message, T: Handler<M>> Handler<M> for Shared<T> {
         self.borrow_mut().handle_unchecked(msg)
     }
 }
+
+/// Serialise a value (e.g. a trained agent) to a JSON file at `path`.
+#[cfg(feature = "json")]
+pub fn save_to_file<T, P>(value: &T, path: P) -> io::Result<()>
+where
+    T: serde_crate::Serialize,
+    P: AsRef<Path>,
+{
+    let file = File::create(path)?;
+
+    serde_json::to_writer(io::BufWriter::new(file), value).map_err(io::Error::from)
+}
+
+/// Deserialise a value (e.g. a trained agent) from a JSON file at `path`.
+#[cfg(feature = "json")]
+pub fn load_from_file<T, P>(path: P) -> io::Result<T>
+where
+    T: serde_crate::de::DeserializeOwned,
+    P: AsRef<Path>,
+{
+    let file = File::open(path)?;
+
+    serde_json::from_reader(io::BufReader::new(file)).map_err(io::Error::from)
+}
+
+#[cfg(all(test, feature = "json"))]
+mod tests {
+    use super::{load_from_file, save_to_file};
+    use crate::{
+        domains::{Observation, Transition},
+        fa::tabular::Table,
+        params::Vector,
+        prediction::td::{TDLambda, TD},
+        traces::{Accumulate, Trace},
+        Function,
+        Handler,
+    };
+    use ndarray::Array1;
+    use std::{
+        path::PathBuf,
+        time::{SystemTime, UNIX_EPOCH},
+    };
+
+    fn temp_path(name: &str) -> PathBuf {
+        let nanos = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_nanos();
+
+        std::env::temp_dir().join(format!("rsrl_{}_{}_{}.json", name, std::process::id(), nanos))
+    }
+
+    // Evaluating the dense branch of `Features::dot` requires a BLAS implementation.
+    #[cfg(feature = "blas")]
+    #[test]
+    fn test_trained_round_trip() {
+        use crate::fa::linear::{basis::Fourier, optim::SGD, ScalarLFA};
+
+        let basis = Fourier::new(3, vec![(0.0, 1.0)]);
+        let mut agent = TD::new(ScalarLFA::scalar(basis, SGD(0.1)), 0.9);
+
+        for _ in 0..10 {
+            agent.handle_unchecked(&Transition {
+                from: Observation::Full(vec![0.2]),
+                action: 0usize,
+                reward: 0.3,
+                to: Observation::Full(vec![0.6]),
+            });
+            agent.handle_unchecked(&Transition {
+                from: Observation::Full(vec![0.6]),
+                action: 0usize,
+                reward: 1.7,
+                to: Observation::Terminal(vec![1.0]),
+            });
+        }
+
+        let path = temp_path("trained_round_trip");
+
+        save_to_file(&agent, &path).unwrap();
+
+        let loaded: TD<ScalarLFA<Fourier, SGD>> = load_from_file(&path).unwrap();
+
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(agent.v_func.weights, loaded.v_func.weights);
+
+        for s in [0.0, 0.2, 0.45, 0.6, 1.0].iter() {
+            let s = vec![*s];
+
+            assert_eq!(agent.v_func.evaluate(&s).unwrap(), loaded.v_func.evaluate(&s).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_traces_round_trip() {
+        let mut agent = TDLambda {
+            fa_theta: Table::dense(Array1::zeros(2)),
+            trace: Trace::accumulating(2, 0.9, 0.7),
+            gamma: 0.9.into(),
+            grad_clip_norm: None,
+        };
+
+        agent.trace.update(&Vector::from(vec![0.25, 1.0 / 3.0]));
+
+        let path = temp_path("traces_round_trip");
+
+        save_to_file(&agent, &path).unwrap();
+
+        let loaded: TDLambda<Table<Vector>, Trace<Vector, Accumulate>> =
+            load_from_file(&path).unwrap();
+
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(agent.fa_theta.evaluate((0usize,)), loaded.fa_theta.evaluate((0usize,)));
+        assert_eq!(agent.trace.buffer, loaded.trace.buffer);
+        assert_eq!(agent.trace.update_rule.lambda, loaded.trace.update_rule.lambda);
+    }
+}
@@ -0,0 +1,502 @@
+//! Training loop utilities.
+use crate::{
+    domains::{Action, Domain, State, Transition},
+    logging::{EpisodeStats, Statistics},
+    policies::{EnumerablePolicy, Policy},
+    prediction::td::WeightedTransition,
+    Function,
+    Handler,
+};
+use rand::Rng;
+use std::ops::Index;
+
+/// Train an agent on a sequence of episodes of a domain.
+///
+/// Each episode begins by resetting `domain`, and proceeds by sampling actions
+/// from `policy` and passing each resulting transition to `agent`. An episode
+/// ends when a terminal state is reached or after `max_steps` steps, whichever
+/// is sooner.
+///
+/// Returns the statistics of each episode, or the first error returned by
+/// `agent`, at which point training stops.
+///
+/// # Arguments
+///
+/// * `domain` - Domain instance, reset at the start of each episode.
+/// * `agent` - Learning agent that handles each transition.
+/// * `policy` - Behaviour policy used to select actions.
+/// * `rng` - Random number generator used to sample from `policy`.
+/// * `n_episodes` - Number of episodes to run.
+/// * `max_steps` - Maximum number of steps per episode.
+pub fn run_episodes<D, H, P, R, E>(
+    domain: &mut D,
+    agent: &mut H,
+    policy: &P,
+    rng: &mut R,
+    n_episodes: usize,
+    max_steps: usize,
+) -> Result<Vec<EpisodeStats>, E>
+where
+    D: Domain,
+    H: for<'t> Handler<&'t Transition<State<D>, Action<D>>, Error = E>,
+    P: for<'s> Policy<&'s State<D>, Action = Action<D>>,
+    R: Rng + ?Sized,
+{
+    (0..n_episodes)
+        .map(|episode| run_episode(domain, agent, policy, rng, episode, max_steps))
+        .collect()
+}
+
+fn run_episode<D, H, P, R, E>(
+    domain: &mut D,
+    agent: &mut H,
+    policy: &P,
+    rng: &mut R,
+    episode: usize,
+    max_steps: usize,
+) -> Result<EpisodeStats, E>
+where
+    D: Domain,
+    H: for<'t> Handler<&'t Transition<State<D>, Action<D>>, Error = E>,
+    P: for<'s> Policy<&'s State<D>, Action = Action<D>>,
+    R: Rng + ?Sized,
+{
+    domain.reset();
+
+    let mut stats = EpisodeStats {
+        episode,
+        total_reward: 0.0,
+        steps: 0,
+    };
+
+    while stats.steps < max_steps {
+        let action = policy.sample(rng, domain.emit().state());
+        let t = domain.transition(action);
+
+        agent.handle(&t)?;
+
+        stats.steps += 1;
+        stats.total_reward += t.reward;
+
+        if t.terminated() {
+            break;
+        }
+    }
+
+    Ok(stats)
+}
+
+/// Criterion used to stop training early once learning has converged.
+pub trait Convergence {
+    /// Return true if training has converged, given the statistics of every
+    /// episode run so far, in order.
+    fn has_converged(&self, history: &[EpisodeStats]) -> bool;
+}
+
+impl<F: Fn(&[EpisodeStats]) -> bool> Convergence for F {
+    fn has_converged(&self, history: &[EpisodeStats]) -> bool { self(history) }
+}
+
+/// Convergence criterion on the moving average of the episode returns.
+///
+/// Training is deemed to have converged once at least `window` episodes have
+/// been run and the mean total reward over the last `window` of them is at
+/// least `threshold`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate")
+)]
+pub struct MovingAverage {
+    pub window: usize,
+    pub threshold: f64,
+}
+
+impl MovingAverage {
+    pub fn new(window: usize, threshold: f64) -> Self {
+        assert!(window > 0, "Window must contain at least one episode.");
+
+        MovingAverage { window, threshold }
+    }
+}
+
+impl Convergence for MovingAverage {
+    fn has_converged(&self, history: &[EpisodeStats]) -> bool {
+        if history.len() < self.window {
+            return false;
+        }
+
+        let recent = &history[history.len() - self.window..];
+        let mean = recent.iter().map(|s| s.total_reward).sum::<f64>() / self.window as f64;
+
+        mean >= self.threshold
+    }
+}
+
+/// Outcome of [`run_until_converged`].
+#[derive(Clone, Debug)]
+pub struct Run {
+    /// Statistics of every episode that was run.
+    pub stats: Vec<EpisodeStats>,
+
+    /// Index of the episode after which the criterion was first satisfied, if
+    /// at all.
+    pub converged_at: Option<usize>,
+}
+
+impl Run {
+    /// Summarise the episodes that were run.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no episodes were run.
+    pub fn statistics(&self) -> Statistics { Statistics::from_episodes(&self.stats) }
+}
+
+/// Train an agent as in [`run_episodes`], stopping early once `criterion` is
+/// satisfied.
+///
+/// The criterion is checked after every episode against the statistics of all
+/// episodes so far. If it is never satisfied, training stops after
+/// `max_episodes` episodes and `converged_at` is `None`. As in
+/// [`run_episodes`], the first error returned by `agent` stops training.
+pub fn run_until_converged<D, H, P, R, C, E>(
+    domain: &mut D,
+    agent: &mut H,
+    policy: &P,
+    rng: &mut R,
+    max_episodes: usize,
+    max_steps: usize,
+    criterion: &C,
+) -> Result<Run, E>
+where
+    D: Domain,
+    H: for<'t> Handler<&'t Transition<State<D>, Action<D>>, Error = E>,
+    P: for<'s> Policy<&'s State<D>, Action = Action<D>>,
+    R: Rng + ?Sized,
+    C: Convergence + ?Sized,
+{
+    let mut stats = Vec::with_capacity(max_episodes);
+
+    for episode in 0..max_episodes {
+        stats.push(run_episode(domain, agent, policy, rng, episode, max_steps)?);
+
+        if criterion.has_converged(&stats) {
+            return Ok(Run {
+                stats,
+                converged_at: Some(episode),
+            });
+        }
+    }
+
+    Ok(Run {
+        stats,
+        converged_at: None,
+    })
+}
+
+/// Train an agent offline on a dataset of recorded transitions.
+///
+/// Each transition is passed to `agent` in order, exactly as it would have
+/// been during online training, but without interacting with a domain. This
+/// allows for batch learning from logged data, e.g. trajectories collected
+/// with [`Trajectory::record`](crate::domains::Trajectory::record) and
+/// converted with `into_batch`.
+///
+/// Returns the number of transitions that were replayed, or the first error
+/// returned by `agent`, at which point replay stops.
+pub fn learn_offline<'m, S: 'm, A: 'm, H, I>(
+    agent: &mut H,
+    transitions: I,
+) -> Result<usize, H::Error>
+where
+    H: Handler<&'m Transition<S, A>>,
+    I: IntoIterator<Item = &'m Transition<S, A>>,
+{
+    transitions.into_iter().try_fold(0, |n, t| {
+        agent.handle(t)?;
+
+        Ok(n + 1)
+    })
+}
+
+/// Train an off-policy agent offline on a dataset of recorded transitions.
+///
+/// This is the counterpart of [`learn_offline`] for data generated by a
+/// `behaviour` policy that differs from the `target` policy being learned
+/// about: each transition is paired with its importance sampling ratio,
+/// `π(a|s) / b(a|s)`, before being passed to `agent`. Transitions whose action
+/// has zero probability under `behaviour` are skipped.
+///
+/// Returns the number of transitions that were replayed, or the first error
+/// returned by `agent`, at which point replay stops.
+pub fn learn_offline_weighted<'m, S: 'm, H, I, P, B>(
+    agent: &mut H,
+    transitions: I,
+    target: &P,
+    behaviour: &B,
+) -> Result<usize, H::Error>
+where
+    H: Handler<WeightedTransition<'m, S, usize>>,
+    I: IntoIterator<Item = &'m Transition<S, usize>>,
+    P: EnumerablePolicy<&'m S>,
+    B: EnumerablePolicy<&'m S>,
+
+    <P as Function<(&'m S,)>>::Output: Index<usize, Output = f64> + IntoIterator<Item = f64>,
+    <<P as Function<(&'m S,)>>::Output as IntoIterator>::IntoIter: ExactSizeIterator,
+
+    <B as Function<(&'m S,)>>::Output: Index<usize, Output = f64> + IntoIterator<Item = f64>,
+    <<B as Function<(&'m S,)>>::Output as IntoIterator>::IntoIter: ExactSizeIterator,
+{
+    transitions.into_iter().try_fold(0, |n, t| {
+        match WeightedTransition::from_policies(t, target, behaviour) {
+            Some(msg) => {
+                agent.handle(msg)?;
+
+                Ok(n + 1)
+            },
+            None => Ok(n),
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        control::td::QLearning,
+        domains::{GridWorld, Observation, Trajectory},
+        fa::{mocking::MockQ, tabular::Table},
+        make_shared,
+        policies::{Greedy, Random},
+        prediction::td::TD,
+        Function,
+    };
+    use ndarray::{Array1, Array2};
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn test_td_grid_world() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut agent = TD::new(Table::dense(Array1::zeros(25)), 0.9);
+
+        let mut domain = GridWorld::default();
+        let policy = Random::new(4);
+        let stats = run_episodes(&mut domain, &mut agent, &policy, &mut rng, 5, 1000).unwrap();
+
+        assert_eq!(stats.len(), 5);
+
+        for (i, s) in stats.iter().enumerate() {
+            assert_eq!(s.episode, i);
+            assert!(s.steps > 0 && s.steps <= 1000);
+        }
+
+        assert!(agent.v_func.evaluate((0usize,)) < 0.0);
+    }
+
+    #[test]
+    fn test_max_steps() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut agent = TD::new(Table::dense(Array1::zeros(25)), 0.9);
+
+        let mut domain = GridWorld::default();
+        let policy = Random::new(4);
+        let stats = run_episodes(&mut domain, &mut agent, &policy, &mut rng, 3, 2).unwrap();
+
+        assert!(stats.iter().all(|s| s.steps == 2 && s.total_reward == -2.0));
+    }
+
+    #[test]
+    fn test_stops_on_error() {
+        // Fails on the fifth transition it is given.
+        struct Failing(usize);
+
+        impl<'m> Handler<&'m Transition<usize, usize>> for Failing {
+            type Response = ();
+            type Error = usize;
+
+            fn handle(&mut self, _: &'m Transition<usize, usize>) -> Result<(), usize> {
+                self.0 += 1;
+
+                if self.0 == 5 { Err(self.0) } else { Ok(()) }
+            }
+        }
+
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut agent = Failing(0);
+
+        let mut domain = GridWorld::default();
+        let policy = Random::new(4);
+
+        assert_eq!(run_episodes(&mut domain, &mut agent, &policy, &mut rng, 3, 2), Err(5));
+        assert_eq!(agent.0, 5);
+    }
+
+    #[test]
+    fn test_moving_average() {
+        let history: Vec<EpisodeStats> = [-5.0, -3.0, -1.0, -1.0]
+            .iter()
+            .enumerate()
+            .map(|(episode, &total_reward)| EpisodeStats {
+                episode,
+                total_reward,
+                steps: 1,
+            })
+            .collect();
+
+        let criterion = MovingAverage::new(3, -2.0);
+
+        assert!(!criterion.has_converged(&history[..2]));
+        assert!(!criterion.has_converged(&history[..3]));
+        assert!(criterion.has_converged(&history));
+    }
+
+    #[test]
+    fn test_early_stopping() {
+        let mut rng = StdRng::seed_from_u64(0);
+
+        // Three cell corridor: the optimal return is -1.
+        let mut domain = GridWorld::new(3, 1, [0, 0], &[[2, 0]], 0.0);
+        let q_func = make_shared(Table::dense(Array2::zeros((3, 4))));
+        let mut agent = QLearning::new(q_func.clone(), 1.0);
+        let policy = Greedy::new(q_func);
+
+        let run = run_until_converged(
+            &mut domain,
+            &mut agent,
+            &policy,
+            &mut rng,
+            1000,
+            100,
+            &MovingAverage::new(5, -1.0),
+        )
+        .unwrap();
+        let converged_at = run.converged_at.unwrap();
+
+        assert!(converged_at < 50);
+        assert_eq!(run.stats.len(), converged_at + 1);
+        assert!(run.stats.iter().rev().take(5).all(|s| s.total_reward == -1.0));
+
+        let summary = run.statistics();
+
+        assert_eq!(summary.n_episodes, converged_at + 1);
+        assert_eq!(summary.returns.max, -1.0);
+        assert_eq!(summary.steps.min, 2.0);
+
+        // A criterion that is never satisfied runs to the episode cap.
+        let never = |_: &[EpisodeStats]| false;
+        let run = run_until_converged(&mut domain, &mut agent, &policy, &mut rng, 10, 100, &never)
+            .unwrap();
+
+        assert_eq!(run.stats.len(), 10);
+        assert_eq!(run.converged_at, None);
+    }
+
+    #[test]
+    fn test_offline_matches_online() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut online = TD::new(Table::dense(Array1::zeros(25)), 0.9);
+
+        let mut domain = GridWorld::default();
+        let policy = Random::new(4);
+        let mut trajectories = vec![];
+
+        for _ in 0..3 {
+            domain.reset();
+
+            let mut trajectory = Trajectory::new(domain.emit());
+
+            for _ in 0..100 {
+                let t = domain.transition(policy.sample(&mut rng, domain.emit().state()));
+
+                online.handle(&t).ok();
+                trajectory.record(t);
+
+                if trajectory.steps.last().unwrap().0.is_terminal() {
+                    break;
+                }
+            }
+
+            trajectories.push(trajectory.into_batch());
+        }
+
+        let mut offline = TD::new(Table::dense(Array1::zeros(25)), 0.9);
+        let n = learn_offline(&mut offline, trajectories.iter().flatten()).unwrap();
+
+        assert_eq!(n, trajectories.iter().map(|b| b.len()).sum::<usize>());
+
+        for s in 0..25 {
+            assert_eq!(offline.v_func.evaluate((s,)), online.v_func.evaluate((s,)));
+        }
+    }
+
+    #[test]
+    fn test_offline_importance_weights() {
+        struct Ratios(Vec<f64>);
+
+        impl<'m> Handler<WeightedTransition<'m, Vec<f64>, usize>> for Ratios {
+            type Response = ();
+            type Error = ();
+
+            fn handle(&mut self, msg: WeightedTransition<'m, Vec<f64>, usize>) -> Result<(), ()> {
+                self.0.push(msg.ratio);
+
+                Ok(())
+            }
+        }
+
+        let target = Greedy::new(MockQ::new_shared(Some(vec![1.0, 0.0])));
+        let behaviour = Random::new(2);
+
+        let dataset: Vec<Transition<Vec<f64>, usize>> = [0, 1, 0]
+            .iter()
+            .map(|&action| Transition {
+                from: Observation::Full(vec![]),
+                action,
+                reward: 0.0,
+                to: Observation::Full(vec![]),
+            })
+            .collect();
+
+        let mut agent = Ratios(vec![]);
+
+        assert_eq!(learn_offline_weighted(&mut agent, &dataset, &target, &behaviour), Ok(3));
+        assert_eq!(agent.0, vec![2.0, 0.0, 2.0]);
+    }
+
+    #[test]
+    fn test_offline_stops_on_error() {
+        struct Failing(usize);
+
+        impl<'m> Handler<&'m Transition<usize, usize>> for Failing {
+            type Response = ();
+            type Error = usize;
+
+            fn handle(&mut self, t: &'m Transition<usize, usize>) -> Result<(), usize> {
+                if t.action == 1 {
+                    return Err(self.0);
+                }
+
+                self.0 += 1;
+
+                Ok(())
+            }
+        }
+
+        let dataset: Vec<Transition<usize, usize>> = [0, 0, 1, 0]
+            .iter()
+            .map(|&action| Transition {
+                from: Observation::Full(0),
+                action,
+                reward: 0.0,
+                to: Observation::Full(0),
+            })
+            .collect();
+
+        let mut agent = Failing(0);
+
+        assert_eq!(learn_offline(&mut agent, &dataset), Err(2));
+        assert_eq!(agent.0, 2);
+    }
+}
@@ -0,0 +1,158 @@
+// The deprecated `CompactLFA` is still implemented and tested here.
+#![allow(deprecated)]
+
+use crate::{
+    fa::{
+        linear::{basis::Basis, Features, FeaturesExt, Result},
+        StateActionUpdate,
+    },
+    Enumerable,
+    Function,
+    Handler,
+};
+use ndarray::Array2;
+use spaces::Space;
+use std::borrow::Borrow;
+
+/// Linear action-value function with single-precision weight storage.
+///
+/// Weights are held as `f32`, halving the memory footprint relative to the
+/// `f64` representations used elsewhere, which matters for large (e.g. tile
+/// coded) bases. Features, predictions and errors remain `f64`; only the
+/// stored weights, and hence the accumulated updates, are rounded to single
+/// precision. Updates are plain SGD with step size `alpha`.
+#[deprecated(note = "use `SimpleQLFA<B, f32>` instead")]
+#[derive(Clone, Debug)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate")
+)]
+pub struct CompactLFA<B> {
+    pub basis: B,
+    pub alpha: f64,
+
+    weights: Array2<f32>,
+}
+
+impl<B: Space> CompactLFA<B> {
+    pub fn new(basis: B, alpha: f64, n_actions: usize) -> Self {
+        let n_features: usize = basis.dim().into();
+
+        CompactLFA {
+            basis,
+            alpha,
+
+            weights: Array2::zeros((n_features, n_actions)),
+        }
+    }
+}
+
+impl<B> CompactLFA<B> {
+    /// Return the weight matrix, indexed by `(feature, action)`.
+    pub fn weights(&self) -> &Array2<f32> { &self.weights }
+
+    fn project<S>(&self, s: S) -> Result<Vec<(usize, f64)>>
+    where B: Basis<S, Value = Features> {
+        Ok(self.basis.project(s)?.iter_active().collect())
+    }
+
+    fn dot(&self, phi: &[(usize, f64)], action: usize) -> f64 {
+        phi.iter().map(|&(i, x)| f64::from(self.weights[(i, action)]) * x).sum()
+    }
+}
+
+impl<S, B> Function<(S,)> for CompactLFA<B>
+where B: Basis<S, Value = Features>
+{
+    type Output = Vec<f64>;
+
+    fn evaluate(&self, (s,): (S,)) -> Vec<f64> {
+        let phi = self.project(s).unwrap();
+
+        (0..self.weights.ncols()).map(|a| self.dot(&phi, a)).collect()
+    }
+}
+
+impl<S, B> Enumerable<(S,)> for CompactLFA<B>
+where B: Basis<S, Value = Features>
+{
+    fn len(&self, _: (S,)) -> usize { self.weights.ncols() }
+
+    fn evaluate_index(&self, (s,): (S,), index: usize) -> f64 {
+        self.dot(&self.project(s).unwrap(), index)
+    }
+}
+
+impl<S, A, B> Function<(S, A)> for CompactLFA<B>
+where
+    A: Borrow<usize>,
+    B: Basis<S, Value = Features>,
+{
+    type Output = f64;
+
+    fn evaluate(&self, (s, a): (S, A)) -> f64 { self.evaluate_index((s,), *a.borrow()) }
+}
+
+impl<S, A, B> Handler<StateActionUpdate<S, A, f64>> for CompactLFA<B>
+where
+    A: Borrow<usize>,
+    B: Basis<S, Value = Features>,
+{
+    type Response = ();
+    type Error = crate::fa::linear::Error;
+
+    fn handle(&mut self, msg: StateActionUpdate<S, A, f64>) -> Result<()> {
+        let a = *msg.action.borrow();
+        let scale = self.alpha * msg.error;
+
+        for (i, x) in self.project(msg.state)? {
+            self.weights[(i, a)] += (scale * x) as f32;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fa::{linear::basis::BoundedTileCoding, SparseLFA};
+    use spaces::{real::Interval, ProductSpace};
+
+    #[test]
+    fn test_matches_double_precision() {
+        let space =
+            ProductSpace::empty() + Interval::bounded(0.0, 1.0) + Interval::bounded(0.0, 1.0);
+        let basis = BoundedTileCoding::new(space, &[4, 4], 4);
+
+        let mut compact = CompactLFA::new(basis.clone(), 0.1, 2);
+        let mut double = SparseLFA::new(basis, 0.1, 2);
+
+        assert_eq!(compact.weights().dim(), (100, 2));
+
+        let updates = [([0.1, 0.2], 0, 1.0), ([0.8, 0.7], 1, -2.0), ([0.12, 0.21], 0, 0.3)];
+
+        for _ in 0..10 {
+            for &(x, a, error) in updates.iter() {
+                let msg = |x: &[f64; 2]| StateActionUpdate {
+                    state: x.to_vec(),
+                    action: a,
+                    error,
+                };
+
+                compact.handle(msg(&x)).unwrap();
+                double.handle(msg(&x)).unwrap();
+            }
+        }
+
+        for x in [[0.1, 0.2], [0.8, 0.7], [0.5, 0.5]].iter() {
+            let qs32 = compact.evaluate((x.to_vec(),));
+            let qs64 = double.evaluate((x.to_vec(),));
+
+            for (q32, q64) in qs32.into_iter().zip(qs64) {
+                assert!((q32 - q64).abs() < 1e-5);
+            }
+        }
+    }
+}
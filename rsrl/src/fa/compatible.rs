@@ -0,0 +1,169 @@
+use crate::{
+    fa::{
+        linear::{
+            basis::{Basis, CompatibleBasis},
+            FeaturesExt,
+            Result,
+        },
+        StateActionUpdate,
+    },
+    params::Parameterised,
+    policies::{DifferentiablePolicy, Policy},
+    Function,
+    Handler,
+};
+use ndarray::Array1;
+
+/// Linear action-value critic over features that are compatible with a
+/// policy.
+///
+/// The features of the pair `(s, a)` are the (flattened) gradient of the
+/// log-policy, `φ(s, a) = ∇_θ ln π(a | s)`, such that there is exactly one
+/// critic weight per policy weight and `Q_w(s, a) = φ(s, a)ᵀ w`. By the
+/// compatible function approximation theorem, substituting a critic of this
+/// form (with `w` fit to minimise the squared error to `Q^π`) for the true
+/// action-value function leaves the policy gradient unbiased. Since
+/// `Σ_a π(a | s) φ(s, a) = 0`, the critic estimates the advantage function
+/// rather than the action-value function itself.
+///
+/// The projection is derived directly from `policy`; pass a
+/// [`Shared`](crate::Shared) policy to keep it in sync with an actor that is
+/// being trained concurrently. Updates are plain SGD with step size `alpha`.
+///
+/// # References
+/// - Sutton, R. S., McAllester, D. A., Singh, S. P., Mansour, Y. (2000).
+///   Policy gradient methods for reinforcement learning with function
+///   approximation. In Advances in Neural Information Processing Systems, pp.
+///   1057–1063.
+#[derive(Clone, Debug, Parameterised)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate")
+)]
+pub struct CompatibleCritic<P> {
+    pub basis: CompatibleBasis<P>,
+
+    #[weights]
+    pub weights: Array1<f64>,
+
+    pub alpha: f64,
+}
+
+impl<P: Parameterised> CompatibleCritic<P> {
+    pub fn new(policy: P, alpha: f64) -> Self {
+        let n_weights = policy.n_weights();
+
+        CompatibleCritic {
+            basis: CompatibleBasis(policy),
+            weights: Array1::zeros(n_weights),
+            alpha,
+        }
+    }
+}
+
+impl<P> CompatibleCritic<P> {
+    /// Return the policy from which the features are derived.
+    pub fn policy(&self) -> &P { &self.basis.0 }
+
+    /// Return the compatible features, `∇_θ ln π(a | s)`, of the pair `(s, a)`.
+    pub fn features<S>(&self, s: S, a: P::Action) -> Result<Array1<f64>>
+    where P: DifferentiablePolicy<S> {
+        self.basis.project((s, a)).map(|phi| phi.into_dense())
+    }
+}
+
+impl<S, P> Function<(S, <P as Policy<S>>::Action)> for CompatibleCritic<P>
+where P: DifferentiablePolicy<S>
+{
+    type Output = f64;
+
+    fn evaluate(&self, (s, a): (S, P::Action)) -> f64 {
+        self.basis
+            .project((s, a))
+            .unwrap()
+            .iter_active()
+            .map(|(i, x)| x * self.weights[i])
+            .sum()
+    }
+}
+
+impl<S, P> Handler<StateActionUpdate<S, <P as Policy<S>>::Action, f64>> for CompatibleCritic<P>
+where P: DifferentiablePolicy<S>
+{
+    type Response = ();
+    type Error = crate::fa::linear::Error;
+
+    fn handle(&mut self, msg: StateActionUpdate<S, P::Action, f64>) -> Result<()> {
+        let scale = self.alpha * msg.error;
+
+        for (i, x) in self.basis.project((msg.state, msg.action))?.iter_active() {
+            self.weights[i] += scale * x;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        fa::linear::{basis::Closure, Features},
+        policies::LinearSoftmax,
+    };
+
+    type TestBasis = Closure<fn(&usize) -> Result<Features>>;
+
+    fn policy() -> LinearSoftmax<TestBasis> {
+        let basis: TestBasis = Closure::new(2, |s: &usize| {
+            Ok(Features::dense(vec![1.0, *s as f64]))
+        });
+        let mut p = LinearSoftmax::standard(basis, 3);
+
+        p.theta = ndarray::array![[0.2, -0.1, 0.4], [0.3, 0.5, -0.6]];
+        p
+    }
+
+    #[test]
+    fn test_compatible_features() {
+        let critic = CompatibleCritic::new(policy(), 0.1);
+
+        assert_eq!(critic.weights.len(), 6);
+
+        for s in 0..3 {
+            let probs = critic.policy().probabilities(&s);
+            let mut mean = Array1::<f64>::zeros(6);
+
+            for (a, p) in probs.into_iter().enumerate() {
+                let phi = critic.features(&s, a).unwrap();
+
+                assert_eq!(phi.len(), 6);
+
+                mean.scaled_add(p, &phi);
+            }
+
+            assert!(mean.iter().all(|m| m.abs() < 1e-12));
+        }
+    }
+
+    #[test]
+    fn test_update() {
+        let mut critic = CompatibleCritic::new(policy(), 0.5);
+
+        assert_eq!(critic.evaluate((&1, 0)), 0.0);
+
+        critic
+            .handle(StateActionUpdate {
+                state: &1,
+                action: 0,
+                error: 1.0,
+            })
+            .unwrap();
+
+        let phi = critic.features(&1, 0).unwrap();
+        let norm2: f64 = phi.iter().map(|x| x * x).sum();
+
+        assert!((critic.evaluate((&1, 0)) - 0.5 * norm2).abs() < 1e-12);
+    }
+}
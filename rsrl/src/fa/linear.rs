@@ -6,7 +6,7 @@ use crate::{
     Function,
     Handler,
 };
-use ndarray::{Array1, ArrayBase, Axis, DataMut, Dimension, Ix1, IntoDimension};
+use ndarray::{Array, Array1, ArrayBase, Axis, DataMut, Dimension, Ix1, IntoDimension};
 
 pub use lfa::*;
 
@@ -103,6 +103,1261 @@ pub mod basis {
     }
 
     impl<P, B> Combinators for SCB<P, B> {}
+
+    /// Radial basis function network with Gaussian prototypes on a regular grid.
+    ///
+    /// Each feature is given by `exp(-||s - c||^2 / (2 sigma^2))` for a centre
+    /// `c`. Chain with `normalise_l1` for activations that sum to one.
+    #[derive(Clone, Debug)]
+    #[cfg_attr(
+        feature = "serde",
+        derive(Serialize, Deserialize),
+        serde(crate = "serde_crate")
+    )]
+    pub struct RBFNetwork {
+        pub centres: Vec<Vec<f64>>,
+        pub sigma: f64,
+    }
+
+    impl RBFNetwork {
+        pub fn new(centres: Vec<Vec<f64>>, sigma: f64) -> Self {
+            if sigma <= 0.0 {
+                panic!("Bandwidth parameter in RBFNetwork must be positive.");
+            }
+
+            RBFNetwork { centres, sigma }
+        }
+
+        /// Construct a network with centres placed at the midpoints of an
+        /// equipartition of each dimension of `space` into `n_partitions[i]`
+        /// cells.
+        pub fn from_space(
+            space: spaces::ProductSpace<spaces::real::Interval>,
+            n_partitions: &[usize],
+            sigma: f64,
+        ) -> Self {
+            let centres = space
+                .into_iter()
+                .zip(n_partitions.iter())
+                .map(|(d, &n)| spaces::Equipartition::from_interval(d, n).centres())
+                .fold(vec![vec![]], |acc: Vec<Vec<f64>>, cs| {
+                    acc.into_iter()
+                        .flat_map(|prefix| {
+                            cs.iter().map(move |&c| {
+                                let mut centre = prefix.clone();
+                                centre.push(c);
+
+                                centre
+                            })
+                        })
+                        .collect()
+                });
+
+            RBFNetwork::new(centres, sigma)
+        }
+
+        fn activation(&self, input: &[f64], centre: &[f64]) -> f64 {
+            let d2: f64 = input.iter().zip(centre.iter()).map(|(x, c)| (x - c) * (x - c)).sum();
+
+            (-d2 / (2.0 * self.sigma * self.sigma)).exp()
+        }
+    }
+
+    impl spaces::Space for RBFNetwork {
+        type Value = super::Features;
+
+        fn dim(&self) -> spaces::Dim { spaces::Dim::Finite(self.centres.len()) }
+
+        fn card(&self) -> spaces::Card { spaces::Card::Infinite }
+    }
+
+    impl<T: std::borrow::Borrow<[f64]>> Basis<T> for RBFNetwork {
+        fn project(&self, input: T) -> Result<super::Features, super::Error> {
+            let input = input.borrow();
+
+            Ok(self.centres.iter().map(|c| self.activation(input, c)).collect())
+        }
+    }
+
+    impl<T: std::borrow::Borrow<[f64]>> EnumerableBasis<T> for RBFNetwork {
+        fn ith(&self, input: T, index: usize) -> Result<f64, super::Error> {
+            self.centres
+                .get(index)
+                .map(|c| self.activation(input.borrow(), c))
+                .ok_or_else(|| super::Error::index_error(index, self.centres.len()))
+        }
+    }
+
+    impl Combinators for RBFNetwork {}
+
+    /// Tile coding over a bounded space with multiple offset tilings.
+    ///
+    /// Each tiling partitions dimension `i` into `resolutions[i] + 1` tiles of
+    /// equal width, and is displaced from the first by a fraction of a tile
+    /// width along each dimension (using the asymmetric `1, 3, 5, ...`
+    /// displacement vector). Every input thus activates exactly one binary
+    /// feature per tiling. Inputs outside the bounds are clipped.
+    ///
+    /// Cyclic dimensions, such as angles, may instead be marked as wrapping
+    /// with [`BoundedTileCoding::with_wrapping`]. These are partitioned into
+    /// exactly `resolutions[i]` tiles, and the tiles displaced past the upper
+    /// bound continue from the lower bound, such that inputs either side of
+    /// the boundary are tiled together.
+    ///
+    /// Projecting an input whose length differs from the number of
+    /// resolutions panics, rather than silently ignoring trailing components.
+    ///
+    /// Unlike the hashing `TileCoding` exported by `lfa`, the features of
+    /// distinct tiles never collide.
+    ///
+    /// # References
+    /// - Sutton, R. S., & Barto, A. G. (2018). Reinforcement learning: An
+    ///   introduction (2nd ed., Section 9.5.4). MIT press.
+    #[derive(Clone, Debug)]
+    #[cfg_attr(
+        feature = "serde",
+        derive(Serialize, Deserialize),
+        serde(crate = "serde_crate")
+    )]
+    pub struct BoundedTileCoding {
+        bounds: Vec<(f64, f64)>,
+        resolutions: Vec<usize>,
+        wrap: Vec<bool>,
+        n_tilings: usize,
+    }
+
+    impl BoundedTileCoding {
+        /// Construct a tile coder over the bounded product space `space`.
+        ///
+        /// # Panics
+        ///
+        /// Panics if any dimension of `space` is unbounded, if `resolutions`
+        /// does not match the dimensionality of `space`, or if `n_tilings` is
+        /// zero.
+        pub fn new(
+            space: spaces::ProductSpace<spaces::real::Interval>,
+            resolutions: &[usize],
+            n_tilings: usize,
+        ) -> Self {
+            use spaces::BoundedSpace;
+
+            if n_tilings == 0 {
+                panic!("BoundedTileCoding requires at least one tiling.");
+            }
+
+            let bounds: Vec<(f64, f64)> = space
+                .into_iter()
+                .map(|d| match (d.inf(), d.sup()) {
+                    (Some(lb), Some(ub)) => (lb, ub),
+                    _ => panic!("BoundedTileCoding requires a bounded space."),
+                })
+                .collect();
+
+            if bounds.len() != resolutions.len() {
+                panic!("BoundedTileCoding requires one resolution per dimension.");
+            }
+
+            BoundedTileCoding {
+                bounds,
+                resolutions: resolutions.to_vec(),
+                wrap: vec![false; resolutions.len()],
+                n_tilings,
+            }
+        }
+
+        /// Return a new instance in which each dimension `i` with `wrap[i]`
+        /// set is treated as cyclic, i.e. its lower and upper bounds coincide.
+        ///
+        /// # Panics
+        ///
+        /// Panics if `wrap` does not match the dimensionality of the space, or
+        /// if a wrapping dimension has a resolution of zero.
+        pub fn with_wrapping(mut self, wrap: &[bool]) -> Self {
+            if wrap.len() != self.resolutions.len() {
+                panic!("BoundedTileCoding requires one wrap flag per dimension.");
+            }
+
+            if wrap.iter().zip(self.resolutions.iter()).any(|(&w, &res)| w && res == 0) {
+                panic!("BoundedTileCoding requires a positive resolution to wrap a dimension.");
+            }
+
+            self.wrap = wrap.to_vec();
+            self
+        }
+
+        /// Return the number of tilings.
+        pub fn n_tilings(&self) -> usize { self.n_tilings }
+
+        fn n_tiles(&self, i: usize) -> usize {
+            if self.wrap[i] {
+                self.resolutions[i]
+            } else {
+                self.resolutions[i] + 1
+            }
+        }
+
+        fn n_tiles_per_tiling(&self) -> usize {
+            (0..self.resolutions.len()).map(|i| self.n_tiles(i)).product()
+        }
+
+        /// Tile coordinates of `input` within the `t`th tiling.
+        fn tile_coords(&self, input: &[f64], t: usize) -> Vec<usize> {
+            assert_eq!(
+                input.len(),
+                self.resolutions.len(),
+                "BoundedTileCoding input does not match the dimensionality of the space."
+            );
+
+            self.bounds
+                .iter()
+                .zip(self.resolutions.iter())
+                .zip(input.iter())
+                .enumerate()
+                .map(|(i, ((&(lb, ub), &res), &x))| {
+                    let offset = ((t * (2 * i + 1)) % self.n_tilings) as f64
+                        / self.n_tilings as f64;
+
+                    if self.wrap[i] {
+                        let u = ((x - lb) / (ub - lb)).rem_euclid(1.0) * res as f64;
+
+                        (u + offset).floor() as usize % res
+                    } else {
+                        let u = (x.max(lb).min(ub) - lb) / (ub - lb) * res as f64;
+
+                        ((u + offset).floor() as usize).min(res)
+                    }
+                })
+                .collect()
+        }
+
+        fn active_index(&self, input: &[f64], t: usize) -> usize {
+            let flat = self
+                .tile_coords(input, t)
+                .into_iter()
+                .enumerate()
+                .fold(0, |acc, (i, c)| acc * self.n_tiles(i) + c);
+
+            t * self.n_tiles_per_tiling() + flat
+        }
+    }
+
+    impl spaces::Space for BoundedTileCoding {
+        type Value = super::Features;
+
+        fn dim(&self) -> spaces::Dim {
+            spaces::Dim::Finite(self.n_tilings * self.n_tiles_per_tiling())
+        }
+
+        fn card(&self) -> spaces::Card { spaces::Card::Infinite }
+    }
+
+    impl<T: std::borrow::Borrow<[f64]>> Basis<T> for BoundedTileCoding {
+        fn project(&self, input: T) -> Result<super::Features, super::Error> {
+            let input = input.borrow();
+            let dim = self.n_tilings * self.n_tiles_per_tiling();
+
+            Ok(super::Features::unitary(
+                dim,
+                (0..self.n_tilings).map(|t| self.active_index(input, t)),
+            ))
+        }
+    }
+
+    impl<T: std::borrow::Borrow<[f64]>> EnumerableBasis<T> for BoundedTileCoding {
+        fn ith(&self, input: T, index: usize) -> Result<f64, super::Error> {
+            let dim = self.n_tilings * self.n_tiles_per_tiling();
+
+            if index >= dim {
+                return Err(super::Error::index_error(index, dim));
+            }
+
+            let t = index / self.n_tiles_per_tiling();
+
+            Ok(if self.active_index(input.borrow(), t) == index { 1.0 } else { 0.0 })
+        }
+    }
+
+    impl Combinators for BoundedTileCoding {}
+
+    const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    /// 64-bit FNV-1a hash of `bytes`.
+    fn fnv1a<I: IntoIterator<Item = u8>>(bytes: I) -> u64 {
+        bytes
+            .into_iter()
+            .fold(FNV_OFFSET_BASIS, |h, b| (h ^ u64::from(b)).wrapping_mul(FNV_PRIME))
+    }
+
+    /// Tile coding with tile coordinates hashed into a fixed number of
+    /// features.
+    ///
+    /// This uses the same offset tilings as [`BoundedTileCoding`], but maps
+    /// the coordinates of each active tile to one of `memory_size` features
+    /// using the hashing trick. This bounds memory independently of the
+    /// dimensionality and resolution of the input space, at the cost of
+    /// occasional collisions between unrelated tiles; see
+    /// [`HashedTileCoding::collisions`]. Tiles are hashed with FNV-1a, so the
+    /// mapping is stable across builds and platforms.
+    #[derive(Clone, Debug)]
+    #[cfg_attr(
+        feature = "serde",
+        derive(Serialize, Deserialize),
+        serde(crate = "serde_crate")
+    )]
+    pub struct HashedTileCoding {
+        tilings: BoundedTileCoding,
+        memory_size: usize,
+    }
+
+    impl HashedTileCoding {
+        /// Construct a hashed tile coder with `memory_size` features.
+        ///
+        /// # Panics
+        ///
+        /// Panics under the same conditions as [`BoundedTileCoding::new`], or
+        /// if `memory_size` is zero.
+        pub fn new(
+            space: spaces::ProductSpace<spaces::real::Interval>,
+            resolutions: &[usize],
+            n_tilings: usize,
+            memory_size: usize,
+        ) -> Self {
+            if memory_size == 0 {
+                panic!("HashedTileCoding requires a positive memory size.");
+            }
+
+            HashedTileCoding {
+                tilings: BoundedTileCoding::new(space, resolutions, n_tilings),
+                memory_size,
+            }
+        }
+
+        /// Return a new instance with cyclic dimensions; see
+        /// [`BoundedTileCoding::with_wrapping`].
+        pub fn with_wrapping(mut self, wrap: &[bool]) -> Self {
+            self.tilings = self.tilings.with_wrapping(wrap);
+            self
+        }
+
+        /// Return the number of tilings.
+        pub fn n_tilings(&self) -> usize { self.tilings.n_tilings }
+
+        fn bucket(&self, input: &[f64], t: usize) -> (usize, Vec<usize>) {
+            let coords = self.tilings.tile_coords(input, t);
+            let bytes = std::iter::once(t)
+                .chain(coords.iter().cloned())
+                .flat_map(|x| (x as u64).to_le_bytes().to_vec());
+
+            ((fnv1a(bytes) % self.memory_size as u64) as usize, coords)
+        }
+
+        /// Count the tiles activated by `inputs` that were hashed into a bucket
+        /// already occupied by a different tile.
+        pub fn collisions<'a, I>(&self, inputs: I) -> usize
+        where I: IntoIterator<Item = &'a [f64]> {
+            let mut occupants = std::collections::HashMap::new();
+            let mut n_collisions = 0;
+
+            for input in inputs {
+                for t in 0..self.tilings.n_tilings {
+                    let (b, coords) = self.bucket(input, t);
+
+                    match occupants.get(&b) {
+                        Some(tile) if tile != &(t, coords.clone()) => n_collisions += 1,
+                        Some(_) => {},
+                        None => {
+                            occupants.insert(b, (t, coords));
+                        },
+                    }
+                }
+            }
+
+            n_collisions
+        }
+    }
+
+    impl spaces::Space for HashedTileCoding {
+        type Value = super::Features;
+
+        fn dim(&self) -> spaces::Dim { spaces::Dim::Finite(self.memory_size) }
+
+        fn card(&self) -> spaces::Card { spaces::Card::Infinite }
+    }
+
+    impl<T: std::borrow::Borrow<[f64]>> Basis<T> for HashedTileCoding {
+        fn project(&self, input: T) -> Result<super::Features, super::Error> {
+            let input = input.borrow();
+
+            Ok(super::Features::unitary(
+                self.memory_size,
+                (0..self.tilings.n_tilings).map(|t| self.bucket(input, t).0),
+            ))
+        }
+    }
+
+    impl<T: std::borrow::Borrow<[f64]>> EnumerableBasis<T> for HashedTileCoding {
+        fn ith(&self, input: T, index: usize) -> Result<f64, super::Error> {
+            if index >= self.memory_size {
+                return Err(super::Error::index_error(index, self.memory_size));
+            }
+
+            let input = input.borrow();
+
+            Ok(if (0..self.tilings.n_tilings).any(|t| self.bucket(input, t).0 == index) {
+                1.0
+            } else {
+                0.0
+            })
+        }
+    }
+
+    impl Combinators for HashedTileCoding {}
+
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    #[cfg_attr(
+        feature = "serde",
+        derive(Serialize, Deserialize),
+        serde(crate = "serde_crate")
+    )]
+    enum Norm {
+        L1,
+        L2,
+    }
+
+    /// Rescale the output of a basis to unit norm.
+    ///
+    /// Unlike the `normalise_l1` and `normalise_l2` combinators, a projection
+    /// with zero norm (e.g. far from every RBF centre) is returned unchanged
+    /// rather than producing `NaN` activations.
+    #[derive(Clone, Debug)]
+    #[cfg_attr(
+        feature = "serde",
+        derive(Serialize, Deserialize),
+        serde(crate = "serde_crate")
+    )]
+    pub struct Normalized<B> {
+        basis: B,
+        norm: Norm,
+    }
+
+    impl<B> Normalized<B> {
+        /// Normalise such that the absolute activations sum to one.
+        pub fn l1(basis: B) -> Self {
+            Normalized {
+                basis,
+                norm: Norm::L1,
+            }
+        }
+
+        /// Normalise such that the Euclidean norm of the activations is one.
+        pub fn l2(basis: B) -> Self {
+            Normalized {
+                basis,
+                norm: Norm::L2,
+            }
+        }
+
+        /// Return a reference to the wrapped basis.
+        pub fn inner(&self) -> &B { &self.basis }
+    }
+
+    impl<B: spaces::Space> spaces::Space for Normalized<B> {
+        type Value = super::Features;
+
+        fn dim(&self) -> spaces::Dim { self.basis.dim() }
+
+        fn card(&self) -> spaces::Card { self.basis.card() }
+    }
+
+    impl<T, B> Basis<T> for Normalized<B>
+    where B: Basis<T, Value = super::Features>
+    {
+        fn project(&self, input: T) -> Result<super::Features, super::Error> {
+            self.basis.project(input).map(|f| {
+                let z = match self.norm {
+                    Norm::L1 => f.fold(0.0, |acc, x| acc + x.abs()),
+                    Norm::L2 => f.fold(0.0, |acc, x| acc + x * x).sqrt(),
+                };
+
+                if z > 0.0 { f.map_into(|x| x / z) } else { f }
+            })
+        }
+    }
+
+    impl<B> Combinators for Normalized<B> {}
+
+    /// Joint projection of state-action pairs onto a single feature space.
+    ///
+    /// A linear approximator over such a basis (e.g. `ScalarLFA`) evaluates
+    /// `Q(s, a) = φ(s, a)ᵀ w` with a single weight vector, rather than one
+    /// vector per action, and so extends to continuous or structured action
+    /// spaces. This trait is implemented for every basis over pairs `(S, A)`.
+    pub trait StateActionProjector<S, A>: Basis<(S, A), Value = super::Features> {}
+
+    impl<S, A, B> StateActionProjector<S, A> for B
+    where B: Basis<(S, A), Value = super::Features>
+    {
+    }
+
+    /// Real-valued coordinates of an action, as used by [`JointBasis`].
+    pub trait ActionCoordinates {
+        /// Append the coordinates of the action to `buffer`.
+        fn extend_coordinates(&self, buffer: &mut Vec<f64>);
+    }
+
+    impl ActionCoordinates for usize {
+        fn extend_coordinates(&self, buffer: &mut Vec<f64>) { buffer.push(*self as f64); }
+    }
+
+    impl ActionCoordinates for f64 {
+        fn extend_coordinates(&self, buffer: &mut Vec<f64>) { buffer.push(*self); }
+    }
+
+    impl ActionCoordinates for [f64] {
+        fn extend_coordinates(&self, buffer: &mut Vec<f64>) { buffer.extend_from_slice(self); }
+    }
+
+    impl ActionCoordinates for Vec<f64> {
+        fn extend_coordinates(&self, buffer: &mut Vec<f64>) { buffer.extend_from_slice(self); }
+    }
+
+    impl<A: ActionCoordinates + ?Sized> ActionCoordinates for &A {
+        fn extend_coordinates(&self, buffer: &mut Vec<f64>) { (**self).extend_coordinates(buffer) }
+    }
+
+    /// Projection of the concatenated state and action coordinates, `[s, a]`,
+    /// by a basis over the combined space.
+    ///
+    /// The inner basis, `B`, is typically a [`BoundedTileCoding`] or
+    /// [`RBFNetwork`] whose trailing dimensions span the action space. Discrete
+    /// actions are mapped to the coordinate `a as f64`; for tile coding over an
+    /// interval `[0, n - 1]` with `n - 1` tiles, each action then occupies its
+    /// own tile in every tiling, recovering the per-action formulation exactly.
+    #[derive(Clone, Debug)]
+    #[cfg_attr(
+        feature = "serde",
+        derive(Serialize, Deserialize),
+        serde(crate = "serde_crate")
+    )]
+    pub struct JointBasis<B>(pub B);
+
+    impl<B: spaces::Space> spaces::Space for JointBasis<B> {
+        type Value = super::Features;
+
+        fn dim(&self) -> spaces::Dim { self.0.dim() }
+
+        fn card(&self) -> spaces::Card { self.0.card() }
+    }
+
+    impl<S, A, B> Basis<(S, A)> for JointBasis<B>
+    where
+        S: std::borrow::Borrow<[f64]>,
+        A: ActionCoordinates,
+        B: Basis<Vec<f64>, Value = super::Features>,
+    {
+        fn project(&self, (s, a): (S, A)) -> Result<super::Features, super::Error> {
+            let mut input = s.borrow().to_vec();
+
+            a.extend_coordinates(&mut input);
+
+            self.0.project(input)
+        }
+    }
+
+    impl<B> Combinators for JointBasis<B> {}
+
+    /// Basis with a constant, unit bias feature appended to the projection of
+    /// the inner basis, `B`; see [`Combinators::with_bias`].
+    pub type WithBias<B> = Stack<B, Bias>;
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use crate::fa::linear::Features;
+        use spaces::{real::Interval, ProductSpace, Space};
+
+        #[test]
+        fn test_centre_layout() {
+            let space = ProductSpace::empty()
+                + Interval::bounded(0.0, 1.0)
+                + Interval::bounded(-2.0, 2.0);
+            let net = RBFNetwork::from_space(space, &[2, 4], 0.5);
+
+            assert_eq!(net.dim(), spaces::Dim::Finite(8));
+            assert_eq!(net.centres, vec![
+                vec![0.25, -1.5], vec![0.25, -0.5], vec![0.25, 0.5], vec![0.25, 1.5],
+                vec![0.75, -1.5], vec![0.75, -0.5], vec![0.75, 0.5], vec![0.75, 1.5],
+            ]);
+        }
+
+        #[test]
+        fn test_activation_decay() {
+            let net = RBFNetwork::new(vec![vec![0.0]], 0.5);
+
+            let acts: Vec<f64> = [0.0, 0.25, 0.5, 1.0, 2.0]
+                .iter()
+                .map(|&x| net.ith([x], 0).unwrap())
+                .collect();
+
+            assert_eq!(acts[0], 1.0);
+            assert!((acts[2] - (-0.5f64).exp()).abs() < 1e-10);
+            assert!(acts.windows(2).all(|w| w[0] > w[1]));
+        }
+
+        fn unit_square() -> ProductSpace<Interval> {
+            ProductSpace::empty() + Interval::bounded(0.0, 1.0) + Interval::bounded(0.0, 1.0)
+        }
+
+        #[test]
+        fn test_tile_coding_active_count() {
+            let tc = BoundedTileCoding::new(unit_square(), &[4, 4], 8);
+
+            assert_eq!(tc.dim(), spaces::Dim::Finite(8 * 25));
+
+            for x in &[[0.0, 0.0], [0.3, 0.7], [1.0, 1.0], [-5.0, 5.0]] {
+                let f = tc.project(&x[..]).unwrap();
+
+                assert_eq!(f.n_active(), 8);
+                assert_eq!(f.sum(), 8.0);
+                assert_eq!(f.n_features(), 8 * 25);
+            }
+        }
+
+        #[test]
+        fn test_tile_coding_generalisation() {
+            let tc = BoundedTileCoding::new(unit_square(), &[4, 4], 8);
+
+            let shared = |a: [f64; 2], b: [f64; 2]| {
+                (0..8).filter(|&t| tc.active_index(&a, t) == tc.active_index(&b, t)).count()
+            };
+
+            assert!(shared([0.5, 0.5], [0.51, 0.5]) >= 6);
+            assert_eq!(shared([0.1, 0.1], [0.9, 0.9]), 0);
+            assert_eq!(tc.ith(&[0.5, 0.5][..], tc.active_index(&[0.5, 0.5], 3)).unwrap(), 1.0);
+        }
+
+        #[test]
+        fn test_tile_coding_wrapping() {
+            use std::f64::consts::PI;
+
+            let space = ProductSpace::empty()
+                + Interval::bounded(-PI, PI)
+                + Interval::bounded(0.0, 1.0);
+            let eps = 1e-3;
+            let (a, b) = ([PI - eps, 0.5], [-PI + eps, 0.5]);
+
+            let tc = BoundedTileCoding::new(space.clone(), &[6, 4], 8);
+            let wrapped = BoundedTileCoding::new(space, &[6, 4], 8).with_wrapping(&[true, false]);
+
+            let shared = |tc: &BoundedTileCoding| {
+                (0..8).filter(|&t| tc.active_index(&a, t) == tc.active_index(&b, t)).count()
+            };
+
+            assert_eq!(shared(&tc), 0);
+            assert!(shared(&wrapped) >= 6);
+
+            // The cyclic dimension needs no extra tile for the displaced tilings.
+            assert_eq!(wrapped.dim(), spaces::Dim::Finite(8 * 6 * 5));
+
+            // Inputs outside the bounds wrap around rather than being clipped.
+            for t in 0..8 {
+                assert_eq!(
+                    wrapped.active_index(&[PI / 3.0, 0.5], t),
+                    wrapped.active_index(&[PI / 3.0 - 2.0 * PI, 0.5], t)
+                );
+            }
+
+            for x in &[a, b, [0.0, 0.0], [3.0 * PI, 1.0]] {
+                let f = wrapped.project(&x[..]).unwrap();
+
+                assert_eq!(f.n_active(), 8);
+                assert!((0..8).all(|t| wrapped.active_index(x, t) < 8 * 30));
+            }
+        }
+
+        #[test]
+        #[should_panic]
+        fn test_tile_coding_dimension_mismatch() {
+            let tc = BoundedTileCoding::new(unit_square(), &[4, 4], 2);
+
+            tc.project(&[0.5, 0.5, 0.5][..]).unwrap();
+        }
+
+        #[test]
+        #[should_panic]
+        fn test_hashed_tile_coding_dimension_mismatch() {
+            let htc = HashedTileCoding::new(unit_square(), &[4, 4], 2, 64);
+
+            htc.project(&[0.5][..]).unwrap();
+        }
+
+        #[test]
+        fn test_hashed_tile_coding() {
+            let htc = HashedTileCoding::new(unit_square(), &[9, 9], 4, 4096);
+
+            assert_eq!(htc.dim(), spaces::Dim::Finite(4096));
+
+            let f = htc.project(&[0.25, 0.75][..]).unwrap();
+
+            assert_eq!(f.n_features(), 4096);
+            assert!(f.n_active() <= 4);
+
+            let grid: Vec<[f64; 2]> = (0..10)
+                .flat_map(|i| (0..10).map(move |j| [i as f64 / 9.0, j as f64 / 9.0]))
+                .collect();
+
+            // 400 tile activations; distinct tiles should rarely share a bucket.
+            assert!(htc.collisions(grid.iter().map(|x| &x[..])) < 40);
+        }
+
+        #[test]
+        fn test_hashed_tile_coding_stable() {
+            assert_eq!(fnv1a(vec![]), 0xcbf2_9ce4_8422_2325);
+            assert_eq!(fnv1a(b"a".to_vec()), 0xaf63_dc4c_8601_ec8c);
+            assert_eq!(fnv1a(b"foobar".to_vec()), 0x8594_4171_f739_67e8);
+
+            // Buckets must not change between builds, or saved weights are invalidated.
+            let htc = HashedTileCoding::new(unit_square(), &[9, 9], 4, 4096);
+            let f = htc.project(&[0.25, 0.75][..]).unwrap();
+
+            assert_eq!(active(f), vec![1665, 1729, 1730, 2274]);
+        }
+
+        fn active(f: Features) -> Vec<usize> {
+            let mut idx: Vec<usize> = match f {
+                Features::Sparse(sa) => sa.activations.keys().cloned().collect(),
+                Features::Dense(da) => (0..da.len()).filter(|&i| da[i] != 0.0).collect(),
+            };
+
+            idx.sort_unstable();
+            idx
+        }
+
+        #[test]
+        fn test_stack_sparse() {
+            let tc1 = BoundedTileCoding::new(unit_square(), &[4, 4], 2);
+            let tc2 = BoundedTileCoding::new(unit_square(), &[2, 2], 3);
+            let stacked = tc1.clone().stack(tc2.clone());
+
+            assert_eq!(stacked.dim(), spaces::Dim::Finite(50 + 27));
+
+            let x = [0.3, 0.7];
+            let f = stacked.project(&x[..]).unwrap();
+
+            assert!(f.is_sparse());
+            assert_eq!(f.n_features(), 77);
+
+            let mut expected: Vec<usize> = (0..2).map(|t| tc1.active_index(&x, t)).collect();
+
+            expected.extend((0..3).map(|t| 50 + tc2.active_index(&x, t)));
+            expected.sort_unstable();
+
+            assert_eq!(active(f), expected);
+        }
+
+        #[test]
+        fn test_stack_mixed() {
+            let tc = BoundedTileCoding::new(unit_square(), &[4, 4], 2);
+            let net = RBFNetwork::new(vec![vec![0.0, 0.0], vec![1.0, 1.0]], 0.5);
+
+            let x = [0.3, 0.7];
+            let phi_tc = tc.project(&x[..]).unwrap().into_dense();
+            let phi_net = net.project(&x[..]).unwrap().into_dense();
+
+            let f = tc.clone().stack(net.clone()).project(&x[..]).unwrap().into_dense();
+
+            assert_eq!(f.len(), 52);
+            assert_eq!(f.slice(s![..50]), phi_tc);
+            assert_eq!(f.slice(s![50..]), phi_net);
+
+            let f = net.stack(tc).project(&x[..]).unwrap().into_dense();
+
+            assert_eq!(f.len(), 52);
+            assert_eq!(f.slice(s![..2]), phi_net);
+            assert_eq!(f.slice(s![2..]), phi_tc);
+        }
+
+        #[test]
+        fn test_normalized_unit_norm() {
+            let net = RBFNetwork::new(vec![vec![0.0], vec![1.0], vec![2.0]], 1.0);
+
+            let l2 = Normalized::l2(net.clone()).project([0.3]).unwrap().into_dense();
+            let l1 = Normalized::l1(net).project([0.3]).unwrap().into_dense();
+
+            assert!((l2.iter().map(|x| x * x).sum::<f64>() - 1.0).abs() < 1e-10);
+            assert!((l1.iter().map(|x| x.abs()).sum::<f64>() - 1.0).abs() < 1e-10);
+
+            let tc = Normalized::l2(BoundedTileCoding::new(unit_square(), &[4, 4], 4));
+            let f = tc.project(&[0.3, 0.7][..]).unwrap();
+
+            assert!(f.is_sparse());
+            assert!(f.to_dense().iter().all(|&x| x == 0.0 || (x - 0.5).abs() < 1e-10));
+        }
+
+        #[test]
+        fn test_normalized_zero() {
+            // Activations underflow to zero far from every centre.
+            let net = RBFNetwork::new(vec![vec![0.0], vec![1.0]], 0.01);
+
+            for basis in [Normalized::l1(net.clone()), Normalized::l2(net)].iter() {
+                let phi = basis.project([1e3]).unwrap().into_dense();
+
+                assert_eq!(phi.len(), 2);
+                assert!(phi.iter().all(|&x| x == 0.0));
+            }
+        }
+
+        #[test]
+        fn test_with_bias() {
+            let net = RBFNetwork::new(vec![vec![0.0], vec![1.0]], 0.5);
+            let biased: WithBias<_> = net.clone().with_bias();
+
+            assert_eq!(biased.dim(), spaces::Dim::Finite(3));
+
+            for &x in &[-10.0, 0.3, 1.0] {
+                let phi = biased.project([x]).unwrap().into_dense();
+
+                assert_eq!(phi.len(), 3);
+                assert_eq!(phi[2], 1.0);
+                assert_eq!(phi.slice(s![..2]), net.project([x]).unwrap().into_dense());
+            }
+
+            let tc = BoundedTileCoding::new(unit_square(), &[4, 4], 2);
+            let biased = WithBias::new(tc.clone(), Bias::unit());
+
+            assert_eq!(biased.dim(), tc.dim() + spaces::Dim::Finite(1));
+
+            let f = biased.project(&[0.3, 0.7][..]).unwrap();
+
+            assert_eq!(f.n_features(), 51);
+            assert_eq!(f.to_dense()[50], 1.0);
+        }
+
+        #[test]
+        fn test_normalised() {
+            let net = RBFNetwork::new(vec![vec![0.0], vec![1.0], vec![2.0]], 1.0).normalise_l1();
+            let total: f64 = net.project([0.3]).unwrap().into_dense().sum();
+
+            assert!((total - 1.0).abs() < 1e-10);
+        }
+    }
+}
+
+pub mod optim {
+    pub use lfa::optim::*;
+
+    use crate::fa::linear::{Features, FeaturesExt, Result};
+    use ndarray::{Array1, ArrayViewMut1};
+
+    /// Incremental delta-bar-delta (IDBD) with per-weight adaptive step sizes.
+    ///
+    /// Each weight `w_i` has its own step size `exp(beta_i)`, where `beta_i` is
+    /// adapted by meta-gradient descent using a trace, `h_i`, of recent updates
+    /// to `w_i`. Inputs that are consistently relevant to the target see their
+    /// step sizes grow, while those that only contribute noise are damped.
+    ///
+    /// # References
+    /// - Sutton, R. S. (1992). Adapting bias by gradient descent: An incremental
+    ///   version of delta-bar-delta. In Proceedings of the Tenth National
+    ///   Conference on Artificial Intelligence, pp. 171–176.
+    #[derive(Clone, Debug, PartialEq)]
+    #[cfg_attr(
+        feature = "serde",
+        derive(Serialize, Deserialize),
+        serde(crate = "serde_crate")
+    )]
+    pub struct IDBD {
+        pub meta_rate: f64,
+
+        initial_beta: f64,
+        betas: Array1<f64>,
+        traces: Array1<f64>,
+    }
+
+    impl IDBD {
+        pub fn new(n_params: usize, initial_alpha: f64, meta_rate: f64) -> Self {
+            let initial_beta = initial_alpha.ln();
+
+            IDBD {
+                meta_rate,
+
+                initial_beta,
+                betas: Array1::from_elem(n_params, initial_beta),
+                traces: Array1::zeros(n_params),
+            }
+        }
+
+        /// Return the current step size associated with each weight.
+        pub fn step_sizes(&self) -> Array1<f64> { self.betas.mapv(f64::exp) }
+
+        fn update_weight(&mut self, w: &mut f64, i: usize, x: f64, delta: f64) {
+            let h = self.traces[i];
+
+            self.betas[i] += self.meta_rate * delta * x * h;
+
+            let alpha = self.betas[i].exp();
+
+            *w += alpha * delta * x;
+            self.traces[i] = h * (1.0 - alpha * x * x).max(0.0) + alpha * delta * x;
+        }
+    }
+
+    impl Optimiser<Features> for IDBD {
+        fn step_scaled(
+            &mut self,
+            weights: &mut ArrayViewMut1<f64>,
+            features: &Features,
+            scale_factor: f64,
+        ) -> Result<()>
+        {
+            // Inactive features leave the weights, step sizes and traces unchanged.
+            for (i, x) in features.iter_active() {
+                self.update_weight(&mut weights[i], i, x, scale_factor);
+            }
+
+            Ok(())
+        }
+
+        fn reset(&mut self) {
+            self.betas.fill(self.initial_beta);
+            self.traces.fill(0.0);
+        }
+    }
+
+    /// Adaptive moment estimation (Adam).
+    ///
+    /// Maintains exponential moving averages of the gradient and its square,
+    /// with the usual bias correction for their zero initialisation. Unlike the
+    /// variant in `lfa`, the bias correction at step `t` uses `β^t` exactly and
+    /// all weights are updated on every step, including those whose features
+    /// are inactive in a sparse gradient.
+    ///
+    /// # References
+    /// - Kingma, D. P., Ba, J. (2015). Adam: A method for stochastic
+    ///   optimization. In Proceedings of the International Conference on
+    ///   Learning Representations.
+    #[derive(Clone, Debug, PartialEq)]
+    #[cfg_attr(
+        feature = "serde",
+        derive(Serialize, Deserialize),
+        serde(crate = "serde_crate")
+    )]
+    pub struct Adam {
+        pub learning_rate: f64,
+        pub beta1: f64,
+        pub beta2: f64,
+        pub eps: f64,
+
+        t: i32,
+        exp_avg: Array1<f64>,
+        exp_avg_sq: Array1<f64>,
+    }
+
+    impl Adam {
+        pub fn new(n_params: usize, learning_rate: f64, beta1: f64, beta2: f64) -> Self {
+            Adam {
+                learning_rate,
+                beta1,
+                beta2,
+                eps: 1e-8,
+
+                t: 0,
+                exp_avg: Array1::zeros(n_params),
+                exp_avg_sq: Array1::zeros(n_params),
+            }
+        }
+
+        /// Construct an instance with the default `β1 = 0.9` and `β2 = 0.999`.
+        pub fn standard(n_params: usize, learning_rate: f64) -> Self {
+            Adam::new(n_params, learning_rate, 0.9, 0.999)
+        }
+
+        /// Return a copy with the numerical stability constant set to `eps`.
+        pub fn with_eps(mut self, eps: f64) -> Self {
+            self.eps = eps;
+            self
+        }
+
+        /// Return the bias-corrected first and second moment estimates.
+        pub fn moments(&self) -> (Array1<f64>, Array1<f64>) {
+            if self.t == 0 {
+                return (self.exp_avg.clone(), self.exp_avg_sq.clone());
+            }
+
+            let c1 = 1.0 - self.beta1.powi(self.t);
+            let c2 = 1.0 - self.beta2.powi(self.t);
+
+            (self.exp_avg.mapv(|m| m / c1), self.exp_avg_sq.mapv(|v| v / c2))
+        }
+    }
+
+    impl Optimiser<Features> for Adam {
+        fn step_scaled(
+            &mut self,
+            weights: &mut ArrayViewMut1<f64>,
+            features: &Features,
+            scale_factor: f64,
+        ) -> Result<()>
+        {
+            self.t += 1;
+
+            let c1 = 1.0 - self.beta1.powi(self.t);
+            let c2 = 1.0 - self.beta2.powi(self.t);
+
+            for (i, w) in weights.iter_mut().enumerate() {
+                let g = match features {
+                    Features::Dense(da) => da[i],
+                    Features::Sparse(sa) => sa.activations.get(&i).cloned().unwrap_or(0.0),
+                } * scale_factor;
+
+                let m = &mut self.exp_avg[i];
+                let v = &mut self.exp_avg_sq[i];
+
+                *m = self.beta1 * *m + (1.0 - self.beta1) * g;
+                *v = self.beta2 * *v + (1.0 - self.beta2) * g * g;
+
+                *w += self.learning_rate * (*m / c1) / ((*v / c2).sqrt() + self.eps);
+            }
+
+            Ok(())
+        }
+
+        fn reset(&mut self) {
+            self.t = 0;
+            self.exp_avg.fill(0.0);
+            self.exp_avg_sq.fill(0.0);
+        }
+    }
+
+    /// Root mean square propagation (RMSProp).
+    ///
+    /// Scales the step for each weight by the root of an exponentially decaying
+    /// average of its squared gradients.
+    ///
+    /// # References
+    /// - Tieleman, T., Hinton, G. (2012). Lecture 6.5 - RMSProp: Divide the
+    ///   gradient by a running average of its recent magnitude. COURSERA: Neural
+    ///   Networks for Machine Learning.
+    #[derive(Clone, Debug, PartialEq)]
+    #[cfg_attr(
+        feature = "serde",
+        derive(Serialize, Deserialize),
+        serde(crate = "serde_crate")
+    )]
+    pub struct RMSProp {
+        pub learning_rate: f64,
+        pub decay: f64,
+        pub eps: f64,
+
+        mean_square: Array1<f64>,
+    }
+
+    impl RMSProp {
+        pub fn new(n_params: usize, learning_rate: f64, decay: f64) -> Self {
+            RMSProp {
+                learning_rate,
+                decay,
+                eps: 1e-8,
+
+                mean_square: Array1::zeros(n_params),
+            }
+        }
+
+        /// Return a copy with the numerical stability constant set to `eps`.
+        pub fn with_eps(mut self, eps: f64) -> Self {
+            self.eps = eps;
+            self
+        }
+
+        /// Return the running average of the squared gradient for each weight.
+        pub fn mean_square(&self) -> &Array1<f64> { &self.mean_square }
+    }
+
+    impl Optimiser<Features> for RMSProp {
+        fn step_scaled(
+            &mut self,
+            weights: &mut ArrayViewMut1<f64>,
+            features: &Features,
+            scale_factor: f64,
+        ) -> Result<()>
+        {
+            for (i, w) in weights.iter_mut().enumerate() {
+                let g = match features {
+                    Features::Dense(da) => da[i],
+                    Features::Sparse(sa) => sa.activations.get(&i).cloned().unwrap_or(0.0),
+                } * scale_factor;
+
+                let ms = &mut self.mean_square[i];
+
+                *ms = self.decay * *ms + (1.0 - self.decay) * g * g;
+                *w += self.learning_rate * g / (ms.sqrt() + self.eps);
+            }
+
+            Ok(())
+        }
+
+        fn reset(&mut self) { self.mean_square.fill(0.0); }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use rand::{rngs::StdRng, Rng, SeedableRng};
+
+        // Tracking task from Sutton (1992): only the first few inputs are
+        // relevant, and the signs of their target weights flip periodically.
+        fn tracking_error<O: Optimiser>(mut opt: O) -> f64 {
+            const N: usize = 20;
+            const N_RELEVANT: usize = 5;
+
+            let mut rng = StdRng::seed_from_u64(0);
+            let mut target = [1.0; N_RELEVANT];
+            let mut weights = Array1::<f64>::zeros(N);
+            let mut sse = 0.0;
+
+            for t in 0..20_000 {
+                if t % 20 == 0 {
+                    let i = rng.gen_range(0, N_RELEVANT);
+
+                    target[i] = -target[i];
+                }
+
+                let x: Array1<f64> = (0..N).map(|_| rng.gen_range(-1.0, 1.0)).collect();
+                let y: f64 = target.iter().zip(x.iter()).map(|(a, b)| a * b).sum();
+                let y_hat: f64 = weights.iter().zip(x.iter()).map(|(a, b)| a * b).sum();
+                let error = y - y_hat;
+
+                if t >= 10_000 {
+                    sse += error * error;
+                }
+
+                opt.step_scaled(&mut weights.view_mut(), &Features::Dense(x), error).unwrap();
+            }
+
+            sse / 10_000.0
+        }
+
+        #[test]
+        fn test_step_sizes_adapt() {
+            let mut opt = IDBD::new(2, 0.1, 0.01);
+            let mut weights = Array1::zeros(2);
+            let x = Features::dense(vec![1.0, 0.0]);
+
+            for _ in 0..10 {
+                opt.step_scaled(&mut weights.view_mut(), &x, 1.0).unwrap();
+            }
+
+            let alphas = opt.step_sizes();
+
+            assert!(alphas[0] > 0.1);
+            assert!((alphas[1] - 0.1).abs() < 1e-12);
+            assert_eq!(weights[1], 0.0);
+
+            opt.reset();
+
+            assert!((opt.step_sizes()[0] - 0.1).abs() < 1e-12);
+        }
+
+        #[test]
+        fn test_adam_moments() {
+            let (b1, b2, lr, eps) = (0.9, 0.999, 0.1, 1e-8);
+            let grads = [[1.0, -2.0], [0.5, 0.0], [-1.0, 3.0]];
+
+            let mut opt = Adam::new(2, lr, b1, b2);
+            let mut weights = Array1::zeros(2);
+
+            // Reference computation following Algorithm 1 of Kingma & Ba (2015).
+            let mut m = [0.0; 2];
+            let mut v = [0.0; 2];
+            let mut w = [0.0; 2];
+
+            for (t, g) in grads.iter().enumerate() {
+                let t = t as i32 + 1;
+
+                opt.step(&mut weights.view_mut(), &Features::dense(g.to_vec())).unwrap();
+
+                let (m_hat, v_hat) = opt.moments();
+
+                for i in 0..2 {
+                    m[i] = b1 * m[i] + (1.0 - b1) * g[i];
+                    v[i] = b2 * v[i] + (1.0 - b2) * g[i] * g[i];
+
+                    let m_ref = m[i] / (1.0 - b1.powi(t));
+                    let v_ref = v[i] / (1.0 - b2.powi(t));
+
+                    w[i] += lr * m_ref / (v_ref.sqrt() + eps);
+
+                    assert!((m_hat[i] - m_ref).abs() < 1e-12);
+                    assert!((v_hat[i] - v_ref).abs() < 1e-12);
+                    assert!((weights[i] - w[i]).abs() < 1e-12);
+                }
+            }
+
+            // The first step has magnitude equal to the learning rate.
+            let mut opt = Adam::standard(1, 0.01);
+            let mut weights = Array1::zeros(1);
+
+            opt.step_scaled(&mut weights.view_mut(), &Features::dense(vec![4.0]), -2.0).unwrap();
+
+            assert!((weights[0] + 0.01).abs() < 1e-9);
+        }
+
+        #[test]
+        fn test_adam_sparse_matches_dense() {
+            let mut dense = Adam::standard(3, 0.1);
+            let mut sparse = dense.clone();
+            let mut w_dense = Array1::zeros(3);
+            let mut w_sparse = Array1::zeros(3);
+
+            for x in [vec![1.0, 0.0, 0.0], vec![0.0, 0.0, 2.0], vec![0.0, 0.0, 0.0]].iter() {
+                let active = x.iter().cloned().enumerate().filter(|(_, v)| *v != 0.0);
+                let sa = Features::sparse(3, active);
+
+                dense.step(&mut w_dense.view_mut(), &Features::dense(x.clone())).unwrap();
+                sparse.step(&mut w_sparse.view_mut(), &sa).unwrap();
+            }
+
+            assert_eq!(w_dense, w_sparse);
+            assert_eq!(dense.moments(), sparse.moments());
+        }
+
+        #[test]
+        fn test_rmsprop() {
+            let mut opt = RMSProp::new(2, 0.01, 0.9);
+            let mut weights = Array1::zeros(2);
+
+            opt.step(&mut weights.view_mut(), &Features::dense(vec![2.0, 0.0])).unwrap();
+
+            // E[g²] = 0.1 * 4 = 0.4; step = 0.01 * 2 / sqrt(0.4).
+            assert!((opt.mean_square()[0] - 0.4).abs() < 1e-12);
+            assert!((weights[0] - 0.02 / 0.4f64.sqrt()).abs() < 1e-6);
+            assert_eq!(weights[1], 0.0);
+
+            opt.step_scaled(&mut weights.view_mut(), &Features::dense(vec![1.0, 1.0]), -1.0)
+                .unwrap();
+
+            // E[g²] = 0.9 * 0.4 + 0.1 * 1 = 0.46 and 0.1 respectively.
+            let ms = opt.mean_square();
+
+            assert!((ms[0] - 0.46).abs() < 1e-12);
+            assert!((ms[1] - 0.1).abs() < 1e-12);
+            assert!((weights[0] - (0.02 / 0.4f64.sqrt() - 0.01 / 0.46f64.sqrt())).abs() < 1e-6);
+            assert!((weights[1] + 0.01 / 0.1f64.sqrt()).abs() < 1e-6);
+
+            opt.reset();
+
+            assert_eq!(opt.mean_square(), &Array1::<f64>::zeros(2));
+        }
+
+        #[test]
+        fn test_tracks_faster_than_fixed_alpha() {
+            let fixed = tracking_error(SGD(0.05));
+            let idbd = tracking_error(IDBD::new(20, 0.05, 0.01));
+
+            assert!(idbd < fixed);
+        }
+    }
 }
 
 type Jacobian = Columnar<Features>;
@@ -164,6 +1419,87 @@ impl BufferMut for Features {
     }
 }
 
+/// Iterator over the non-zero activations of a `Features` instance.
+pub enum ActiveIter<'a> {
+    Dense(std::iter::Enumerate<ndarray::iter::Iter<'a, f64, Ix1>>),
+    Sparse(std::collections::hash_map::Iter<'a, usize, f64>),
+}
+
+impl<'a> Iterator for ActiveIter<'a> {
+    type Item = (usize, f64);
+
+    fn next(&mut self) -> Option<(usize, f64)> {
+        match self {
+            ActiveIter::Dense(it) => it.find(|(_, &x)| x != 0.0).map(|(i, &x)| (i, x)),
+            ActiveIter::Sparse(it) => it.next().map(|(&i, &x)| (i, x)),
+        }
+    }
+}
+
+/// Representation-agnostic access to `Features`.
+///
+/// Dense conversion is already provided by `Features::to_dense`, which uses
+/// the dimensionality carried by the features themselves.
+pub trait FeaturesExt {
+    /// Return a sparse copy of the features, retaining only non-zero
+    /// activations.
+    fn to_sparse(&self) -> Features;
+
+    /// Iterate over the `(index, activation)` pairs of all active features,
+    /// regardless of representation. The order of iteration is unspecified.
+    fn iter_active(&self) -> ActiveIter<'_>;
+}
+
+impl FeaturesExt for Features {
+    fn to_sparse(&self) -> Features {
+        Features::Sparse(SparseActivations {
+            dim: self.n_features(),
+            activations: self.iter_active().collect(),
+        })
+    }
+
+    fn iter_active(&self) -> ActiveIter<'_> {
+        match self {
+            Features::Dense(da) => ActiveIter::Dense(da.iter().enumerate()),
+            Features::Sparse(sa) => ActiveIter::Sparse(sa.activations.iter()),
+        }
+    }
+}
+
+/// Construction of linear function approximators from pre-computed weights.
+///
+/// This allows an approximator to be warm-started from weights that were
+/// learned previously, or computed externally (e.g. by LSTD or value
+/// iteration), for transfer and fine-tuning.
+pub trait FromWeights<B, W, O>: Sized {
+    /// Construct an approximator with the given basis, initial weights and
+    /// optimisation routine.
+    ///
+    /// # Panics
+    /// If the leading dimension of `weights` does not match the dimensionality
+    /// of `basis`.
+    fn from_weights(basis: B, weights: W, optimiser: O) -> Self;
+}
+
+impl<B, D, O> FromWeights<B, Array<f64, D>, O> for LFA<B, Array<f64, D>, O>
+where
+    B: spaces::Space,
+    D: Dimension,
+{
+    fn from_weights(basis: B, weights: Array<f64, D>, optimiser: O) -> Self {
+        let n_features: usize = basis.dim().into();
+        let n_rows = weights.shape().first().cloned().unwrap_or(0);
+
+        assert_eq!(
+            n_rows, n_features,
+            "Weights have {} rows but the basis has {} features.",
+            n_rows, n_features
+        );
+
+        LFA::new(basis, weights, optimiser)
+    }
+}
+
 ///////////////////////////////////////////////////////////////////////////////////////////////////
 // Common
 ///////////////////////////////////////////////////////////////////////////////////////////////////
@@ -389,3 +1725,124 @@ where
         self.update_index(msg.state, *msg.action.borrow(), msg.error)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_joint_basis_matches_per_action() {
+        use crate::{fa::SimpleQLFA, QFunction};
+        use spaces::{real::Interval, ProductSpace};
+
+        let state_space = ProductSpace::empty() + Interval::bounded(0.0, 1.0);
+        let joint_space = state_space.clone() + Interval::bounded(0.0, 2.0);
+
+        let mut joint = ScalarLFA::scalar(
+            basis::JointBasis(basis::BoundedTileCoding::new(joint_space, &[4, 2], 4)),
+            optim::SGD(0.1),
+        );
+        let mut per_action =
+            SimpleQLFA::new(basis::BoundedTileCoding::new(state_space, &[4], 4), 0.1, 3);
+
+        fn q_joint<P>(fa: &ScalarLFA<P, optim::SGD>, s: f64, a: usize) -> f64
+        where P: basis::StateActionProjector<Vec<f64>, usize> {
+            let phi = fa.basis.project((vec![s], a)).unwrap();
+
+            phi.iter_active().map(|(i, x)| x * fa.weights[i]).sum()
+        }
+
+        let updates = [(0.1, 0, 1.0), (0.15, 1, -0.5), (0.6, 2, 2.0), (0.9, 0, 0.3)];
+
+        for _ in 0..20 {
+            for &(s, a, error) in updates.iter() {
+                let pred = q_joint(&joint, s, a);
+
+                joint
+                    .handle(StateActionUpdate {
+                        state: vec![s],
+                        action: a,
+                        error: error - pred,
+                    })
+                    .unwrap();
+
+                let pred = per_action.evaluate_index((vec![s],), a);
+
+                per_action.update(vec![s], a, error - pred).unwrap();
+            }
+        }
+
+        assert!((per_action.action_values(vec![0.6])[2] - 2.0).abs() < 1e-3);
+
+        for s in [0.0, 0.1, 0.3, 0.6, 0.95].iter() {
+            let values = per_action.action_values(vec![*s]);
+
+            for (a, v) in values.into_iter().enumerate() {
+                assert!((q_joint(&joint, *s, a) - v).abs() < 1e-12);
+            }
+        }
+    }
+
+    #[test]
+    fn test_sparse_dense_round_trip() {
+        let sparse = Features::sparse(6, vec![(1, 0.5), (4, -2.0)]);
+        let dense = sparse.to_dense();
+
+        assert_eq!(dense, Array1::from(vec![0.0, 0.5, 0.0, 0.0, -2.0, 0.0]));
+
+        let round_trip = Features::Dense(dense).to_sparse();
+
+        assert!(round_trip.is_sparse());
+        assert_eq!(round_trip, sparse);
+    }
+
+    #[test]
+    fn test_iter_active() {
+        let dense = Features::Dense(Array1::from(vec![0.0, 1.0, 0.0, 3.0]));
+        let sparse = dense.to_sparse();
+
+        let mut from_dense: Vec<(usize, f64)> = dense.iter_active().collect();
+        let mut from_sparse: Vec<(usize, f64)> = sparse.iter_active().collect();
+
+        from_dense.sort_by_key(|&(i, _)| i);
+        from_sparse.sort_by_key(|&(i, _)| i);
+
+        assert_eq!(from_dense, vec![(1, 1.0), (3, 3.0)]);
+        assert_eq!(from_sparse, from_dense);
+    }
+
+    fn one_hot(n: usize) -> basis::Closure<impl Fn(&usize) -> Result<Features>> {
+        basis::Closure::new(n, move |s: &usize| Ok(Features::sparse(n, vec![(*s, 1.0)])))
+    }
+
+    // Evaluating the dense branch of `Features::dot` requires a BLAS implementation.
+    #[cfg(feature = "blas")]
+    #[test]
+    fn test_from_weights_scalar() {
+        let weights = Array1::from(vec![1.0, -2.0, 3.0]);
+        let fa = ScalarLFA::from_weights(one_hot(3), weights, optim::SGD(1.0));
+
+        assert_eq!(fa.weights_dim(), (3, 1));
+        assert_eq!(fa.evaluate(&0).unwrap(), 1.0);
+        assert_eq!(fa.evaluate(&1).unwrap(), -2.0);
+        assert_eq!(fa.evaluate(&2).unwrap(), 3.0);
+    }
+
+    #[cfg(feature = "blas")]
+    #[test]
+    fn test_from_weights_vector() {
+        let weights = ndarray::Array2::from_shape_vec((2, 3), vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0])
+            .unwrap();
+        let fa = VectorLFA::from_weights(one_hot(2), weights, optim::SGD(1.0));
+
+        assert_eq!(fa.n_outputs(), 3);
+        assert_eq!(fa.evaluate(&0).unwrap(), Array1::from(vec![1.0, 2.0, 3.0]));
+        assert_eq!(fa.evaluate(&1).unwrap(), Array1::from(vec![4.0, 5.0, 6.0]));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_from_weights_shape_mismatch() {
+        ScalarLFA::from_weights(one_hot(3), Array1::zeros(4), optim::SGD(1.0));
+    }
+}
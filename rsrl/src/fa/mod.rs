@@ -53,3 +53,19 @@ pub mod transforms;
 
 mod composition;
 pub use self::composition::Composition;
+
+mod sparse;
+pub use self::sparse::SparseLFA;
+
+mod compact;
+#[allow(deprecated)]
+pub use self::compact::CompactLFA;
+
+mod simple;
+pub use self::simple::SimpleQLFA;
+
+mod compatible;
+pub use self::compatible::CompatibleCritic;
+
+mod uncertainty;
+pub use self::uncertainty::Uncertain;
@@ -0,0 +1,253 @@
+use crate::{
+    fa::{
+        linear::{basis::Basis, Features, FeaturesExt, Result},
+        StateActionUpdate,
+    },
+    params::{Parameterised, WeightsView, WeightsViewMut},
+    Enumerable,
+    Function,
+    Handler,
+};
+use ndarray::{Array2, NdFloat};
+use spaces::Space;
+use std::borrow::Borrow;
+
+/// Linear action-value function with one weight vector per action.
+///
+/// Each state is projected once onto a shared basis, `φ(s)`, and the value of
+/// action `a` is given by `Q(s, a) = φ(s)ᵀ w_a`. An update to `(s, a)` only
+/// modifies the weight vector `w_a`, leaving the values of all other actions
+/// unchanged. Updates are plain SGD with step size `alpha`.
+///
+/// The weights are stored with float type `F`, which defaults to `f64`. Using
+/// `f32` halves the memory footprint for large (e.g. tile coded) bases at the
+/// cost of precision; features, predictions and errors remain `f64`. Only the
+/// `f64` representation is [`Parameterised`].
+#[derive(Clone, Debug)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate")
+)]
+pub struct SimpleQLFA<B, F = f64> {
+    pub basis: B,
+
+    /// Weight matrix, indexed by `(feature, action)`.
+    pub weights: Array2<F>,
+
+    pub alpha: f64,
+}
+
+impl<B: Space> SimpleQLFA<B> {
+    pub fn new(basis: B, alpha: f64, n_actions: usize) -> Self {
+        SimpleQLFA::zeros(basis, alpha, n_actions)
+    }
+}
+
+impl<B: Space, F: NdFloat> SimpleQLFA<B, F> {
+    /// Construct a new instance with zeroed weights of float type `F`, e.g.
+    /// `SimpleQLFA::<_, f32>::zeros(basis, alpha, n_actions)`.
+    pub fn zeros(basis: B, alpha: f64, n_actions: usize) -> Self {
+        let n_features: usize = basis.dim().into();
+
+        SimpleQLFA {
+            basis,
+            weights: Array2::zeros((n_features, n_actions)),
+            alpha,
+        }
+    }
+}
+
+impl<B> Parameterised for SimpleQLFA<B> {
+    fn weights_view(&self) -> WeightsView<'_> { self.weights.view() }
+
+    fn weights_view_mut(&mut self) -> WeightsViewMut<'_> { self.weights.view_mut() }
+}
+
+impl<B, F: NdFloat> SimpleQLFA<B, F> {
+    /// Return the number of actions.
+    pub fn n_actions(&self) -> usize { self.weights.ncols() }
+
+    fn project<S>(&self, s: S) -> Result<Vec<(usize, f64)>>
+    where B: Basis<S, Value = Features> {
+        Ok(self.basis.project(s)?.iter_active().collect())
+    }
+
+    fn dot(&self, phi: &[(usize, f64)], action: usize) -> f64 {
+        phi.iter()
+            .map(|&(i, x)| self.weights[(i, action)].to_f64().unwrap() * x)
+            .sum()
+    }
+}
+
+impl<S, B, F> Function<(S,)> for SimpleQLFA<B, F>
+where
+    B: Basis<S, Value = Features>,
+    F: NdFloat,
+{
+    type Output = Vec<f64>;
+
+    fn evaluate(&self, (s,): (S,)) -> Vec<f64> {
+        let phi = self.project(s).unwrap();
+
+        (0..self.n_actions()).map(|a| self.dot(&phi, a)).collect()
+    }
+}
+
+impl<S, B, F> Enumerable<(S,)> for SimpleQLFA<B, F>
+where
+    B: Basis<S, Value = Features>,
+    F: NdFloat,
+{
+    fn len(&self, _: (S,)) -> usize { self.n_actions() }
+
+    fn evaluate_index(&self, (s,): (S,), index: usize) -> f64 {
+        self.dot(&self.project(s).unwrap(), index)
+    }
+}
+
+impl<S, A, B, F> Function<(S, A)> for SimpleQLFA<B, F>
+where
+    A: Borrow<usize>,
+    B: Basis<S, Value = Features>,
+    F: NdFloat,
+{
+    type Output = f64;
+
+    fn evaluate(&self, (s, a): (S, A)) -> f64 { self.evaluate_index((s,), *a.borrow()) }
+}
+
+impl<S, A, B, F> Handler<StateActionUpdate<S, A, f64>> for SimpleQLFA<B, F>
+where
+    A: Borrow<usize>,
+    B: Basis<S, Value = Features>,
+    F: NdFloat,
+{
+    type Response = ();
+    type Error = crate::fa::linear::Error;
+
+    fn handle(&mut self, msg: StateActionUpdate<S, A, f64>) -> Result<()> {
+        let a = *msg.action.borrow();
+        let scale = self.alpha * msg.error;
+
+        for (i, x) in self.project(msg.state)? {
+            self.weights[(i, a)] += F::from(scale * x).unwrap();
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        fa::linear::{
+            basis::{BoundedTileCoding, Closure},
+            Error,
+        },
+        QFunction,
+    };
+    use spaces::{real::Interval, ProductSpace};
+
+    fn q_func() -> SimpleQLFA<BoundedTileCoding> {
+        let space =
+            ProductSpace::empty() + Interval::bounded(0.0, 1.0) + Interval::bounded(0.0, 1.0);
+
+        SimpleQLFA::new(BoundedTileCoding::new(space, &[4, 4], 4), 0.1, 3)
+    }
+
+    #[test]
+    fn test_update_is_action_local() {
+        let mut q = q_func();
+        let s = vec![0.3, 0.7];
+        let near = vec![0.35, 0.7];
+
+        assert_eq!(q.weights.dim(), (100, 3));
+        assert_eq!(q.action_values(s.clone()), vec![0.0; 3]);
+
+        q.update(s.clone(), 1, 1.0).unwrap();
+        q.update(s.clone(), 1, 1.0).unwrap();
+
+        let values = q.action_values(s.clone());
+
+        assert!(values[1] > 0.0);
+        assert_eq!(values[0], 0.0);
+        assert_eq!(values[2], 0.0);
+
+        // A nearby state shares some of the features of `s`.
+        let before = q.action_values(near.clone());
+
+        q.update(s, 2, -1.0).unwrap();
+
+        let after = q.action_values(near);
+
+        assert_eq!(before[0], after[0]);
+        assert_eq!(before[1], after[1]);
+        assert!(after[2] < before[2]);
+    }
+
+    #[test]
+    fn test_update_propagates_basis_error() {
+        let basis = Closure::new(2, |s: &usize| {
+            if *s < 2 {
+                Ok(Features::sparse(2, vec![(*s, 1.0)]))
+            } else {
+                Err(Error::index_error(*s, 2))
+            }
+        });
+        let mut q = SimpleQLFA::new(basis, 0.1, 2);
+
+        assert!(q
+            .handle(StateActionUpdate {
+                state: &2,
+                action: 0,
+                error: 1.0,
+            })
+            .is_err());
+        assert_eq!(q.weights, Array2::<f64>::zeros((2, 2)));
+
+        q.handle(StateActionUpdate {
+            state: &1,
+            action: 0,
+            error: 1.0,
+        })
+        .unwrap();
+
+        assert_eq!(q.weights[(1, 0)], 0.1);
+    }
+
+    #[test]
+    fn test_single_precision() {
+        let mut q32 = {
+            let q = q_func();
+
+            SimpleQLFA::<_, f32>::zeros(q.basis, 0.1, 3)
+        };
+        let mut q64 = q_func();
+
+        assert_eq!(q32.weights.dim(), q64.weights.dim());
+
+        let updates = [
+            (vec![0.1, 0.2], 0, 1.0),
+            (vec![0.8, 0.7], 1, -2.0),
+            (vec![0.12, 0.21], 0, 0.3),
+        ];
+
+        for _ in 0..10 {
+            for (s, a, error) in updates.iter() {
+                q32.update(s.clone(), *a, *error).unwrap();
+                q64.update(s.clone(), *a, *error).unwrap();
+            }
+        }
+
+        for s in [vec![0.1, 0.2], vec![0.8, 0.7], vec![0.5, 0.5]].iter() {
+            let qs32 = q32.action_values(s.clone());
+            let qs64 = q64.action_values(s.clone());
+
+            for (x, y) in qs32.into_iter().zip(qs64) {
+                assert!((x - y).abs() < 1e-5);
+            }
+        }
+    }
+}
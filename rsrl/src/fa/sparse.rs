@@ -0,0 +1,194 @@
+use crate::{
+    fa::{
+        linear::{basis::Basis, Features, FeaturesExt, Result},
+        StateActionUpdate,
+    },
+    Enumerable,
+    Function,
+    Handler,
+};
+use std::{borrow::Borrow, collections::HashMap};
+
+/// Linear action-value function with sparse weight storage.
+///
+/// Weights are held in a map keyed by `(feature, action)` and are allocated
+/// lazily, the first time an update touches them; unallocated weights are
+/// implicitly zero. With one-hot or tile-coded projections only a handful of
+/// features are active at a time, so the memory footprint scales with the
+/// number of visited features rather than the size of the basis. Updates are
+/// plain SGD with step size `alpha`.
+#[derive(Clone, Debug)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate")
+)]
+pub struct SparseLFA<B> {
+    pub basis: B,
+    pub alpha: f64,
+
+    n_actions: usize,
+    weights: HashMap<(usize, usize), f64>,
+}
+
+impl<B> SparseLFA<B> {
+    pub fn new(basis: B, alpha: f64, n_actions: usize) -> Self {
+        SparseLFA {
+            basis,
+            alpha,
+
+            n_actions,
+            weights: HashMap::new(),
+        }
+    }
+
+    /// Return the number of weights that have been allocated.
+    pub fn n_allocated(&self) -> usize { self.weights.len() }
+
+    /// Return the weight associated with a feature/action pair.
+    pub fn weight(&self, feature: usize, action: usize) -> f64 {
+        self.weights.get(&(feature, action)).cloned().unwrap_or(0.0)
+    }
+
+    fn project<S>(&self, s: S) -> Result<Vec<(usize, f64)>>
+    where B: Basis<S, Value = Features> {
+        Ok(self.basis.project(s)?.iter_active().collect())
+    }
+}
+
+impl<S, B> Function<(S,)> for SparseLFA<B>
+where B: Basis<S, Value = Features>
+{
+    type Output = Vec<f64>;
+
+    fn evaluate(&self, (s,): (S,)) -> Vec<f64> {
+        let phi = self.project(s).unwrap();
+
+        (0..self.n_actions)
+            .map(|a| phi.iter().map(|&(i, x)| self.weight(i, a) * x).sum())
+            .collect()
+    }
+}
+
+impl<S, B> Enumerable<(S,)> for SparseLFA<B>
+where B: Basis<S, Value = Features>
+{
+    fn len(&self, _: (S,)) -> usize { self.n_actions }
+
+    fn evaluate_index(&self, (s,): (S,), index: usize) -> f64 {
+        self.project(s).unwrap().into_iter().map(|(i, x)| self.weight(i, index) * x).sum()
+    }
+}
+
+impl<S, A, B> Function<(S, A)> for SparseLFA<B>
+where
+    A: Borrow<usize>,
+    B: Basis<S, Value = Features>,
+{
+    type Output = f64;
+
+    fn evaluate(&self, (s, a): (S, A)) -> f64 { self.evaluate_index((s,), *a.borrow()) }
+}
+
+impl<S, A, B> Handler<StateActionUpdate<S, A, f64>> for SparseLFA<B>
+where
+    A: Borrow<usize>,
+    B: Basis<S, Value = Features>,
+{
+    type Response = ();
+    type Error = crate::fa::linear::Error;
+
+    fn handle(&mut self, msg: StateActionUpdate<S, A, f64>) -> Result<()> {
+        let a = *msg.action.borrow();
+        let scale = self.alpha * msg.error;
+
+        for (i, x) in self.project(msg.state)? {
+            *self.weights.entry((i, a)).or_insert(0.0) += scale * x;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fa::linear::{
+        basis::{Closure, HashedTileCoding},
+        Error,
+    };
+    use ndarray::Array2;
+    use spaces::{real::Interval, ProductSpace};
+
+    #[test]
+    fn test_matches_dense() {
+        let space =
+            ProductSpace::empty() + Interval::bounded(0.0, 1.0) + Interval::bounded(0.0, 1.0);
+        let basis = HashedTileCoding::new(space, &[8, 8], 4, 100_000);
+
+        let mut sparse = SparseLFA::new(basis.clone(), 0.1, 2);
+        let mut dense = Array2::<f64>::zeros((100_000, 2));
+
+        let updates = [([0.1, 0.2], 0, 1.0), ([0.8, 0.7], 1, -2.0), ([0.12, 0.21], 0, 0.5)];
+
+        for &(x, a, error) in updates.iter() {
+            sparse
+                .handle(StateActionUpdate {
+                    state: &x[..],
+                    action: a,
+                    error,
+                })
+                .unwrap();
+
+            let phi = basis.project(&x[..]).unwrap().into_dense();
+
+            dense.column_mut(a).scaled_add(0.1 * error, &phi);
+        }
+
+        // 4 tilings, each update touching one tile per tiling.
+        assert!(sparse.n_allocated() <= 12);
+        assert!(sparse.n_allocated() >= 8);
+
+        for x in [[0.1, 0.2], [0.8, 0.7], [0.5, 0.5]].iter() {
+            let phi = basis.project(&x[..]).unwrap().into_dense();
+            let qs = sparse.evaluate((&x[..],));
+
+            for (a, q) in qs.into_iter().enumerate() {
+                let q_dense: f64 = phi.iter().zip(dense.column(a)).map(|(p, w)| p * w).sum();
+
+                assert!((q - q_dense).abs() < 1e-12);
+                assert!((sparse.evaluate((&x[..], a)) - q_dense).abs() < 1e-12);
+            }
+        }
+    }
+
+    #[test]
+    fn test_update_propagates_basis_error() {
+        let basis = Closure::new(2, |s: &usize| {
+            if *s < 2 {
+                Ok(Features::sparse(2, vec![(*s, 1.0)]))
+            } else {
+                Err(Error::index_error(*s, 2))
+            }
+        });
+        let mut q = SparseLFA::new(basis, 0.1, 2);
+
+        assert!(q
+            .handle(StateActionUpdate {
+                state: &2,
+                action: 0,
+                error: 1.0,
+            })
+            .is_err());
+        assert_eq!(q.n_allocated(), 0);
+
+        q.handle(StateActionUpdate {
+            state: &1,
+            action: 0,
+            error: 1.0,
+        })
+        .unwrap();
+
+        assert_eq!(q.weight(1, 0), 0.1);
+    }
+}
@@ -1,6 +1,7 @@
 use super::Table;
 use crate::{
-    fa::{StateActionUpdate, StateUpdate},
+    fa::{GradientUpdate, ScaledGradientUpdate, StateActionUpdate, StateUpdate},
+    params::Buffer,
     Differentiable,
     Enumerable,
     Function,
@@ -19,6 +20,31 @@ impl<D: Dimension> From<Array<f64, D>> for Table<Array<f64, D>> {
     fn from(w: Array<f64, D>) -> Table<Array<f64, D>> { Table::dense(w) }
 }
 
+///////////////////////////////////////////////////////////////////////////////////////////////////
+// Common
+///////////////////////////////////////////////////////////////////////////////////////////////////
+impl<D: Dimension, J: Buffer<Dim = D>> Handler<GradientUpdate<J>> for Table<Array<f64, D>> {
+    type Response = super::Response;
+    type Error = super::Error;
+
+    fn handle(&mut self, msg: GradientUpdate<J>) -> Result<Self::Response, Self::Error> {
+        msg.0.addto(&mut self.0);
+
+        Ok(super::Response)
+    }
+}
+
+impl<D: Dimension, J: Buffer<Dim = D>> Handler<ScaledGradientUpdate<J>> for Table<Array<f64, D>> {
+    type Response = super::Response;
+    type Error = super::Error;
+
+    fn handle(&mut self, msg: ScaledGradientUpdate<J>) -> Result<Self::Response, Self::Error> {
+        msg.jacobian.scaled_addto(msg.alpha, &mut self.0);
+
+        Ok(super::Response)
+    }
+}
+
 ///////////////////////////////////////////////////////////////////////////////////////////////////
 // Implement V(s)
 ///////////////////////////////////////////////////////////////////////////////////////////////////
@@ -125,3 +151,31 @@ impl<S: Borrow<usize>, A: Borrow<usize>> Handler<StateActionUpdate<S, A>> for Ta
         Ok(super::Response)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::QFunction;
+
+    #[test]
+    fn test_greedy_action() {
+        let q = Table::dense(ndarray::arr2(&[[0.5, 2.0, -1.0], [3.0, 3.0, 1.0]]));
+
+        assert_eq!(q.greedy_action(0), 1);
+        assert_eq!(q.max_value(0), 2.0);
+
+        // Ties are broken in favour of the lowest index.
+        assert_eq!(q.greedy_action(1), 0);
+        assert_eq!(q.max_value(1), 3.0);
+
+        // Masked actions are never selected, even if they have higher values.
+        assert_eq!(q.greedy_action_among(0, &[0, 2]), 0);
+        assert_eq!(q.max_value_among(0, &[0, 2]), 0.5);
+        assert_eq!(q.greedy_action_among(1, &[2, 1]), 1);
+        assert_eq!(q.max_value_among(1, &[2]), 1.0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_no_legal_actions() { Table::dense(Array2::zeros((1, 2))).greedy_action_among(0, &[]); }
+}
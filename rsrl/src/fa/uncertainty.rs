@@ -0,0 +1,124 @@
+use crate::{
+    fa::StateUpdate,
+    params::{Buffer, Parameterised},
+    Differentiable,
+    Function,
+    Handler,
+};
+use ndarray::{Array1, Ix1};
+
+/// Value function wrapper that tracks the uncertainty of its predictions.
+///
+/// The wrapper maintains a diagonal approximation to the posterior precision
+/// of the weights of a (linear) value function, in the style of Bayesian
+/// linear regression. Each update adds the squared gradient at the visited
+/// state to the precision, so the predictive variance,
+/// `σ² Σ_i φ_i(s)² / (λ + P_i)`, shrinks for states (and features) that have
+/// been seen often. This makes it suitable for optimism-based exploration,
+/// e.g. UCB-style bonuses over state values.
+#[derive(Clone, Debug, Parameterised)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate")
+)]
+pub struct Uncertain<F> {
+    #[weights]
+    pub fa: F,
+
+    /// Prior precision of each weight, `λ`.
+    pub prior_precision: f64,
+
+    /// Observation noise variance, `σ²`.
+    pub noise_variance: f64,
+
+    precision: Array1<f64>,
+}
+
+impl<F: Parameterised> Uncertain<F> {
+    pub fn new(fa: F, prior_precision: f64, noise_variance: f64) -> Self {
+        let n = fa.weights_dim().0;
+
+        Uncertain {
+            fa,
+
+            prior_precision,
+            noise_variance,
+
+            precision: Array1::zeros(n),
+        }
+    }
+
+    /// Return the accumulated (data) precision associated with each weight.
+    pub fn precision(&self) -> &Array1<f64> { &self.precision }
+
+    /// Return the value prediction at `s` along with its variance.
+    pub fn evaluate_with_variance<'s, S>(&self, s: &'s S) -> (f64, f64)
+    where
+        F: Function<(&'s S,), Output = f64> + Differentiable<(&'s S,)>,
+        F::Jacobian: Buffer<Dim = Ix1>,
+    {
+        let phi = self.fa.grad((s,)).into_dense();
+        let variance = phi
+            .iter()
+            .zip(self.precision.iter())
+            .map(|(g, p)| g * g / (self.prior_precision + p))
+            .sum::<f64>();
+
+        (self.fa.evaluate((s,)), self.noise_variance * variance)
+    }
+}
+
+impl<Args, F: Function<Args>> Function<Args> for Uncertain<F> {
+    type Output = F::Output;
+
+    fn evaluate(&self, args: Args) -> F::Output { self.fa.evaluate(args) }
+}
+
+impl<'s, S, F> Handler<StateUpdate<&'s S, f64>> for Uncertain<F>
+where
+    F: Differentiable<(&'s S,)> + Handler<StateUpdate<&'s S, f64>>,
+    F::Jacobian: Buffer<Dim = Ix1>,
+{
+    type Response = F::Response;
+    type Error = F::Error;
+
+    fn handle(&mut self, msg: StateUpdate<&'s S, f64>) -> Result<Self::Response, Self::Error> {
+        let phi = self.fa.grad((msg.state,)).into_dense();
+
+        self.precision.zip_mut_with(&phi, |p, g| *p += g * g);
+        self.fa.handle(msg)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fa::tabular::Table;
+
+    #[test]
+    fn test_variance_shrinks_with_visits() {
+        let mut v = Uncertain::new(Table::dense(Array1::zeros(2)), 1.0, 1.0);
+
+        assert_eq!(v.evaluate_with_variance(&0usize), (0.0, 1.0));
+
+        let mut variances = vec![];
+
+        for _ in 0..5 {
+            v.handle(StateUpdate {
+                state: &0usize,
+                error: 0.5,
+            })
+            .unwrap();
+
+            variances.push(v.evaluate_with_variance(&0usize).1);
+        }
+
+        assert!(variances.windows(2).all(|w| w[1] < w[0]));
+        assert!((variances[4] - 1.0 / 6.0).abs() < 1e-10);
+
+        // The unvisited state retains the prior variance.
+        assert_eq!(v.evaluate_with_variance(&1usize), (0.0, 1.0));
+        assert_eq!(v.evaluate((&0usize,)), 2.5);
+    }
+}
@@ -11,9 +11,14 @@ extern crate ndarray_linalg;
 extern crate rand;
 extern crate rand_distr;
 
+#[cfg(all(test, feature = "blas"))]
+extern crate openblas_src;
+
 #[cfg_attr(feature = "serde", macro_use)]
 #[cfg(feature = "serde")]
 extern crate serde_crate;
+#[cfg(feature = "json")]
+extern crate serde_json;
 
 #[allow(unused_imports)]
 #[macro_use]
@@ -34,7 +39,12 @@ pub extern crate spaces;
 pub mod params;
 #[macro_use]
 pub mod fa;
+pub mod schedule;
 pub mod traces;
+pub mod replay;
+pub mod logging;
+pub mod experiment;
 pub mod prediction;
 pub mod control;
+pub mod planning;
 pub mod policies;
@@ -0,0 +1,331 @@
+//! Learning curve and value function logging.
+use crate::{
+    domains::Domain,
+    spaces::{real::Interval, BoundedSpace, ProductSpace},
+    Function,
+};
+use ndarray::Array2;
+use std::io::{self, Write};
+
+/// Summary statistics for a single episode.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate")
+)]
+pub struct EpisodeStats {
+    /// Index of the episode.
+    pub episode: usize,
+
+    /// Sum of rewards received during the episode.
+    pub total_reward: f64,
+
+    /// Number of steps taken in the episode.
+    pub steps: usize,
+}
+
+/// Mean, standard deviation and range of a sample.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate")
+)]
+pub struct Summary {
+    pub mean: f64,
+
+    /// Population standard deviation.
+    pub std: f64,
+
+    pub min: f64,
+    pub max: f64,
+}
+
+impl Summary {
+    fn from_iter(xs: impl Iterator<Item = f64> + Clone) -> Summary {
+        let n = xs.clone().count() as f64;
+        let mean = xs.clone().sum::<f64>() / n;
+        let var = xs.clone().map(|x| (x - mean).powi(2)).sum::<f64>() / n;
+
+        Summary {
+            mean,
+            std: var.sqrt(),
+            min: xs.clone().fold(f64::INFINITY, f64::min),
+            max: xs.fold(f64::NEG_INFINITY, f64::max),
+        }
+    }
+}
+
+/// Summary statistics over a sequence of episodes.
+///
+/// # Example
+///
+/// ```
+/// use rsrl::logging::{EpisodeStats, Statistics};
+///
+/// let episodes: Vec<EpisodeStats> = [(-3.0, 3), (-1.0, 1), (-2.0, 2)]
+///     .iter()
+///     .enumerate()
+///     .map(|(episode, &(total_reward, steps))| EpisodeStats {
+///         episode,
+///         total_reward,
+///         steps,
+///     })
+///     .collect();
+///
+/// let stats = Statistics::from_episodes(&episodes);
+///
+/// assert_eq!(stats.n_episodes, 3);
+/// assert_eq!(stats.returns.mean, -2.0);
+/// assert_eq!(stats.steps.max, 3.0);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate")
+)]
+pub struct Statistics {
+    /// Number of episodes summarised.
+    pub n_episodes: usize,
+
+    /// Summary of the total reward of each episode.
+    pub returns: Summary,
+
+    /// Summary of the number of steps taken in each episode.
+    pub steps: Summary,
+}
+
+impl Statistics {
+    /// Summarise the given episodes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `episodes` is empty.
+    pub fn from_episodes(episodes: &[EpisodeStats]) -> Statistics {
+        assert!(!episodes.is_empty(), "Statistics requires at least one episode.");
+
+        Statistics {
+            n_episodes: episodes.len(),
+            returns: Summary::from_iter(episodes.iter().map(|e| e.total_reward)),
+            steps: Summary::from_iter(episodes.iter().map(|e| e.steps as f64)),
+        }
+    }
+
+    /// Summarise each run of `window` consecutive episodes, in order.
+    ///
+    /// Entry `i` of the output covers episodes `i` to `i + window - 1`, so
+    /// nothing is returned if there are fewer than `window` episodes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `window` is zero.
+    pub fn rolling(episodes: &[EpisodeStats], window: usize) -> Vec<Statistics> {
+        assert!(window > 0, "Window must contain at least one episode.");
+
+        episodes.windows(window).map(Statistics::from_episodes).collect()
+    }
+}
+
+/// Logger that writes one CSV row of [`EpisodeStats`] per episode.
+///
+/// The current learning and exploration rates are agent specific, so they are
+/// passed alongside the [`EpisodeStats`] of each episode. Missing values (e.g.
+/// `epsilon` for an on-policy prediction agent) are written as empty fields.
+///
+/// # Example
+///
+/// ```
+/// use rsrl::logging::{CsvLogger, EpisodeStats};
+///
+/// let mut logger = CsvLogger::new(Vec::new()).unwrap();
+///
+/// let stats = EpisodeStats {
+///     episode: 0,
+///     total_reward: -10.0,
+///     steps: 10,
+/// };
+///
+/// logger.record(&stats, Some(0.1), None).unwrap();
+///
+/// let csv = String::from_utf8(logger.into_inner()).unwrap();
+///
+/// assert_eq!(csv, "episode,total_reward,steps,alpha,epsilon\n0,-10,10,0.1,\n");
+/// ```
+pub struct CsvLogger<W: Write> {
+    writer: W,
+}
+
+impl<W: Write> CsvLogger<W> {
+    /// Column names written as the first row.
+    pub const HEADER: [&'static str; 5] =
+        ["episode", "total_reward", "steps", "alpha", "epsilon"];
+
+    /// Construct a new logger, writing the CSV header to `writer`.
+    pub fn new(mut writer: W) -> io::Result<Self> {
+        writeln!(writer, "{}", Self::HEADER.join(","))?;
+
+        Ok(CsvLogger { writer })
+    }
+
+    /// Append a row for the given episode, with the current learning rate,
+    /// `alpha`, and exploration rate, `epsilon`, if applicable.
+    pub fn record(
+        &mut self,
+        stats: &EpisodeStats,
+        alpha: Option<f64>,
+        epsilon: Option<f64>,
+    ) -> io::Result<()>
+    {
+        fn optional(x: Option<f64>) -> String { x.map(|x| x.to_string()).unwrap_or_default() }
+
+        writeln!(
+            self.writer,
+            "{},{},{},{},{}",
+            stats.episode,
+            stats.total_reward,
+            stats.steps,
+            optional(alpha),
+            optional(epsilon),
+        )
+    }
+
+    /// Flush the underlying writer.
+    pub fn flush(&mut self) -> io::Result<()> { self.writer.flush() }
+
+    /// Consume the logger and return the underlying writer.
+    pub fn into_inner(self) -> W { self.writer }
+}
+
+/// Evaluate a value function over a regular grid spanning the state space of
+/// a two-dimensional domain, e.g. for plotting a heatmap.
+///
+/// Each dimension is sampled at `resolution` evenly spaced points from its
+/// lower to its upper bound inclusive (or at the midpoint if the resolution
+/// is one), and entry `[i, j]` of the output holds the value at the `i`th
+/// point along the first dimension and the `j`th along the second. For
+/// action-value functions, pass e.g. a closure returning the maximum over
+/// actions.
+///
+/// # Panics
+///
+/// Panics if the state space is not two-dimensional, or if either dimension
+/// is unbounded.
+pub fn value_grid<D, V>(domain: &D, v_func: &V, resolution: [usize; 2]) -> Array2<f64>
+where
+    D: Domain<StateSpace = ProductSpace<Interval>>,
+    V: for<'s> Function<(&'s Vec<f64>,), Output = f64>,
+{
+    let space = domain.state_space();
+
+    assert_eq!(space.iter().len(), 2, "value_grid requires a two-dimensional state space.");
+
+    let points: Vec<Vec<f64>> = space
+        .iter()
+        .zip(resolution.iter())
+        .map(|(d, &n)| {
+            let (lb, ub) = match (d.inf(), d.sup()) {
+                (Some(lb), Some(ub)) => (lb, ub),
+                _ => panic!("value_grid requires a bounded state space."),
+            };
+
+            if n == 1 {
+                vec![(lb + ub) / 2.0]
+            } else {
+                (0..n).map(|i| lb + (ub - lb) * i as f64 / (n - 1) as f64).collect()
+            }
+        })
+        .collect();
+
+    Array2::from_shape_fn((resolution[0], resolution[1]), |(i, j)| {
+        v_func.evaluate((&vec![points[0][i], points[1][j]],))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domains::MountainCar;
+
+    #[test]
+    fn test_value_grid() {
+        let v = |(s,): (&Vec<f64>,)| s[0] + s[1];
+        let grid = value_grid(&MountainCar::default(), &v, [5, 3]);
+
+        assert_eq!(grid.dim(), (5, 3));
+
+        // Position spans [-1.2, 0.6] and velocity [-0.07, 0.07].
+        assert!((grid[[0, 0]] - (-1.2 - 0.07)).abs() < 1e-10);
+        assert!((grid[[4, 2]] - (0.6 + 0.07)).abs() < 1e-10);
+        assert!((grid[[2, 1]] - (-0.3)).abs() < 1e-10);
+
+        assert_eq!(value_grid(&MountainCar::default(), &v, [1, 7]).dim(), (1, 7));
+    }
+
+    fn episodes(returns: &[f64]) -> Vec<EpisodeStats> {
+        returns
+            .iter()
+            .enumerate()
+            .map(|(episode, &total_reward)| EpisodeStats {
+                episode,
+                total_reward,
+                steps: 10 * (episode + 1),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_statistics() {
+        let returns = [2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0];
+        let stats = Statistics::from_episodes(&episodes(&returns));
+
+        assert_eq!(stats.n_episodes, 8);
+        assert_eq!(stats.returns, Summary {
+            mean: 5.0,
+            std: 2.0,
+            min: 2.0,
+            max: 9.0,
+        });
+
+        assert_eq!(stats.steps.mean, 45.0);
+        assert!((stats.steps.std - 525.0f64.sqrt()).abs() < 1e-10);
+        assert_eq!((stats.steps.min, stats.steps.max), (10.0, 80.0));
+    }
+
+    #[test]
+    fn test_rolling_statistics() {
+        let rolling = Statistics::rolling(&episodes(&[1.0, 3.0, 5.0, 7.0]), 2);
+        let means: Vec<f64> = rolling.iter().map(|s| s.returns.mean).collect();
+
+        assert_eq!(means, vec![2.0, 4.0, 6.0]);
+        assert!(rolling.iter().all(|s| s.n_episodes == 2 && s.returns.std == 1.0));
+
+        assert!(Statistics::rolling(&episodes(&[1.0]), 2).is_empty());
+    }
+
+    #[test]
+    fn test_header_and_row() {
+        let mut logger = CsvLogger::new(Vec::new()).unwrap();
+
+        let stats = EpisodeStats {
+            episode: 3,
+            total_reward: 12.5,
+            steps: 42,
+        };
+
+        logger.record(&stats, Some(0.01), Some(0.2)).unwrap();
+
+        let csv = String::from_utf8(logger.into_inner()).unwrap();
+        let rows: Vec<Vec<&str>> = csv.lines().map(|l| l.split(',').collect()).collect();
+
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0], CsvLogger::<Vec<u8>>::HEADER.to_vec());
+
+        assert_eq!(rows[1][0].parse::<usize>().unwrap(), 3);
+        assert_eq!(rows[1][1].parse::<f64>().unwrap(), 12.5);
+        assert_eq!(rows[1][2].parse::<usize>().unwrap(), 42);
+        assert_eq!(rows[1][3].parse::<f64>().unwrap(), 0.01);
+        assert_eq!(rows[1][4].parse::<f64>().unwrap(), 0.2);
+    }
+}
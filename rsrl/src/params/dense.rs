@@ -1,86 +1,86 @@
 use super::*;
-use ndarray::{Array1, Array2, ArrayBase, Data, DataMut, Ix1, Ix2, IntoDimension};
+use ndarray::{Array1, Array2, ArrayBase, Data, DataMut, Ix1, Ix2, IntoDimension, NdFloat};
 use std::ops::AddAssign;
 
 pub type Vector = Array1<f64>;
 pub type Matrix = Array2<f64>;
 
-impl<D: Data<Elem = f64>> Buffer for ArrayBase<D, Ix1> {
+impl<F: NdFloat, D: Data<Elem = F>> Buffer<F> for ArrayBase<D, Ix1> {
     type Dim = Ix1;
 
     fn raw_dim(&self) -> Ix1 { self.raw_dim() }
 
-    fn addto<DM: DataMut<Elem = f64>>(&self, weights: &mut ArrayBase<DM, Ix1>) {
+    fn addto<DM: DataMut<Elem = F>>(&self, weights: &mut ArrayBase<DM, Ix1>) {
         weights.add_assign(self);
     }
 
-    fn scaled_addto<DM: DataMut<Elem = f64>>(&self, alpha: f64, weights: &mut ArrayBase<DM, Ix1>) {
+    fn scaled_addto<DM: DataMut<Elem = F>>(&self, alpha: F, weights: &mut ArrayBase<DM, Ix1>) {
         weights.scaled_add(alpha, self);
     }
 
-    fn to_dense(&self) -> Array1<f64> { self.to_owned() }
+    fn to_dense(&self) -> Array1<F> { self.to_owned() }
 }
 
-impl BufferMut for Array1<f64> {
+impl<F: NdFloat> BufferMut<F> for Array1<F> {
     fn zeros<D: IntoDimension<Dim = Ix1>>(dim: D) -> Self { Self::zeros(dim) }
 
-    fn map(&self, f: impl Fn(f64) -> f64) -> Self { self.mapv(f) }
+    fn map(&self, f: impl Fn(F) -> F) -> Self { self.mapv(f) }
 
-    fn map_into(self, f: impl Fn(f64) -> f64) -> Self { self.mapv_into(f) }
+    fn map_into(self, f: impl Fn(F) -> F) -> Self { self.mapv_into(f) }
 
-    fn map_inplace(&mut self, f: impl Fn(f64) -> f64) { self.mapv_inplace(f); }
+    fn map_inplace(&mut self, f: impl Fn(F) -> F) { self.mapv_inplace(f); }
 
-    fn merge(&self, other: &Self, f: impl Fn(f64, f64) -> f64) -> Self {
+    fn merge(&self, other: &Self, f: impl Fn(F, F) -> F) -> Self {
         // TODO: this can be implemented much more efficiently.
         self.clone().merge_into(other, f)
     }
 
-    fn merge_into(mut self, other: &Self, f: impl Fn(f64, f64) -> f64) -> Self {
+    fn merge_into(mut self, other: &Self, f: impl Fn(F, F) -> F) -> Self {
         self.merge_inplace(other, f);
         self
     }
 
-    fn merge_inplace(&mut self, other: &Self, f: impl Fn(f64, f64) -> f64) {
+    fn merge_inplace(&mut self, other: &Self, f: impl Fn(F, F) -> F) {
         self.zip_mut_with(other, |x, y| *x = f(*x, *y));
     }
 }
 
-impl<D: Data<Elem = f64>> Buffer for ArrayBase<D, Ix2> {
+impl<F: NdFloat, D: Data<Elem = F>> Buffer<F> for ArrayBase<D, Ix2> {
     type Dim = Ix2;
 
     fn raw_dim(&self) -> Ix2 { self.raw_dim() }
 
-    fn addto<DM: DataMut<Elem = f64>>(&self, weights: &mut ArrayBase<DM, Ix2>) {
+    fn addto<DM: DataMut<Elem = F>>(&self, weights: &mut ArrayBase<DM, Ix2>) {
         weights.add_assign(self);
     }
 
-    fn scaled_addto<DM: DataMut<Elem = f64>>(&self, alpha: f64, weights: &mut ArrayBase<DM, Ix2>) {
+    fn scaled_addto<DM: DataMut<Elem = F>>(&self, alpha: F, weights: &mut ArrayBase<DM, Ix2>) {
         weights.scaled_add(alpha, self);
     }
 
-    fn to_dense(&self) -> Array2<f64> { self.to_owned() }
+    fn to_dense(&self) -> Array2<F> { self.to_owned() }
 }
 
-impl BufferMut for Array2<f64> {
+impl<F: NdFloat> BufferMut<F> for Array2<F> {
     fn zeros<D: IntoDimension<Dim = Ix2>>(dim: D) -> Self { Self::zeros(dim) }
 
-    fn map(&self, f: impl Fn(f64) -> f64) -> Self { self.mapv(f) }
+    fn map(&self, f: impl Fn(F) -> F) -> Self { self.mapv(f) }
 
-    fn map_into(self, f: impl Fn(f64) -> f64) -> Self { self.mapv_into(f) }
+    fn map_into(self, f: impl Fn(F) -> F) -> Self { self.mapv_into(f) }
 
-    fn map_inplace(&mut self, f: impl Fn(f64) -> f64) { self.mapv_inplace(f); }
+    fn map_inplace(&mut self, f: impl Fn(F) -> F) { self.mapv_inplace(f); }
 
-    fn merge(&self, other: &Self, f: impl Fn(f64, f64) -> f64) -> Self {
+    fn merge(&self, other: &Self, f: impl Fn(F, F) -> F) -> Self {
         // TODO: this can be implemented much more efficiently.
         self.clone().merge_into(other, f)
     }
 
-    fn merge_into(mut self, other: &Self, f: impl Fn(f64, f64) -> f64) -> Self {
+    fn merge_into(mut self, other: &Self, f: impl Fn(F, F) -> F) -> Self {
         self.merge_inplace(other, f);
         self
     }
 
-    fn merge_inplace(&mut self, other: &Self, f: impl Fn(f64, f64) -> f64) {
+    fn merge_inplace(&mut self, other: &Self, f: impl Fn(F, F) -> F) {
         self.zip_mut_with(other, |x, y| *x = f(*x, *y));
     }
 }
@@ -1,8 +1,12 @@
 use crate::Shared;
-use ndarray::{Array, Array2, ArrayBase, DataMut, Dimension, IntoDimension};
+use ndarray::{Array, Array2, ArrayBase, Data, DataMut, Dimension, IntoDimension, NdFloat};
 
 /// Gradient buffer with arbitrary dimension.
-pub trait Buffer: Sized {
+///
+/// Buffers are generic over their floating point type, `F`, which defaults to
+/// `f64`; dense buffers may also be populated with `f32` to halve their memory
+/// footprint.
+pub trait Buffer<F: NdFloat = f64>: Sized {
     /// Dimensionality od the the buffer.
     type Dim: Dimension;
 
@@ -16,18 +20,33 @@ pub trait Buffer: Sized {
     fn raw_dim(&self) -> Self::Dim;
 
     /// Add the buffer's state to a mutable tensor of equal dimensionality.
-    fn addto<E: DataMut<Elem = f64>>(&self, arr: &mut ArrayBase<E, Self::Dim>) {
-        self.scaled_addto(1.0, arr)
+    fn addto<E: DataMut<Elem = F>>(&self, arr: &mut ArrayBase<E, Self::Dim>) {
+        self.scaled_addto(F::one(), arr)
     }
 
     /// Add the buffer's state (scaled) to a mutable tensor of equal dimensionality.
     ///
     /// This is a common operation in SGD-type methods and can typically be implemented in a highly
     /// optimised form compared to a pair of addition/scale mutations.
-    fn scaled_addto<E: DataMut<Elem = f64>>(&self, alpha: f64, arr: &mut ArrayBase<E, Self::Dim>);
+    fn scaled_addto<E: DataMut<Elem = F>>(&self, alpha: F, arr: &mut ArrayBase<E, Self::Dim>);
+
+    /// Compute the inner product between the buffer's state and a tensor of equal
+    /// dimensionality.
+    fn dot<E: Data<Elem = F>>(&self, arr: &ArrayBase<E, Self::Dim>) -> F {
+        self.to_dense().iter().zip(arr.iter()).fold(F::zero(), |acc, (&x, &y)| acc + x * y)
+    }
+
+    /// Return the Euclidean norm of the buffer's state.
+    fn l2_norm(&self) -> F { self.to_dense().iter().fold(F::zero(), |acc, &x| acc + x * x).sqrt() }
+
+    /// Return the number of active components of the buffer.
+    ///
+    /// Dense buffers count their non-zero entries; sparse buffers count their
+    /// stored entries, which may include explicit zeros.
+    fn n_active(&self) -> usize { self.to_dense().iter().filter(|x| !x.is_zero()).count() }
 
     /// Construct a dense tensor representation from the `Buffer` state.
-    fn to_dense(&self) -> Array<f64, Self::Dim> {
+    fn to_dense(&self) -> Array<F, Self::Dim> {
         let mut arr = Array::zeros(self.dim());
 
         self.addto(&mut arr);
@@ -36,10 +55,10 @@ pub trait Buffer: Sized {
     }
 
     /// Convert the `Buffer` into a dense tensor.
-    fn into_dense(self) -> Array<f64, Self::Dim> { self.to_dense() }
+    fn into_dense(self) -> Array<F, Self::Dim> { self.to_dense() }
 }
 
-impl<T: Buffer> Buffer for &T {
+impl<F: NdFloat, T: Buffer<F>> Buffer<F> for &T {
     type Dim = T::Dim;
 
     fn dim(&self) -> <Self::Dim as Dimension>::Pattern { (*self).dim() }
@@ -48,45 +67,59 @@ impl<T: Buffer> Buffer for &T {
 
     fn raw_dim(&self) -> Self::Dim { (*self).raw_dim() }
 
-    fn addto<E: DataMut<Elem = f64>>(&self, arr: &mut ArrayBase<E, Self::Dim>) {
+    fn addto<E: DataMut<Elem = F>>(&self, arr: &mut ArrayBase<E, Self::Dim>) {
         (*self).addto(arr)
     }
 
-    fn scaled_addto<E: DataMut<Elem = f64>>(&self, alpha: f64, arr: &mut ArrayBase<E, Self::Dim>) {
+    fn scaled_addto<E: DataMut<Elem = F>>(&self, alpha: F, arr: &mut ArrayBase<E, Self::Dim>) {
         (*self).scaled_addto(alpha, arr)
     }
 
-    fn to_dense(&self) -> Array<f64, Self::Dim> { (*self).to_dense() }
+    fn dot<E: Data<Elem = F>>(&self, arr: &ArrayBase<E, Self::Dim>) -> F { (*self).dot(arr) }
 
-    fn into_dense(self) -> Array<f64, Self::Dim> { self.to_dense() }
+    fn l2_norm(&self) -> F { (*self).l2_norm() }
+
+    fn n_active(&self) -> usize { (*self).n_active() }
+
+    fn to_dense(&self) -> Array<F, Self::Dim> { (*self).to_dense() }
+
+    fn into_dense(self) -> Array<F, Self::Dim> { self.to_dense() }
 }
 
-pub trait BufferMut: Buffer + Clone {
+pub trait BufferMut<F: NdFloat = f64>: Buffer<F> + Clone {
     fn zeros<D: IntoDimension<Dim = Self::Dim>>(dim: D) -> Self;
 
-    fn reset(&mut self) { self.map_inplace(|_| 0.0) }
+    fn reset(&mut self) { self.map_inplace(|_| F::zero()) }
 
-    fn map(&self, f: impl Fn(f64) -> f64) -> Self {
+    fn map(&self, f: impl Fn(F) -> F) -> Self {
         self.clone().map_into(f)
     }
 
-    fn map_into(mut self, f: impl Fn(f64) -> f64) -> Self {
+    fn map_into(mut self, f: impl Fn(F) -> F) -> Self {
         self.map_inplace(f);
         self
     }
 
-    fn map_inplace(&mut self, f: impl Fn(f64) -> f64);
+    fn map_inplace(&mut self, f: impl Fn(F) -> F);
 
-    fn merge(&self, other: &Self, f: impl Fn(f64, f64) -> f64) -> Self {
+    fn merge(&self, other: &Self, f: impl Fn(F, F) -> F) -> Self {
         self.clone().merge_into(other, f)
     }
 
-    fn merge_into(mut self, other: &Self, f: impl Fn(f64, f64) -> f64) -> Self {
+    fn merge_into(mut self, other: &Self, f: impl Fn(F, F) -> F) -> Self {
         self.merge_inplace(other, f);
         self
     }
 
-    fn merge_inplace(&mut self, other: &Self, f: impl Fn(f64, f64) -> f64);
+    fn merge_inplace(&mut self, other: &Self, f: impl Fn(F, F) -> F);
+
+    /// Zero out every component whose magnitude is below `threshold`.
+    ///
+    /// Sparse buffers drop such components altogether, such that they no
+    /// longer count towards [`Buffer::n_active`].
+    fn prune(&mut self, threshold: F) {
+        self.map_inplace(|x| if x.abs() < threshold { F::zero() } else { x })
+    }
 }
 
 mod dense;
@@ -100,7 +133,7 @@ pub use self::{
     sparse::Sparse,
 
     columnar::Columnar,
-    tile::Tile,
+    tile::{MultiTile, Tile},
 };
 
 /// Matrix populated with _owned_ weights.
@@ -131,6 +164,28 @@ pub trait Parameterised {
 
         r * c
     }
+
+    /// Add a (pre-scaled) gradient to the weights in place, `w <- w + g`.
+    ///
+    /// This allows external optimisers to drive the parameter updates.
+    ///
+    /// # Panics
+    /// If the shape of `grad` does not match `weights_dim`.
+    fn apply_gradient(&mut self, grad: &Matrix) {
+        assert_eq!(grad.dim(), self.weights_dim(), "Gradient shape must match the weights.");
+
+        self.weights_view_mut().scaled_add(1.0, grad);
+    }
+
+    /// Overwrite the weights with the values in `weights`.
+    ///
+    /// # Panics
+    /// If the shape of `weights` does not match `weights_dim`.
+    fn set_weights(&mut self, weights: &Matrix) {
+        assert_eq!(weights.dim(), self.weights_dim(), "Weight shapes must match.");
+
+        self.weights_view_mut().assign(weights);
+    }
 }
 
 impl<F: Parameterised> Parameterised for Shared<F> {
@@ -146,3 +201,45 @@ impl<F: Parameterised> Parameterised for Shared<F> {
 
     fn weights_dim(&self) -> (usize, usize) { self.borrow().weights_dim() }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fa::tabular::Table;
+
+    #[test]
+    fn test_apply_gradient() {
+        let mut table = Table::dense(array![[1.0, 2.0], [3.0, 4.0]]);
+
+        table.apply_gradient(&Array2::zeros((2, 2)));
+
+        assert_eq!(table.weights(), array![[1.0, 2.0], [3.0, 4.0]]);
+
+        table.apply_gradient(&array![[0.5, -1.0], [0.0, 2.0]]);
+
+        assert_eq!(table.weights(), array![[1.5, 1.0], [3.0, 6.0]]);
+
+        table.set_weights(&Array2::ones((2, 2)));
+
+        assert_eq!(table.weights(), Array2::<f64>::ones((2, 2)));
+    }
+
+    #[test]
+    fn test_single_precision_buffer() {
+        let mut x = ndarray::arr1(&[3.0f32, 0.05, -4.0]);
+
+        assert_eq!(Buffer::dot(&x, &ndarray::arr1(&[1.0, 0.0, 1.0])), -1.0f32);
+        assert_eq!(x.n_active(), 3);
+
+        x.prune(0.1);
+
+        assert_eq!(x.n_active(), 2);
+        assert_eq!(x.l2_norm(), 5.0f32);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_apply_gradient_shape_mismatch() {
+        Table::dense(Array2::<f64>::zeros((2, 2))).apply_gradient(&Array2::zeros((3, 2)));
+    }
+}
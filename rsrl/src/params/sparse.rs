@@ -69,6 +69,8 @@ impl Buffer for Sparse {
 
         g_matrix
     }
+
+    fn n_active(&self) -> usize { self.grads.len() }
 }
 
 impl BufferMut for Sparse {
@@ -107,6 +109,8 @@ impl BufferMut for Sparse {
             self.grads.entry(k).or_insert_with(|| f(0.0, *y));
         }
     }
+
+    fn prune(&mut self, threshold: f64) { self.grads.retain(|_, x| x.abs() >= threshold); }
 }
 
 impl Into<Array2<f64>> for Sparse {
@@ -1,6 +1,22 @@
-use ndarray::{ArrayBase, DataMut, NdIndex, IntoDimension};
+use ndarray::{ArrayBase, Data, DataMut, NdIndex, IntoDimension};
 
-#[derive(Copy, Clone, Debug)]
+#[derive(Clone, Debug)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate")
+)]
+enum Cells<I> {
+    Single(Option<(I, f64)>),
+    Multi(Vec<(I, f64)>),
+}
+
+/// Gradient buffer with (at most) a single active cell.
+///
+/// Merging tiles whose active cells differ retains the contributions of both,
+/// promoting the buffer to a multi-active representation; a tile that has
+/// never been merged in this way does not allocate.
+#[derive(Clone, Debug)]
 #[cfg_attr(
     feature = "serde",
     derive(Serialize, Deserialize),
@@ -8,14 +24,29 @@ use ndarray::{ArrayBase, DataMut, NdIndex, IntoDimension};
 )]
 pub struct Tile<D: ndarray::Dimension, I: NdIndex<D>> {
     dim: D,
-    active: Option<(I, f64)>,
+    cells: Cells<I>,
 }
 
 impl<D: ndarray::Dimension, I: NdIndex<D>> Tile<D, I> {
     pub fn new<T: IntoDimension<Dim = D>>(dim: T, active: Option<(I, f64)>) -> Self {
         Tile {
             dim: dim.into_dimension(),
-            active,
+            cells: Cells::Single(active),
+        }
+    }
+
+    /// Return the active cells and their activations.
+    pub fn active(&self) -> &[(I, f64)] {
+        match &self.cells {
+            Cells::Single(cell) => cell.as_slice(),
+            Cells::Multi(cells) => cells,
+        }
+    }
+
+    fn active_mut(&mut self) -> &mut [(I, f64)] {
+        match &mut self.cells {
+            Cells::Single(cell) => cell.as_mut_slice(),
+            Cells::Multi(cells) => cells,
         }
     }
 }
@@ -26,16 +57,23 @@ impl<D: ndarray::Dimension, I: NdIndex<D> + Clone> crate::params::Buffer for Til
     fn raw_dim(&self) -> D { self.dim.clone() }
 
     fn addto<E: DataMut<Elem = f64>>(&self, arr: &mut ArrayBase<E, Self::Dim>) {
-        if let Some((idx, activation)) = &self.active {
+        for (idx, activation) in self.active() {
             arr[idx.clone()] += activation;
         }
     }
 
     fn scaled_addto<E: DataMut<Elem = f64>>(&self, alpha: f64, arr: &mut ArrayBase<E, Self::Dim>) {
-        if let Some((idx, activation)) = &self.active {
+        for (idx, activation) in self.active() {
             arr[idx.clone()] += alpha * activation;
         }
     }
+
+    fn dot<E: Data<Elem = f64>>(&self, arr: &ArrayBase<E, Self::Dim>) -> f64 {
+        self.active().iter().map(|(idx, activation)| arr[idx.clone()] * activation).sum()
+    }
+
+    // Active cells are distinct, as merges combine entries sharing an index.
+    fn l2_norm(&self) -> f64 { self.active().iter().map(|(_, x)| x * x).sum::<f64>().sqrt() }
 }
 
 impl<D, I> crate::params::BufferMut for Tile<D, I>
@@ -45,40 +83,308 @@ where
 {
     fn zeros<T: IntoDimension<Dim = D>>(dim: T) -> Self { Tile::new(dim, None) }
 
-    fn map(&self, f: impl Fn(f64) -> f64) -> Self {
-        self.clone().map_into(f)
+    fn map_into(mut self, f: impl Fn(f64) -> f64) -> Self {
+        self.map_inplace(f);
+        self
     }
 
-    fn map_into(self, f: impl Fn(f64) -> f64) -> Self {
-        Tile {
-            dim: self.dim,
-            active: self.active.map(|(idx, a)| (idx, f(a))),
+    fn map_inplace(&mut self, f: impl Fn(f64) -> f64) {
+        self.active_mut().iter_mut().for_each(|(_, x)| *x = f(*x));
+    }
+
+    /// Merge `other` into `self` in place.
+    ///
+    /// If the tiles are active at different cells, the result retains both
+    /// contributions.
+    ///
+    /// # Panics
+    /// If the shapes differ.
+    fn merge_inplace(&mut self, other: &Self, f: impl Fn(f64, f64) -> f64) {
+        if self.dim != other.dim {
+            panic!("Incompatible buffers shapes.")
+        }
+
+        match (&mut self.cells, &other.cells) {
+            (Cells::Single(Some((i, x))), Cells::Single(Some((j, y)))) if i == j => {
+                *x = f(*x, *y)
+            },
+            (Cells::Single(Some((_, x))), Cells::Single(None)) => *x = f(*x, 0.0),
+            (Cells::Single(None), Cells::Single(Some((j, y)))) => {
+                self.cells = Cells::Single(Some((j.clone(), f(0.0, *y))))
+            },
+            (Cells::Single(None), Cells::Single(None)) => {},
+            _ => {
+                let multi = MultiTile::new(self.dim.clone(), self.active().to_vec())
+                    .merge_into(&MultiTile::new(other.dim.clone(), other.active().to_vec()), f);
+
+                self.cells = Cells::Multi(multi.active);
+            },
         }
     }
+}
 
-    fn map_inplace(&mut self, f: impl Fn(f64) -> f64) {
-        if let Some((_, x)) = &mut self.active {
-            *x = f(*x);
+/// Gradient buffer with several simultaneously active cells.
+///
+/// This generalises `Tile` to the case of tile coding with multiple tilings,
+/// where one cell is active per tiling. Entries sharing an index are treated
+/// additively.
+#[derive(Clone, Debug)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate")
+)]
+pub struct MultiTile<D: ndarray::Dimension, I: NdIndex<D>> {
+    dim: D,
+    active: Vec<(I, f64)>,
+}
+
+impl<D: ndarray::Dimension, I: NdIndex<D>> MultiTile<D, I> {
+    pub fn new<T: IntoDimension<Dim = D>>(dim: T, active: Vec<(I, f64)>) -> Self {
+        MultiTile {
+            dim: dim.into_dimension(),
+            active,
         }
     }
 
-    fn merge(&self, other: &Self, f: impl Fn(f64, f64) -> f64) -> Self {
-        self.clone().merge_into(other, f)
+    /// Return the active cells and their activations.
+    pub fn active(&self) -> &[(I, f64)] { &self.active }
+
+    /// Sum the activations of entries sharing an index into a single entry.
+    fn coalesce(&mut self)
+    where I: PartialEq {
+        let mut cells: Vec<(I, f64)> = Vec::with_capacity(self.active.len());
+
+        for (idx, activation) in self.active.drain(..) {
+            match cells.iter_mut().find(|(j, _)| *j == idx) {
+                Some((_, x)) => *x += activation,
+                None => cells.push((idx, activation)),
+            }
+        }
+
+        self.active = cells;
     }
 
-    fn merge_into(mut self, other: &Self, f: impl Fn(f64, f64) -> f64) -> Self {
-        self.merge_inplace(other, f);
+    fn coalesced(mut self) -> Self
+    where I: PartialEq {
+        self.coalesce();
         self
     }
+}
+
+impl<D: ndarray::Dimension, I: NdIndex<D>> From<Tile<D, I>> for MultiTile<D, I> {
+    fn from(tile: Tile<D, I>) -> Self {
+        MultiTile {
+            dim: tile.dim,
+            active: match tile.cells {
+                Cells::Single(cell) => cell.into_iter().collect(),
+                Cells::Multi(cells) => cells,
+            },
+        }
+    }
+}
+
+impl<D, I> crate::params::Buffer for MultiTile<D, I>
+where
+    D: ndarray::Dimension,
+    I: NdIndex<D> + PartialEq + Clone,
+{
+    type Dim = D;
+
+    fn raw_dim(&self) -> D { self.dim.clone() }
+
+    fn addto<E: DataMut<Elem = f64>>(&self, arr: &mut ArrayBase<E, Self::Dim>) {
+        for (idx, activation) in self.active.iter() {
+            arr[idx.clone()] += activation;
+        }
+    }
+
+    fn scaled_addto<E: DataMut<Elem = f64>>(&self, alpha: f64, arr: &mut ArrayBase<E, Self::Dim>) {
+        for (idx, activation) in self.active.iter() {
+            arr[idx.clone()] += alpha * activation;
+        }
+    }
 
+    fn dot<E: Data<Elem = f64>>(&self, arr: &ArrayBase<E, Self::Dim>) -> f64 {
+        self.active.iter().map(|(idx, activation)| arr[idx.clone()] * activation).sum()
+    }
+
+    /// Return the Euclidean norm of the buffer's state.
+    ///
+    /// Entries sharing an index are summed before the norm is taken.
+    fn l2_norm(&self) -> f64 {
+        self.clone().coalesced().active.into_iter().map(|(_, x)| x * x).sum::<f64>().sqrt()
+    }
+}
+
+impl<D, I> crate::params::BufferMut for MultiTile<D, I>
+where
+    D: ndarray::Dimension,
+    I: NdIndex<D> + PartialEq + Clone,
+{
+    fn zeros<T: IntoDimension<Dim = D>>(dim: T) -> Self { MultiTile::new(dim, vec![]) }
+
+    fn map_into(self, f: impl Fn(f64) -> f64) -> Self {
+        MultiTile {
+            dim: self.dim,
+            active: self.active.into_iter().map(|(idx, a)| (idx, f(a))).collect(),
+        }
+    }
+
+    fn map_inplace(&mut self, f: impl Fn(f64) -> f64) {
+        self.active.iter_mut().for_each(|(_, x)| *x = f(*x));
+    }
+
+    /// Merge `other` into `self` in place.
+    ///
+    /// Entries sharing an index are summed on either side beforehand, such
+    /// that `f` is applied exactly once per active cell.
     fn merge_inplace(&mut self, other: &Self, f: impl Fn(f64, f64) -> f64) {
         if self.dim != other.dim {
             panic!("Incompatible buffers shapes.")
         }
 
-        match (&mut self.active, &other.active) {
-            (Some((i, x)), Some((j, y))) if i == j => *x = f(*x, *y),
-            _ => panic!("Incompatible buffer indices."),
+        self.coalesce();
+
+        let n = self.active.len();
+        let other = other.clone().coalesced();
+
+        for (i, x) in self.active.iter_mut() {
+            let y = other.active.iter().find(|(j, _)| j == i).map_or(0.0, |(_, y)| *y);
+
+            *x = f(*x, y);
+        }
+
+        for (j, y) in other.active.into_iter() {
+            if !self.active[..n].iter().any(|(i, _)| *i == j) {
+                self.active.push((j, f(0.0, y)));
+            }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::params::{Buffer, BufferMut};
+    use ndarray::{Array2, Ix1, Ix2};
+
+    #[test]
+    fn test_tile_dot_and_norm() {
+        let arr = array![[1.0, 2.0], [3.0, 4.0]];
+
+        let tile = Tile::<Ix2, _>::new((2, 2), Some(([1, 0], -2.0)));
+
+        assert_eq!(tile.dot(&arr), -6.0);
+        assert_eq!(tile.dot(&arr), (tile.to_dense() * &arr).sum());
+        assert_eq!(tile.l2_norm(), 2.0);
+
+        let empty = Tile::<Ix2, [usize; 2]>::zeros((2, 2));
+
+        assert_eq!(empty.dot(&arr), 0.0);
+        assert_eq!(empty.l2_norm(), 0.0);
+
+        let multi = MultiTile::<Ix2, _>::new((2, 2), vec![([0, 1], 3.0), ([1, 1], 4.0)]);
+
+        assert_eq!(multi.dot(&arr), 22.0);
+        assert_eq!(multi.l2_norm(), 5.0);
+        assert_eq!(multi.l2_norm(), multi.to_dense().mapv(|x| x * x).sum().sqrt());
+    }
+
+    #[test]
+    fn test_tile_merge_distinct_indices() {
+        let a = Tile::<Ix1, _>::new(4, Some((1, 1.0)));
+        let b = Tile::<Ix1, _>::new(4, Some((3, 2.0)));
+
+        let merged = a.merge(&b, |x, y| x + y);
+
+        assert_eq!(merged.active(), &[(1, 1.0), (3, 2.0)]);
+        assert_eq!(merged.to_dense(), array![0.0, 1.0, 0.0, 2.0]);
+        assert_eq!(merged.l2_norm(), 5.0f64.sqrt());
+
+        // Matching indices are combined in place.
+        let merged = merged.merge(&a, |x, y| x + y);
+
+        assert_eq!(merged.active().len(), 2);
+        assert_eq!(merged.to_dense(), array![0.0, 2.0, 0.0, 2.0]);
+
+        // Decaying a trace applies to every retained cell.
+        let decayed = merged.merge(&Tile::zeros(4), |x, y| 0.5 * x + y);
+
+        assert_eq!(decayed.to_dense(), array![0.0, 1.0, 0.0, 1.0]);
+    }
+
+    #[test]
+    fn test_tile_merge_same_index() {
+        let a = Tile::<Ix1, _>::new(4, Some((1, 1.0)));
+        let merged = a.merge(&a, |x, y| x + y);
+
+        assert_eq!(a.active(), &[(1, 1.0)]);
+        assert_eq!(merged.active(), &[(1, 2.0)]);
+        assert_eq!(Tile::zeros(4).merge(&a, |x, y| x + y).active(), &[(1, 1.0)]);
+    }
+
+    #[test]
+    fn test_tile_merge_inplace_distinct_indices() {
+        let mut a = Tile::<Ix1, _>::new(4, Some((1, 1.0)));
+        let b = Tile::<Ix1, _>::new(4, Some((3, 2.0)));
+
+        a.merge_inplace(&b, |x, y| x + y);
+
+        assert_eq!(a.active(), &[(1, 1.0), (3, 2.0)]);
+
+        a.merge_inplace(&b, |x, y| x + y);
+
+        assert_eq!(a.to_dense(), array![0.0, 1.0, 0.0, 4.0]);
+    }
+
+    #[test]
+    fn test_multi_tile_addto() {
+        let tile = MultiTile::<Ix2, _>::new((3, 2), vec![([0, 1], 1.0), ([2, 0], 0.5)]);
+        let mut arr = Array2::ones((3, 2));
+
+        tile.addto(&mut arr);
+
+        assert_eq!(arr, array![[1.0, 2.0], [1.0, 1.0], [1.5, 1.0]]);
+
+        tile.scaled_addto(2.0, &mut arr);
+
+        assert_eq!(arr, array![[1.0, 4.0], [1.0, 1.0], [2.5, 1.0]]);
+        assert_eq!(tile.to_dense(), array![[0.0, 1.0], [0.0, 0.0], [0.5, 0.0]]);
+    }
+
+    #[test]
+    fn test_multi_tile_merge() {
+        let a = MultiTile::<Ix2, _>::new((2, 2), vec![([0, 0], 1.0), ([1, 1], 2.0)]);
+        let b = MultiTile::<Ix2, _>::new((2, 2), vec![([1, 1], 3.0), ([0, 1], 4.0)]);
+
+        let merged = a.merge(&b, |x, y| x + y);
+
+        assert_eq!(merged.active().len(), 3);
+        assert_eq!(merged.to_dense(), array![[1.0, 4.0], [0.0, 5.0]]);
+    }
+
+    #[test]
+    fn test_multi_tile_merge_duplicates() {
+        let a = MultiTile::<Ix1, _>::new(3, vec![(0, 1.0), (0, 1.0), (1, 1.0)]);
+        let b = MultiTile::<Ix1, _>::new(3, vec![(0, 2.0), (2, 1.0), (2, 1.0)]);
+
+        let merged = a.merge(&b, |x, y| x * y);
+
+        assert_eq!(merged.active().len(), 3);
+        assert_eq!(merged.to_dense(), array![4.0, 0.0, 0.0]);
+
+        let decayed = a.merge(&MultiTile::zeros(3), |x, y| 0.5 * x + y);
+
+        assert_eq!(decayed.to_dense(), array![1.0, 0.5, 0.0]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_multi_tile_merge_dim_mismatch() {
+        let a = MultiTile::<Ix2, [usize; 2]>::zeros((2, 2));
+        let b = MultiTile::<Ix2, [usize; 2]>::zeros((3, 2));
+
+        a.merge(&b, |x, y| x + y);
+    }
+}
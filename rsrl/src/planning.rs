@@ -0,0 +1,255 @@
+//! Dynamic programming solvers for domains with known dynamics.
+//!
+//! These methods compute the optimal value function and a greedy policy for
+//! finite domains implementing [`Model`], and are useful as ground truth
+//! against which to compare learned agents.
+use crate::{
+    domains::Model,
+    spaces::{discrete::Ordinal, FiniteSpace},
+};
+
+/// Value function and greedy policy computed by a planning method.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate")
+)]
+pub struct Solution {
+    /// State values, `V(s)`, indexed by state.
+    pub values: Vec<f64>,
+
+    /// Greedy action in each state with respect to `values`.
+    pub policy: Vec<usize>,
+
+    /// Number of sweeps over the state space that were performed.
+    pub n_sweeps: usize,
+
+    /// Largest change in value observed in the final sweep.
+    pub residual: f64,
+}
+
+fn action_value<M>(model: &M, gamma: f64, values: &[f64], s: usize, a: usize) -> f64
+where M: Model<StateSpace = Ordinal, ActionSpace = Ordinal> {
+    model
+        .possible_transitions(&s, &a)
+        .into_iter()
+        .map(|(p, ns, r)| {
+            let nv = if ns.is_terminal() { 0.0 } else { values[*ns.state()] };
+
+            p * (r + gamma * nv)
+        })
+        .sum()
+}
+
+fn greedy<M>(model: &M, gamma: f64, values: &[f64], s: usize) -> (usize, f64)
+where M: Model<StateSpace = Ordinal, ActionSpace = Ordinal> {
+    model
+        .action_space()
+        .range()
+        .map(|a| (a, action_value(model, gamma, values, s, a)))
+        .fold((0, f64::MIN), |acc, (a, q)| if q > acc.1 { (a, q) } else { acc })
+}
+
+fn greedy_policy<M>(model: &M, gamma: f64, values: &[f64]) -> Vec<usize>
+where M: Model<StateSpace = Ordinal, ActionSpace = Ordinal> {
+    model.state_space().range().map(|s| greedy(model, gamma, values, s).0).collect()
+}
+
+/// Compute the optimal value function by value iteration.
+///
+/// Sweeps of the Bellman optimality backup are applied in place until the
+/// largest change in value, the Bellman residual, falls below `tol`, or until
+/// `max_sweeps` sweeps have been performed. In the latter case the returned
+/// `residual` is at least `tol`.
+///
+/// Note that, with `gamma = 1`, convergence is only guaranteed if every policy
+/// eventually reaches a terminal state; otherwise the values of states from
+/// which reward can be accrued indefinitely grow without bound.
+pub fn value_iteration<M>(model: &M, gamma: f64, tol: f64, max_sweeps: usize) -> Solution
+where M: Model<StateSpace = Ordinal, ActionSpace = Ordinal> {
+    let mut values = vec![0.0; model.state_space().range().len()];
+    let mut n_sweeps = 0;
+
+    loop {
+        let mut residual: f64 = 0.0;
+
+        for s in model.state_space().range() {
+            let (_, v) = greedy(model, gamma, &values, s);
+
+            residual = residual.max((v - values[s]).abs());
+            values[s] = v;
+        }
+
+        n_sweeps += 1;
+
+        if residual < tol || n_sweeps >= max_sweeps {
+            return Solution {
+                policy: greedy_policy(model, gamma, &values),
+                values,
+                n_sweeps,
+                residual,
+            };
+        }
+    }
+}
+
+/// Compute the optimal value function by policy iteration.
+///
+/// Starting from the policy that always selects action 0, the value of the
+/// current policy is evaluated (to within `tol`) and the policy is then made
+/// greedy with respect to it, until the policy no longer changes. Ties are
+/// broken in favour of the current action.
+///
+/// Note that, with `gamma = 1`, the initial policy must be proper, i.e. reach
+/// a terminal state from every state, for the evaluation step to terminate.
+pub fn policy_iteration<M>(model: &M, gamma: f64, tol: f64) -> Solution
+where M: Model<StateSpace = Ordinal, ActionSpace = Ordinal> {
+    let n_states = model.state_space().range().len();
+
+    let mut values = vec![0.0; n_states];
+    let mut policy = vec![0; n_states];
+    let mut n_sweeps = 0;
+
+    loop {
+        // Policy evaluation:
+        let residual = loop {
+            let mut residual: f64 = 0.0;
+
+            for s in 0..n_states {
+                let v = action_value(model, gamma, &values, s, policy[s]);
+
+                residual = residual.max((v - values[s]).abs());
+                values[s] = v;
+            }
+
+            n_sweeps += 1;
+
+            if residual < tol {
+                break residual;
+            }
+        };
+
+        // Policy improvement:
+        let mut stable = true;
+
+        for (s, action) in policy.iter_mut().enumerate() {
+            let current = action_value(model, gamma, &values, s, *action);
+            let (a, q) = greedy(model, gamma, &values, s);
+
+            if q > current + tol {
+                *action = a;
+                stable = false;
+            }
+        }
+
+        if stable {
+            return Solution {
+                values,
+                policy,
+                n_sweeps,
+                residual,
+            };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domains::{Domain, GridWorld, Observation, Reward};
+
+    /// Single state in which action 0 loops back with a reward of 1, and
+    /// action 1 terminates with no reward.
+    struct SelfLoop;
+
+    impl Domain for SelfLoop {
+        type StateSpace = Ordinal;
+        type ActionSpace = Ordinal;
+
+        fn emit(&self) -> Observation<usize> { Observation::Full(0) }
+
+        fn reset(&mut self) {}
+
+        fn step(&mut self, a: &usize) -> (Observation<usize>, Reward) { self.predict(&0, a) }
+
+        fn state_space(&self) -> Ordinal { Ordinal::new(1) }
+
+        fn action_space(&self) -> Ordinal { Ordinal::new(2) }
+    }
+
+    impl Model for SelfLoop {
+        fn predict(&self, _: &usize, a: &usize) -> (Observation<usize>, Reward) {
+            match a {
+                0 => (Observation::Full(0), 1.0),
+                _ => (Observation::Terminal(0), 0.0),
+            }
+        }
+    }
+
+    fn grid() -> GridWorld {
+        GridWorld::new(4, 4, [0, 0], &[[3, 3]], 10.0).with_walls(&[[1, 1], [2, 1], [1, 2]])
+    }
+
+    #[test]
+    fn test_value_iteration() {
+        let gw = grid();
+        let sol = value_iteration(&gw, 0.9, 1e-8, 10_000);
+
+        assert!(sol.residual < 1e-8);
+
+        // Cell [2, 3] is adjacent to the goal, and [0, 0] is six steps away.
+        assert!((sol.values[14] - 10.0).abs() < 1e-6);
+        let v0 = -1.0 - 0.9 - 0.81 - 0.729 - 0.6561 + 0.59049 * 10.0;
+
+        assert!((sol.values[0] - v0).abs() < 1e-6);
+        assert_eq!(sol.policy[14], 3);
+        assert_eq!(sol.policy[11], 0);
+    }
+
+    #[test]
+    fn test_value_iteration_tolerance() {
+        // Each sweep in the self-loop changes the value by 0.9^(k-1), so the
+        // residual first drops below 0.5 after 8 sweeps (0.9^7 ≈ 0.478).
+        let sol = value_iteration(&SelfLoop, 0.9, 0.5, 10_000);
+
+        assert_eq!(sol.n_sweeps, 8);
+        assert!((sol.residual - 0.9f64.powi(7)).abs() < 1e-10);
+        assert!((sol.values[0] - (1.0 - 0.9f64.powi(8)) / 0.1).abs() < 1e-10);
+        assert_eq!(sol.policy, vec![0]);
+
+        let sol = value_iteration(&SelfLoop, 0.9, 1e-8, 10_000);
+
+        assert!(sol.residual < 1e-8);
+        assert!((sol.values[0] - 10.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_policy_iteration_matches_value_iteration() {
+        let gw = grid();
+
+        let vi = value_iteration(&gw, 0.9, 1e-10, 10_000);
+        let pi = policy_iteration(&gw, 0.9, 1e-10);
+
+        for (v1, v2) in vi.values.iter().zip(pi.values.iter()) {
+            assert!((v1 - v2).abs() < 1e-6);
+        }
+
+        for s in 0..16 {
+            let q1 = action_value(&gw, 0.9, &vi.values, s, vi.policy[s]);
+            let q2 = action_value(&gw, 0.9, &vi.values, s, pi.policy[s]);
+
+            assert!((q1 - q2).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_value_iteration_max_sweeps() {
+        // Undiscounted, the self-loop accrues one unit of reward per sweep forever.
+        let sol = value_iteration(&SelfLoop, 1.0, 1e-8, 50);
+
+        assert_eq!(sol.n_sweeps, 50);
+        assert_eq!(sol.residual, 1.0);
+        assert_eq!(sol.values[0], 50.0);
+    }
+}
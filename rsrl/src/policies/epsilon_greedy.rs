@@ -1,5 +1,6 @@
 use crate::{
-    policies::{Greedy, Policy, Random},
+    policies::{Exploratory, Greedy, Policy, Random},
+    schedule::Parameter,
     Enumerable,
     Function,
 };
@@ -16,7 +17,9 @@ pub struct EpsilonGreedy<Q> {
     greedy: Greedy<Q>,
     random: Random,
 
-    pub epsilon: f64,
+    /// Exploration rate, advanced only by explicit calls to `step`; constant
+    /// by default.
+    pub epsilon: Parameter,
 }
 
 impl<Q> EpsilonGreedy<Q> {
@@ -25,7 +28,25 @@ impl<Q> EpsilonGreedy<Q> {
             greedy,
             random,
 
-            epsilon,
+            epsilon: Parameter::constant(epsilon),
+        }
+    }
+
+    /// Anneal the exploration rate according to `schedule`.
+    ///
+    /// The schedule is typically stepped once per episode to shift the policy
+    /// from exploration towards exploitation over the course of training.
+    pub fn with_epsilon_schedule(mut self, schedule: Parameter) -> Self {
+        self.epsilon = schedule;
+        self
+    }
+}
+
+impl<Q: Clone> Exploratory for EpsilonGreedy<Q> {
+    fn clone_for_eval(&self) -> Self {
+        EpsilonGreedy {
+            epsilon: Parameter::constant(0.0),
+            ..self.clone()
         }
     }
 }
@@ -36,11 +57,12 @@ where Q: Enumerable<(S,), Output = Vec<f64>>
     type Output = Vec<f64>;
 
     fn evaluate(&self, (s,): (S,)) -> Vec<f64> {
+        let epsilon = self.epsilon.value();
         let prs = self.greedy.evaluate((s,));
-        let pr = self.epsilon / prs.len() as f64;
+        let pr = epsilon / prs.len() as f64;
 
         prs.into_iter()
-            .map(|p| pr + p * (1.0 - self.epsilon))
+            .map(|p| pr + p * (1.0 - epsilon))
             .collect()
     }
 }
@@ -53,10 +75,11 @@ where
     type Output = f64;
 
     fn evaluate(&self, (s, a): (S, A)) -> f64 {
+        let epsilon = self.epsilon.value();
         let prs = self.greedy.evaluate((s,));
-        let pr = self.epsilon / prs.len() as f64;
+        let pr = epsilon / prs.len() as f64;
 
-        pr + (1.0 - self.epsilon) * prs[*a.borrow()]
+        pr + (1.0 - epsilon) * prs[*a.borrow()]
     }
 }
 
@@ -72,7 +95,7 @@ where Q: Enumerable<(S,), Output = Vec<f64>>
     type Action = usize;
 
     fn sample<R: Rng + ?Sized>(&self, rng: &mut R, s: S) -> usize {
-        if rng.gen_bool(self.epsilon) {
+        if rng.gen_bool(self.epsilon.value()) {
             self.random.sample(rng, s)
         } else {
             self.greedy.sample(rng, s)
@@ -87,7 +110,8 @@ mod tests {
     use approx::assert_abs_diff_eq;
     use crate::{
         fa::mocking::MockQ,
-        policies::{EnumerablePolicy, EpsilonGreedy, Policy, Greedy, Random},
+        policies::{EnumerablePolicy, EpsilonGreedy, Exploratory, Policy, Greedy, Random},
+        schedule::Parameter,
         Function,
     };
     use rand::thread_rng;
@@ -143,4 +167,70 @@ mod tests {
             .zip([0.25, 0.25, 0.25, 0.25].iter())
             .for_each(|(x, y)| assert_abs_diff_eq!(x, y, epsilon = 1e-6));
     }
+
+    #[test]
+    fn test_probabilities_sum_to_one() {
+        let q = MockQ::new_shared(None);
+        let p = EpsilonGreedy::new(Greedy::new(q), Random::new(4), 0.3);
+
+        for qs in &[vec![1.0, 0.0, 0.0, 0.0], vec![1.0, 1.0, -2.0, 0.5], vec![0.0; 4]] {
+            let ps = p.probabilities(qs);
+
+            assert_eq!(ps.len(), 4);
+            assert_abs_diff_eq!(ps.iter().sum::<f64>(), 1.0, epsilon = 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_seeded_sampling() {
+        use rand::{rngs::StdRng, SeedableRng};
+
+        let q = MockQ::new_shared(Some(vec![1.0, 0.0, 1.0, 0.5]));
+        let p = EpsilonGreedy::new(Greedy::new(q), Random::new(4), 0.5);
+
+        let mut rng1 = StdRng::seed_from_u64(0);
+        let mut rng2 = StdRng::seed_from_u64(0);
+
+        let a1: Vec<usize> = (0..100).map(|_| p.sample(&mut rng1, &vec![])).collect();
+        let a2: Vec<usize> = (0..100).map(|_| p.sample(&mut rng2, &vec![])).collect();
+
+        assert_eq!(a1, a2);
+    }
+
+    #[test]
+    fn test_greedy_boundary() {
+        let mut rng = thread_rng();
+
+        let q = MockQ::new_shared(Some(vec![0.0, 1.0, 0.0]));
+        let p = EpsilonGreedy::new(Greedy::new(q), Random::new(3), 0.0);
+
+        assert!((0..1000).all(|_| p.sample(&mut rng, &vec![]) == 1));
+
+        p.probabilities(&vec![])
+            .into_iter()
+            .zip([0.0, 1.0, 0.0].iter())
+            .for_each(|(x, y)| assert_abs_diff_eq!(x, y, epsilon = 1e-6));
+    }
+
+    #[test]
+    fn test_epsilon_schedule() {
+        let q = MockQ::new_shared(None);
+        let mut p: EpsilonGreedy<_> = EpsilonGreedy::new(Greedy::new(q), Random::new(2), 1.0)
+            .with_epsilon_schedule(Parameter::exponential(1.0, 0.5, 0.1));
+
+        p.epsilon.step();
+        assert_abs_diff_eq!(p.epsilon.value(), 0.5);
+
+        p.probabilities(&vec![1.0, 0.0])
+            .into_iter()
+            .zip([0.75, 0.25].iter())
+            .for_each(|(x, y)| assert_abs_diff_eq!(x, y, epsilon = 1e-6));
+
+        for _ in 0..10 {
+            p.epsilon.step();
+        }
+
+        assert_abs_diff_eq!(p.epsilon.value(), 0.1);
+        assert_abs_diff_eq!(p.clone_for_eval().epsilon.value(), 0.0);
+    }
 }
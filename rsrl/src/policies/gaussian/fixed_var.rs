@@ -1,4 +1,4 @@
-use super::{BuilderSupport, Gaussian};
+use super::{BuilderSupport, Gaussian, IntoCov};
 use crate::{
     fa::{GradientUpdate, ScaledGradientUpdate, StateActionUpdate},
     params::*,
@@ -43,7 +43,7 @@ where
     type Output = f64;
 
     fn evaluate(&self, (x, a): (&'x X, A)) -> f64 {
-        Builder::build_unchecked(self.compute_mean(x), self.stddev).pdf(a.borrow())
+        Builder::build_unchecked(self.compute_mean(x), self.stddev.into_cov()).pdf(a.borrow())
     }
 }
 
@@ -63,7 +63,7 @@ where
     fn grad(&self, _: (&'x X, A)) -> Array2<f64> { todo!() }
 
     fn grad_log(&self, (x, a): (&'x X, A)) -> Array2<f64> {
-        let dist = Builder::build_unchecked(self.compute_mean(x), self.stddev);
+        let dist = Builder::build_unchecked(self.compute_mean(x), self.stddev.into_cov());
         let grad_mean = self.mean.grad((x,)).into_dense().insert_axis(Axis(1));
 
         let normal::Grad { mu: gl_mean, .. } = dist.score(&[*a.borrow()]);
@@ -76,7 +76,7 @@ impl<'x, X, M> Policy<&'x X> for Gaussian<M, f64>
 where
     M: Function<(&'x X,)>,
 
-    M::Output: Clone,
+    M::Output: Clone + super::Clip,
 
     Builder: BuildNormal<M::Output, f64>,
     BuilderSupport<M::Output, f64>: Space<Value = M::Output>,
@@ -84,10 +84,12 @@ where
     type Action = M::Output;
 
     fn sample<R: Rng + ?Sized>(&self, rng: &mut R, x: &'x X) -> Self::Action {
-        Builder::build_unchecked(self.compute_mean(x), self.stddev).sample(rng)
+        let dist = Builder::build_unchecked(self.compute_mean(x), self.stddev.into_cov());
+
+        self.clip_action(dist.sample(rng))
     }
 
-    fn mode(&self, x: &'x X) -> Self::Action { self.compute_mean(x) }
+    fn mode(&self, x: &'x X) -> Self::Action { self.clip_action(self.compute_mean(x)) }
 }
 
 impl<'x, X, A, M> Handler<StateActionUpdate<&'x X, A>> for Gaussian<M, f64>
@@ -105,7 +107,7 @@ where
     type Error = ();
 
     fn handle(&mut self, msg: StateActionUpdate<&'x X, A>) -> Result<Self::Response, Self::Error> {
-        let dist = Builder::build_unchecked(self.compute_mean(msg.state), self.stddev);
+        let dist = Builder::build_unchecked(self.compute_mean(msg.state), self.stddev.into_cov());
         let normal::Grad { mu: gl_mean, .. } = dist.score(&[*msg.action.borrow()]);
 
         self.mean.grad((msg.state,)).scaled_addto(
@@ -176,3 +178,50 @@ where
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        fa::tabular::Table,
+        policies::{Gaussian, Policy},
+        Differentiable,
+    };
+    use ndarray::arr1;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn test_seeded_sampling() {
+        let p = Gaussian::new(Table::dense(arr1(&[0.5, -1.0])), 0.3);
+
+        let mut rng1 = StdRng::seed_from_u64(0);
+        let mut rng2 = StdRng::seed_from_u64(0);
+
+        let a1: Vec<f64> = (0..100).map(|_| p.sample(&mut rng1, &0usize)).collect();
+        let a2: Vec<f64> = (0..100).map(|_| p.sample(&mut rng2, &0usize)).collect();
+
+        assert_eq!(a1, a2);
+        assert_eq!(p.mode(&1usize), -1.0);
+    }
+
+    #[test]
+    fn test_clipped_sampling() {
+        let p = Gaussian::new(Table::dense(arr1(&[0.5, 5.0])), 10.0).clipped(-0.1, 0.1);
+        let mut rng = StdRng::seed_from_u64(0);
+
+        assert!((0..1000).all(|_| p.sample(&mut rng, &0usize).abs() <= 0.1));
+        assert_eq!(p.mode(&1usize), 0.1);
+    }
+
+    #[test]
+    fn test_grad_log_mean() {
+        let p = Gaussian::new(Table::dense(arr1(&[0.5, -1.0])), 2.0);
+
+        for &a in &[-3.0, 0.0, 0.5, 4.0] {
+            let jac = p.grad_log((&0usize, a));
+
+            assert_eq!(jac.dim(), (2, 1));
+            assert!((jac[[0, 0]] - (a - 0.5) / 4.0).abs() < 1e-10);
+            assert_eq!(jac[[1, 0]], 0.0);
+        }
+    }
+}
@@ -162,7 +162,7 @@ where
     M: Function<(&'x X,)>,
     S: Function<(&'x X,)>,
 
-    M::Output: Clone,
+    M::Output: Clone + super::Clip,
     S::Output: std::ops::Add<f64, Output = S::Output> + super::IntoCov,
 
     Builder: BuildNormal<M::Output, S::Output>,
@@ -171,10 +171,10 @@ where
     type Action = M::Output;
 
     fn sample<R: Rng + ?Sized>(&self, rng: &mut R, x: &'x X) -> Self::Action {
-        self.dist(x).sample(rng)
+        self.clip_action(self.dist(x).sample(rng))
     }
 
-    fn mode(&self, x: &'x X) -> Self::Action { self.compute_mean(x) }
+    fn mode(&self, x: &'x X) -> Self::Action { self.clip_action(self.compute_mean(x)) }
 }
 
 impl<'x, X, A, M, S> Handler<StateActionUpdate<&'x X, A>> for Gaussian<M, S>
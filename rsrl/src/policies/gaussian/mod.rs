@@ -34,6 +34,29 @@ impl IntoCov for ndarray::Array1<f64> {
     }
 }
 
+/// Actions that can be clipped component-wise to a closed interval.
+pub trait Clip {
+    fn clip(self, lb: f64, ub: f64) -> Self;
+}
+
+impl Clip for f64 {
+    fn clip(self, lb: f64, ub: f64) -> f64 { self.max(lb).min(ub) }
+}
+
+impl Clip for [f64; 2] {
+    fn clip(self, lb: f64, ub: f64) -> [f64; 2] { [self[0].clip(lb, ub), self[1].clip(lb, ub)] }
+}
+
+impl Clip for Vec<f64> {
+    fn clip(self, lb: f64, ub: f64) -> Vec<f64> {
+        self.into_iter().map(|x| x.clip(lb, ub)).collect()
+    }
+}
+
+impl Clip for ndarray::Array1<f64> {
+    fn clip(self, lb: f64, ub: f64) -> ndarray::Array1<f64> { self.mapv(|x| x.clip(lb, ub)) }
+}
+
 const MIN_TOL: f64 = 0.01;
 
 #[derive(Clone, Debug)]
@@ -45,10 +68,40 @@ const MIN_TOL: f64 = 0.01;
 pub struct Gaussian<M, S> {
     pub mean: M,
     pub stddev: S,
+
+    /// Optional actuator limits to which sampled actions are clipped.
+    pub bounds: Option<(f64, f64)>,
 }
 
 impl<M, S> Gaussian<M, S> {
-    pub fn new(mean: M, stddev: S) -> Self { Gaussian { mean, stddev } }
+    pub fn new(mean: M, stddev: S) -> Self {
+        Gaussian {
+            mean,
+            stddev,
+            bounds: None,
+        }
+    }
+
+    /// Return a new instance whose samples (and mode) are clipped
+    /// component-wise to the interval `[lb, ub]`, e.g. the bounds of a
+    /// domain's `Interval` action space.
+    ///
+    /// Note that the density and its gradient are those of the unclipped
+    /// distribution.
+    pub fn clipped(self, lb: f64, ub: f64) -> Self {
+        Gaussian {
+            bounds: Some((lb, ub)),
+            ..self
+        }
+    }
+
+    #[inline]
+    fn clip_action<A: Clip>(&self, a: A) -> A {
+        match self.bounds {
+            Some((lb, ub)) => a.clip(lb, ub),
+            None => a,
+        }
+    }
 }
 
 impl<M, S> Gaussian<M, S> {
@@ -0,0 +1,185 @@
+use crate::{
+    fa::StateActionUpdate,
+    policies::{sample_probs_with_rng, Policy},
+    utils::argmax_first,
+    Enumerable,
+    Function,
+    Handler,
+};
+use rand::Rng;
+use std::borrow::Borrow;
+
+/// Gradient bandit action selection with softmax action preferences.
+///
+/// Maintains a numerical preference, `H_a`, for each action and selects
+/// actions with probability `π(a) = exp(H_a) / Σ_b exp(H_b)`. After taking
+/// action `A` and receiving reward `R`, every preference is moved along a
+/// stochastic gradient of the expected reward,
+///
+/// `H_a ← H_a + α (R - R̄) (1{a = A} - π(a))`,
+///
+/// where the baseline, `R̄`, is the average of all rewards received prior to
+/// `R`. Rewards are passed as the `error` of a `StateActionUpdate`; the state
+/// is ignored.
+///
+/// # References
+/// - Sutton, R. S., & Barto, A. G. (2018). Reinforcement learning: An
+///   introduction (2nd ed., Section 2.8). MIT press.
+#[derive(Clone, Debug)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate")
+)]
+pub struct GradientBandit {
+    pub alpha: f64,
+
+    preferences: Vec<f64>,
+    baseline: f64,
+    n_rewards: usize,
+}
+
+impl GradientBandit {
+    pub fn new(n_actions: usize, alpha: f64) -> Self {
+        GradientBandit {
+            alpha,
+
+            preferences: vec![0.0; n_actions],
+            baseline: 0.0,
+            n_rewards: 0,
+        }
+    }
+
+    /// Return the preference, `H_a`, for each action.
+    pub fn preferences(&self) -> &[f64] { &self.preferences }
+
+    /// Return the average reward received so far, `R̄`.
+    pub fn baseline(&self) -> f64 { self.baseline }
+
+    fn softmax(&self) -> Vec<f64> {
+        let max = self.preferences.iter().fold(f64::MIN, |acc, &h| acc.max(h));
+        let exps: Vec<f64> = self.preferences.iter().map(|h| (h - max).exp()).collect();
+        let z: f64 = exps.iter().sum();
+
+        exps.into_iter().map(|e| e / z).collect()
+    }
+}
+
+impl<S> Function<(S,)> for GradientBandit {
+    type Output = Vec<f64>;
+
+    fn evaluate(&self, _: (S,)) -> Vec<f64> { self.softmax() }
+}
+
+impl<S, A: Borrow<usize>> Function<(S, A)> for GradientBandit {
+    type Output = f64;
+
+    fn evaluate(&self, (s, a): (S, A)) -> f64 { self.evaluate((s,))[*a.borrow()] }
+}
+
+impl<S> Enumerable<(S,)> for GradientBandit {
+    fn len(&self, _: (S,)) -> usize { self.preferences.len() }
+
+    fn evaluate_index(&self, (s,): (S,), index: usize) -> f64 { self.evaluate((s, index)) }
+}
+
+impl<S> Policy<S> for GradientBandit {
+    type Action = usize;
+
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R, _: S) -> usize {
+        sample_probs_with_rng(rng, &self.softmax())
+    }
+
+    fn mode(&self, _: S) -> usize { argmax_first(self.preferences.iter().cloned()).0 }
+}
+
+impl<S, A: Borrow<usize>> Handler<StateActionUpdate<S, A>> for GradientBandit {
+    type Response = ();
+    type Error = ();
+
+    fn handle(&mut self, msg: StateActionUpdate<S, A>) -> Result<(), ()> {
+        let a = *msg.action.borrow();
+        let scale = self.alpha * (msg.error - self.baseline);
+        let probs = self.softmax();
+
+        for (b, (h, p)) in self.preferences.iter_mut().zip(probs).enumerate() {
+            let indicator = if b == a { 1.0 } else { 0.0 };
+
+            *h += scale * (indicator - p);
+        }
+
+        self.n_rewards += 1;
+        self.baseline += (msg.error - self.baseline) / self.n_rewards as f64;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::GradientBandit;
+    use crate::{
+        domains::{Arm, Bandit, Domain},
+        fa::StateActionUpdate,
+        policies::{EnumerablePolicy, Policy},
+        Handler,
+    };
+    use rand::{rngs::StdRng, SeedableRng};
+
+    fn pull(p: &mut GradientBandit, a: usize, r: f64) {
+        p.handle_unchecked(StateActionUpdate {
+            state: (),
+            action: a,
+            error: r,
+        });
+    }
+
+    #[test]
+    fn test_update() {
+        let mut p = GradientBandit::new(2, 0.5);
+
+        assert_eq!(p.probabilities(()), vec![0.5, 0.5]);
+
+        // The first reward is measured against a zero baseline.
+        pull(&mut p, 0, 2.0);
+
+        assert_eq!(p.preferences(), &[0.5, -0.5]);
+        assert_eq!(p.baseline(), 2.0);
+        assert_eq!(p.mode(()), 0);
+
+        // A below-average reward lowers the preference for the action taken.
+        pull(&mut p, 0, 1.0);
+
+        assert!(p.preferences()[0] < 0.5);
+        assert_eq!(p.baseline(), 1.5);
+
+        let ps = p.probabilities(());
+
+        assert!((ps.iter().sum::<f64>() - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_bandit_convergence() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut domain = Bandit::with_rng(
+            vec![Arm::Bernoulli(0.2), Arm::Bernoulli(0.5), Arm::Bernoulli(0.8)],
+            StdRng::seed_from_u64(1),
+        );
+        let mut p = GradientBandit::new(3, 0.1);
+
+        let best = domain.optimal_arm();
+        let mut history = vec![];
+
+        for _ in 0..2000 {
+            let a = p.sample(&mut rng, ());
+            let (_, r) = domain.step(&a);
+
+            pull(&mut p, a, r);
+            history.push(p.preferences()[best]);
+        }
+
+        assert_eq!(p.mode(()), best);
+        assert!(history[1999] > history[999] && history[999] > history[99]);
+        assert!(p.probabilities(())[best] > 0.8);
+    }
+}
@@ -1,6 +1,6 @@
 use crate::{
-    policies::Policy,
-    utils::{argmax_choose_rng, argmaxima},
+    policies::{Exploratory, Policy},
+    utils::{argmax_ties, argmax_ties_first, argmaxima},
     Enumerable,
     Function,
 };
@@ -19,6 +19,10 @@ impl<Q> Greedy<Q> {
     pub fn new(q_func: Q) -> Self { Greedy(q_func) }
 }
 
+impl<Q: Clone> Exploratory for Greedy<Q> {
+    fn clone_for_eval(&self) -> Self { self.clone() }
+}
+
 impl<S, Q> Function<(S,)> for Greedy<Q>
 where
     Q: Enumerable<(S,)>,
@@ -75,12 +79,10 @@ impl<S, Q: Enumerable<(S,), Output = Vec<f64>>> Policy<S> for Greedy<Q> {
     type Action = usize;
 
     fn sample<R: Rng + ?Sized>(&self, rng: &mut R, s: S) -> usize {
-        let qs = self.0.evaluate((s,));
-
-        argmax_choose_rng(rng, qs).0
+        argmax_ties(rng, &self.0.evaluate((s,)))
     }
 
-    fn mode(&self, s: S) -> usize { self.0.find_max((s,)).0 }
+    fn mode(&self, s: S) -> usize { argmax_ties_first(&self.0.evaluate((s,))) }
 }
 
 #[cfg(test)]
@@ -144,6 +146,25 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_ties() {
+        let p = Greedy::new(MockQ::new_shared(None));
+        let mut rng = thread_rng();
+
+        let qs = vec![1.0, 5.0, 5.0, 5.0];
+        let mut seen = [false; 4];
+
+        for _ in 0..1000 {
+            seen[p.sample(&mut rng, &qs)] = true;
+        }
+
+        assert_eq!(seen, [false, true, true, true]);
+
+        for _ in 0..100 {
+            assert_eq!(p.mode(&qs), 1);
+        }
+    }
+
     #[test]
     fn test_precision() {
         let p = Greedy::new(MockQ::new_shared(None));
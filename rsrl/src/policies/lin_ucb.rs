@@ -0,0 +1,229 @@
+use crate::{
+    fa::StateActionUpdate,
+    policies::Policy,
+    utils::{argmax_choose_rng, argmax_first, argmaxima},
+    Enumerable,
+    Function,
+    Handler,
+};
+use ndarray::{Array1, Array2};
+use rand::Rng;
+use std::borrow::Borrow;
+
+/// Linear upper confidence bound (LinUCB) action selection for contextual
+/// bandits.
+///
+/// The expected reward of each action `a` is modelled as linear in the
+/// context, `x`, with coefficients `θ_a = A_a^{-1} b_a` fit by ridge
+/// regression, where `A_a = I + Σ x xᵀ` and `b_a = Σ r x` are accumulated over
+/// the rounds in which `a` was taken. The policy selects
+///
+/// `argmax_a θ_a·x + α sqrt(xᵀ A_a^{-1} x)`,
+///
+/// trading off the estimated reward against the uncertainty in the estimate.
+/// Only the inverses `A_a^{-1}` are stored, and these are updated in `O(d²)`
+/// time per round using the Sherman-Morrison formula. The statistics are
+/// updated by passing the context as the `state` and the observed reward as
+/// the `error` of a `StateActionUpdate`.
+///
+/// # References
+/// - Li, L., Chu, W., Langford, J., & Schapire, R. E. (2010). A
+///   contextual-bandit approach to personalized news article recommendation. In
+///   Proceedings of the 19th International Conference on World Wide Web, pp.
+///   661-670.
+#[derive(Clone, Debug)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate")
+)]
+pub struct LinUCB {
+    pub alpha: f64,
+
+    a_invs: Vec<Array2<f64>>,
+    bs: Vec<Array1<f64>>,
+}
+
+fn mat_vec(m: &Array2<f64>, x: &[f64]) -> Array1<f64> {
+    m.outer_iter().map(|row| row.iter().zip(x).map(|(m, x)| m * x).sum()).collect()
+}
+
+fn inner(x: &[f64], y: &Array1<f64>) -> f64 { x.iter().zip(y.iter()).map(|(x, y)| x * y).sum() }
+
+impl LinUCB {
+    pub fn new(n_actions: usize, n_features: usize, alpha: f64) -> Self {
+        LinUCB {
+            alpha,
+
+            a_invs: vec![Array2::eye(n_features); n_actions],
+            bs: vec![Array1::zeros(n_features); n_actions],
+        }
+    }
+
+    /// Return the number of actions.
+    pub fn n_actions(&self) -> usize { self.bs.len() }
+
+    /// Return the dimensionality of the contexts.
+    pub fn n_features(&self) -> usize { self.a_invs[0].nrows() }
+
+    /// Return the ridge regression estimate, `θ_a`, for the given action.
+    pub fn theta(&self, action: usize) -> Array1<f64> {
+        mat_vec(&self.a_invs[action], self.bs[action].as_slice().unwrap())
+    }
+
+    /// Return the upper confidence bound of each action in context `x`.
+    pub fn bounds(&self, x: &[f64]) -> Vec<f64> {
+        (0..self.n_actions())
+            .map(|a| {
+                let width = inner(x, &mat_vec(&self.a_invs[a], x));
+
+                inner(x, &self.theta(a)) + self.alpha * width.max(0.0).sqrt()
+            })
+            .collect()
+    }
+}
+
+impl<S: Borrow<[f64]>> Function<(S,)> for LinUCB {
+    type Output = Vec<f64>;
+
+    fn evaluate(&self, (s,): (S,)) -> Vec<f64> {
+        let mut ps = vec![0.0; self.n_actions()];
+
+        let (maxima, _) = argmaxima(self.bounds(s.borrow()));
+
+        let p = 1.0 / maxima.len() as f64;
+        for i in maxima {
+            ps[i] = p;
+        }
+
+        ps
+    }
+}
+
+impl<S: Borrow<[f64]>, A: Borrow<usize>> Function<(S, A)> for LinUCB {
+    type Output = f64;
+
+    fn evaluate(&self, (s, a): (S, A)) -> f64 { self.evaluate((s,))[*a.borrow()] }
+}
+
+impl<S: Borrow<[f64]>> Enumerable<(S,)> for LinUCB {
+    fn len(&self, _: (S,)) -> usize { self.n_actions() }
+
+    fn evaluate_index(&self, (s,): (S,), index: usize) -> f64 { self.evaluate((s, index)) }
+}
+
+impl<S: Borrow<[f64]>> Policy<S> for LinUCB {
+    type Action = usize;
+
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R, s: S) -> usize {
+        argmax_choose_rng(rng, self.bounds(s.borrow())).0
+    }
+
+    fn mode(&self, s: S) -> usize {
+        let x = s.borrow();
+
+        argmax_first((0..self.n_actions()).map(|a| inner(x, &self.theta(a)))).0
+    }
+}
+
+impl<S: Borrow<[f64]>, A: Borrow<usize>> Handler<StateActionUpdate<S, A>> for LinUCB {
+    type Response = ();
+    type Error = ();
+
+    fn handle(&mut self, msg: StateActionUpdate<S, A>) -> Result<(), ()> {
+        let x = msg.state.borrow();
+        let a = *msg.action.borrow();
+
+        // Sherman-Morrison, using the symmetry of A^{-1}:
+        // (A + x xᵀ)^{-1} = A^{-1} - A^{-1} x xᵀ A^{-1} / (1 + xᵀ A^{-1} x).
+        let u = mat_vec(&self.a_invs[a], x);
+        let denom = 1.0 + inner(x, &u);
+
+        for (i, mut row) in self.a_invs[a].outer_iter_mut().enumerate() {
+            row.scaled_add(-u[i] / denom, &u);
+        }
+
+        for (b, x) in self.bs[a].iter_mut().zip(x) {
+            *b += msg.error * x;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LinUCB;
+    use crate::{
+        fa::StateActionUpdate,
+        policies::{EnumerablePolicy, Policy},
+        Handler,
+    };
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    #[test]
+    fn test_sherman_morrison() {
+        let mut p = LinUCB::new(1, 2, 1.0);
+        let xs = [[1.0, 0.0], [0.5, 2.0], [-1.0, 1.0]];
+
+        for x in xs.iter() {
+            p.handle_unchecked(StateActionUpdate {
+                state: &x[..],
+                action: 0,
+                error: 1.0,
+            });
+        }
+
+        // A = I + Σ x xᵀ = [[3.25, 0.0], [0.0, 6.0]].
+        let a_inv = &p.a_invs[0];
+
+        assert!((a_inv[[0, 0]] - 1.0 / 3.25).abs() < 1e-12);
+        assert!((a_inv[[1, 1]] - 1.0 / 6.0).abs() < 1e-12);
+        assert!(a_inv[[0, 1]].abs() < 1e-12 && a_inv[[1, 0]].abs() < 1e-12);
+
+        // b = Σ x = [0.5, 3.0].
+        let theta = p.theta(0);
+
+        assert!((theta[0] - 0.5 / 3.25).abs() < 1e-12);
+        assert!((theta[1] - 0.5).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_sublinear_regret() {
+        // Linear contextual bandit with rewards perturbed by uniform noise on [-0.05, 0.05).
+        let thetas = [[0.6, -0.2, 0.1], [-0.3, 0.7, 0.0], [0.1, 0.1, 0.5]];
+        let mean = |a: usize, x: &[f64]| -> f64 {
+            thetas[a].iter().zip(x).map(|(t, x)| t * x).sum()
+        };
+
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut p = LinUCB::new(3, 3, 0.5);
+        let mut regret = vec![];
+
+        for _ in 0..4000 {
+            let x: Vec<f64> = (0..3).map(|_| rng.gen_range(-1.0, 1.0)).collect();
+            let a = p.sample(&mut rng, &x[..]);
+            let best = (0..3).map(|b| mean(b, &x)).fold(f64::MIN, f64::max);
+            let r = mean(a, &x) + 0.1 * (rng.gen::<f64>() - 0.5);
+
+            regret.push(best - mean(a, &x));
+
+            p.handle_unchecked(StateActionUpdate {
+                state: &x[..],
+                action: a,
+                error: r,
+            });
+        }
+
+        let first: f64 = regret[..1000].iter().sum();
+        let last: f64 = regret[3000..].iter().sum();
+
+        // Linear regret would accrue equally in every block of rounds.
+        assert!(last * 5.0 < first, "{} vs {}", first, last);
+
+        let x = [1.0, 0.0, 0.0];
+
+        assert_eq!(p.mode(&x[..]), 0);
+        assert_eq!(p.probabilities(&x[..]).len(), 3);
+    }
+}
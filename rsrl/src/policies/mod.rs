@@ -17,18 +17,26 @@ use rand::{thread_rng, Rng};
 mod greedy;
 mod random;
 mod epsilon_greedy;
+mod ucb;
+mod lin_ucb;
+mod thompson;
+mod gradient_bandit;
 
 pub use self::greedy::Greedy;
 pub use self::random::Random;
 pub use self::epsilon_greedy::EpsilonGreedy;
+pub use self::ucb::UCB1;
+pub use self::lin_ucb::LinUCB;
+pub use self::thompson::ThompsonSampling;
+pub use self::gradient_bandit::GradientBandit;
 
 mod beta;
 mod gaussian;
 mod softmax;
 
 pub use self::beta::Beta;
-pub use self::gaussian::Gaussian;
-pub use self::softmax::{Gibbs, Softmax};
+pub use self::gaussian::{Clip, Gaussian};
+pub use self::softmax::{Gibbs, LinearSoftmax, Softmax};
 
 mod ipp;
 mod point;
@@ -87,12 +95,31 @@ impl<S, T: Policy<S>> Policy<S> for Shared<T> {
     fn mode(&self, state: S) -> Self::Action { self.borrow().mode(state) }
 }
 
+/// Trait for exploratory policies that can be made to act greedily.
+pub trait Exploratory: Sized {
+    /// Return a copy of the policy with exploration turned off, e.g. for
+    /// evaluating an agent without perturbing its training configuration.
+    ///
+    /// Any (shared) value function underlying the policy is cloned as per its
+    /// `Clone` implementation, so updates to `Shared` weights remain visible
+    /// to the copy.
+    fn clone_for_eval(&self) -> Self;
+}
+
+impl<T: Exploratory> Exploratory for Shared<T> {
+    fn clone_for_eval(&self) -> Self { crate::make_shared(self.borrow().clone_for_eval()) }
+}
+
 /// Trait for policies that are defined on an enumerable action space.
 pub trait EnumerablePolicy<S>: Policy<S, Action = usize> + Enumerable<(S,)>
 where
     OutputOf<Self, (S,)>: std::ops::Index<usize, Output = f64> + IntoIterator<Item = f64>,
     <OutputOf<Self, (S,)> as IntoIterator>::IntoIter: ExactSizeIterator,
 {
+    /// Return the probability of selecting each action in a given `state`.
+    ///
+    /// The returned vector is indexed by action and sums to one.
+    fn probabilities(&self, state: S) -> Vec<f64> { self.evaluate((state,)).into_iter().collect() }
 }
 
 impl<S, P> EnumerablePolicy<S> for P
@@ -104,6 +131,23 @@ where
 {
 }
 
+/// Trait for policies whose entropy, and its gradient wrt the policy
+/// parameters, can be computed in closed form.
+pub trait Entropy<S> {
+    /// Return the entropy of the action distribution in a given `state`.
+    fn entropy(&self, state: S) -> f64;
+
+    /// Return the gradient of the entropy in a given `state` wrt the policy
+    /// parameters.
+    fn grad_entropy(&self, state: S) -> Array2<f64>;
+}
+
+impl<S, T: Entropy<S>> Entropy<S> for Shared<T> {
+    fn entropy(&self, state: S) -> f64 { self.borrow().entropy(state) }
+
+    fn grad_entropy(&self, state: S) -> Array2<f64> { self.borrow().grad_entropy(state) }
+}
+
 /// Trait for policies with a representation that is differentiable wrt its
 /// parameters.
 pub trait DifferentiablePolicy<S>:
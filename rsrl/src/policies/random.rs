@@ -1,9 +1,17 @@
-use crate::{policies::Policy, Enumerable, Function};
-use rand::{
-    distributions::{Distribution, Uniform},
-    Rng,
+use crate::{
+    domains::SampleSpace,
+    policies::Policy,
+    spaces::{discrete::Ordinal, FiniteSpace},
+    Enumerable,
+    Function,
 };
+use rand::Rng;
 
+/// Uniform random policy over a finite set of actions.
+///
+/// The state is ignored entirely: every action is selected with probability
+/// `1 / n_actions`, which makes this the natural baseline against which to
+/// compare learned behaviour.
 #[derive(Clone, Debug)]
 #[cfg_attr(
     feature = "serde",
@@ -15,6 +23,15 @@ pub struct Random(usize);
 impl Random {
     pub fn new(n_actions: usize) -> Self { Random(n_actions) }
 
+    /// Construct a policy over every action in the given action space.
+    pub fn from_space(space: &Ordinal) -> Self { Random(space.range().len()) }
+
+    /// Return the number of actions.
+    pub fn n_actions(&self) -> usize { self.0 }
+
+    /// Return the (uniform) probability of selecting each action.
+    pub fn probabilities(&self) -> Vec<f64> { vec![self.prob(); self.0] }
+
     #[inline(always)]
     fn prob(&self) -> f64 { 1.0 / self.0 as f64 }
 }
@@ -22,7 +39,7 @@ impl Random {
 impl<S> Function<(S,)> for Random {
     type Output = Vec<f64>;
 
-    fn evaluate(&self, _: (S,)) -> Vec<f64> { vec![self.prob(); self.0] }
+    fn evaluate(&self, _: (S,)) -> Vec<f64> { self.probabilities() }
 }
 
 impl<S, A: std::borrow::Borrow<usize>> Function<(S, A)> for Random {
@@ -41,7 +58,7 @@ impl<S> Policy<S> for Random {
     type Action = usize;
 
     fn sample<R: Rng + ?Sized>(&self, rng: &mut R, _: S) -> usize {
-        Uniform::new(0, self.0).sample(rng)
+        Ordinal::new(self.0).sample(rng)
     }
 
     fn mode(&self, _: S) -> usize { panic!("Random policy has no mode.") }
@@ -50,10 +67,12 @@ impl<S> Policy<S> for Random {
 #[cfg(test)]
 mod tests {
     use crate::{
-        policies::{EnumerablePolicy, Policy, Random},
+        policies::{Policy, Random},
+        spaces::discrete::Ordinal,
+        Enumerable,
         Function,
     };
-    use rand::thread_rng;
+    use rand::{rngs::StdRng, thread_rng, SeedableRng};
 
     #[test]
     fn test_sampling() {
@@ -75,28 +94,32 @@ mod tests {
         assert!((0.50 - n1 / 10000.0).abs() < 0.05);
     }
 
-    // #[test]
-    // fn test_probabilites() {
-        // let p = Random::new(4);
-
-        // assert!(compare_floats(
-            // p.evaluate((&vec![1.0, 0.0, 0.0, 1.0],)),
-            // &[0.25; 4],
-            // 1e-6
-        // ));
-
-        // let p = Random::new(5);
-
-        // assert!(compare_floats(
-            // p.evaluate((&vec![1.0, 0.0, 0.0, 0.0, 0.0],)),
-            // &[0.2; 5],
-            // 1e-6
-        // ));
-
-        // assert!(compare_floats(
-            // p.evaluate((&vec![0.0, 0.0, 0.0, 0.0, 1.0],)),
-            // &[0.2; 5],
-            // 1e-6
-        // ));
-    // }
+    #[test]
+    fn test_uniform_frequencies() {
+        let p = Random::from_space(&Ordinal::new(5));
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut counts = [0usize; 5];
+
+        assert_eq!(p.n_actions(), 5);
+
+        for _ in 0..50000 {
+            counts[p.sample(&mut rng, ())] += 1;
+        }
+
+        assert!(counts.iter().all(|&c| (c as f64 / 50000.0 - 0.2).abs() < 0.01));
+    }
+
+    #[test]
+    fn test_probabilities() {
+        let p = Random::new(4);
+
+        assert_eq!(p.probabilities(), vec![0.25; 4]);
+        assert_eq!(p.evaluate((&vec![1.0, 0.0, 0.0, 1.0],)), vec![0.25; 4]);
+        assert_eq!(p.evaluate((&vec![1.0, 0.0, 0.0, 1.0], 3)), 0.25);
+
+        let p = Random::new(5);
+
+        assert!(p.evaluate((&vec![0.0, 0.0, 0.0, 0.0, 1.0],)).iter().all(|&x| x == 0.2));
+        assert_eq!(p.evaluate_index((0,), 2), 0.2);
+    }
 }
@@ -1,8 +1,14 @@
 use crate::{
-    fa::{GradientUpdate, ScaledGradientUpdate, StateActionUpdate},
+    fa::{
+        linear::{basis::Basis, Features, FeaturesExt},
+        GradientUpdate,
+        ScaledGradientUpdate,
+        StateActionUpdate,
+    },
     params::*,
-    policies::{sample_probs_with_rng, Policy},
-    utils::argmax_first,
+    policies::{sample_probs_with_rng, Entropy, Exploratory, Policy},
+    schedule::Parameter,
+    utils::{argmax_first, argmaxima},
     Differentiable,
     Enumerable,
     Function,
@@ -10,6 +16,7 @@ use crate::{
 };
 use ndarray::{Array2, ArrayBase, Data, Ix2};
 use rand::Rng;
+use spaces::Space;
 use std::{f64, iter::FromIterator};
 
 fn softmax<C: FromIterator<f64>>(values: &[f64], tau: f64, c: f64) -> C {
@@ -36,6 +43,13 @@ fn softmax_stable<C: FromIterator<f64>>(values: &[f64], tau: f64) -> C {
     softmax(values, tau, max_v)
 }
 
+fn greedy<C: FromIterator<f64>>(values: &[f64]) -> C {
+    let (maxima, _) = argmaxima(values.iter().cloned());
+    let p = 1.0 / maxima.len() as f64;
+
+    (0..values.len()).map(|i| if maxima.contains(&i) { p } else { 0.0 }).collect()
+}
+
 pub type Gibbs<F> = Softmax<F>;
 
 #[derive(Clone, Copy, Debug)]
@@ -56,7 +70,12 @@ pub struct Softmax<F> {
     #[weights]
     fa: F,
 
-    pub tau: f64,
+    /// Temperature, advanced only by explicit calls to `step`; constant by
+    /// default.
+    pub tau: Parameter,
+
+    /// If true, act in the zero temperature limit, ignoring `tau`.
+    greedy: bool,
 }
 
 impl<F> Softmax<F> {
@@ -65,10 +84,38 @@ impl<F> Softmax<F> {
             panic!("Tau parameter in Softmax must be non-zero.");
         }
 
-        Softmax { fa, tau }
+        Softmax {
+            fa,
+            tau: Parameter::constant(tau),
+            greedy: false,
+        }
     }
 
     pub fn standard(fa: F) -> Self { Self::new(fa, 1.0) }
+
+    /// Anneal the temperature according to `schedule`.
+    ///
+    /// # Panics
+    /// If the initial value of `schedule` is zero.
+    pub fn with_tau_schedule(mut self, schedule: Parameter) -> Self {
+        if schedule.value().abs() < 1e-7 {
+            panic!("Tau parameter in Softmax must be non-zero.");
+        }
+
+        self.tau = schedule;
+        self
+    }
+}
+
+impl<F: Clone> Exploratory for Softmax<F> {
+    /// Return a copy of the policy in the zero temperature limit, which places
+    /// all probability mass on the maximising action(s).
+    fn clone_for_eval(&self) -> Self {
+        Softmax {
+            greedy: true,
+            ..self.clone()
+        }
+    }
 }
 
 impl<'s, S, F: Function<(&'s S,), Output = Vec<f64>>> Function<(&'s S,)> for Softmax<F> {
@@ -77,7 +124,11 @@ impl<'s, S, F: Function<(&'s S,), Output = Vec<f64>>> Function<(&'s S,)> for Sof
     fn evaluate(&self, (s,): (&'s S,)) -> Vec<f64> {
         let values = self.fa.evaluate((s,));
 
-        softmax_stable(&values, self.tau)
+        if self.greedy {
+            greedy(&values)
+        } else {
+            softmax_stable(&values, self.tau.value())
+        }
     }
 }
 
@@ -128,6 +179,35 @@ where
     }
 }
 
+impl<'s, S, F> Entropy<&'s S> for Softmax<F>
+where
+    F: Function<(&'s S, usize), Output = f64> + Parameterised,
+    F: Enumerable<(&'s S,), Output = Vec<f64>>,
+    F: Differentiable<(&'s S, usize)>,
+{
+    fn entropy(&self, s: &'s S) -> f64 {
+        -self
+            .evaluate((s,))
+            .into_iter()
+            .filter(|&p| p > 0.0)
+            .map(|p| p * p.ln())
+            .sum::<f64>()
+    }
+
+    // Since Σ_a π(a|s) ∇ln π(a|s) = 0, ∇H = -Σ_a π(a|s) ln π(a|s) ∇ln π(a|s).
+    fn grad_entropy(&self, s: &'s S) -> Array2<f64> {
+        let mut jac = Array2::zeros(self.weights_dim());
+
+        for (a, p) in self.evaluate((s,)).into_iter().enumerate() {
+            if p > 0.0 {
+                jac.scaled_add(-p * p.ln(), &self.grad_log((s, a)));
+            }
+        }
+
+        jac
+    }
+}
+
 impl<'s, S, F> Policy<&'s S> for Softmax<F>
 where
     F: Function<(&'s S, usize), Output = f64> + Parameterised,
@@ -221,20 +301,203 @@ where
     }
 }
 
+/// Softmax policy over linear action preferences.
+///
+/// The preference for action `a` in state `s` is `h(s, a) = φ(s)ᵀ θ_a`, where
+/// `φ(s)` is the projection of `s` under `basis` and `θ_a` is the `a`th column
+/// of the weight matrix, `theta`. Actions are then selected with probability
+/// `π(a | s) ∝ exp(h(s, a) / τ)`, for which the gradient of the log-policy
+/// has the closed form
+///
+/// `∇_θ ln π(a | s) = φ(s) (e_a - π(· | s))ᵀ / τ`,
+///
+/// where `e_a` is the indicator vector of action `a`. Unlike [`Softmax`],
+/// which wraps an arbitrary differentiable function, the preferences and
+/// gradients are computed directly from the active features.
+#[derive(Clone, Debug, Parameterised)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate")
+)]
+pub struct LinearSoftmax<B> {
+    pub basis: B,
+
+    #[weights]
+    pub theta: Array2<f64>,
+
+    pub tau: f64,
+}
+
+impl<B: Space> LinearSoftmax<B> {
+    pub fn new(basis: B, n_actions: usize, tau: f64) -> Self {
+        if tau.abs() < 1e-7 {
+            panic!("Tau parameter in LinearSoftmax must be non-zero.");
+        }
+
+        let n_features: usize = basis.dim().into();
+
+        LinearSoftmax {
+            basis,
+            theta: Array2::zeros((n_features, n_actions)),
+            tau,
+        }
+    }
+
+    pub fn standard(basis: B, n_actions: usize) -> Self { Self::new(basis, n_actions, 1.0) }
+}
+
+impl<B> LinearSoftmax<B> {
+    pub fn n_actions(&self) -> usize { self.theta.ncols() }
+
+    fn project<S>(&self, s: S) -> crate::fa::linear::Result<Features>
+    where B: Basis<S, Value = Features> {
+        self.basis.project(s)
+    }
+
+    fn preferences_of(&self, phi: &Features) -> Vec<f64> {
+        let mut prefs = vec![0.0; self.n_actions()];
+
+        for (i, x) in phi.iter_active() {
+            for (p, w) in prefs.iter_mut().zip(self.theta.row(i)) {
+                *p += x * w;
+            }
+        }
+
+        prefs
+    }
+
+    fn grad_log_of(&self, phi: &Features, a: usize) -> Array2<f64> {
+        let probs: Vec<f64> = softmax_stable(&self.preferences_of(phi), self.tau);
+        let mut jac = Array2::zeros(self.theta.dim());
+
+        for (i, x) in phi.iter_active() {
+            for (b, (g, p)) in jac.row_mut(i).iter_mut().zip(probs.iter()).enumerate() {
+                let indicator = if b == a { 1.0 } else { 0.0 };
+
+                *g += x * (indicator - p) / self.tau;
+            }
+        }
+
+        jac
+    }
+
+    /// Return the action preferences, `h(s, ·)`, in state `s`.
+    pub fn preferences<S>(&self, s: S) -> Vec<f64>
+    where B: Basis<S, Value = Features> {
+        self.preferences_of(&self.project(s).unwrap())
+    }
+
+    /// Return the action probabilities, `π(· | s)`, in state `s`.
+    pub fn probabilities<S>(&self, s: S) -> Vec<f64>
+    where B: Basis<S, Value = Features> {
+        softmax_stable(&self.preferences(s), self.tau)
+    }
+}
+
+impl<S, B> Function<(S,)> for LinearSoftmax<B>
+where B: Basis<S, Value = Features>
+{
+    type Output = Vec<f64>;
+
+    fn evaluate(&self, (s,): (S,)) -> Vec<f64> { self.probabilities(s) }
+}
+
+impl<S, A, B> Function<(S, A)> for LinearSoftmax<B>
+where
+    A: std::borrow::Borrow<usize>,
+    B: Basis<S, Value = Features>,
+{
+    type Output = f64;
+
+    fn evaluate(&self, (s, a): (S, A)) -> f64 { self.probabilities(s)[*a.borrow()] }
+}
+
+impl<S, B> Enumerable<(S,)> for LinearSoftmax<B>
+where B: Basis<S, Value = Features>
+{
+    fn len(&self, _: (S,)) -> usize { self.n_actions() }
+}
+
+impl<S, A, B> Differentiable<(S, A)> for LinearSoftmax<B>
+where
+    A: std::borrow::Borrow<usize>,
+    B: Basis<S, Value = Features>,
+{
+    type Jacobian = Array2<f64>;
+
+    fn grad(&self, (s, a): (S, A)) -> Array2<f64> {
+        let a = *a.borrow();
+        let phi = self.project(s).unwrap();
+        let p: Vec<f64> = softmax_stable(&self.preferences_of(&phi), self.tau);
+
+        self.grad_log_of(&phi, a) * p[a]
+    }
+
+    fn grad_log(&self, (s, a): (S, A)) -> Array2<f64> {
+        self.grad_log_of(&self.project(s).unwrap(), *a.borrow())
+    }
+}
+
+impl<S, B> Policy<S> for LinearSoftmax<B>
+where B: Basis<S, Value = Features>
+{
+    type Action = usize;
+
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R, s: S) -> usize {
+        sample_probs_with_rng(rng, &self.probabilities(s))
+    }
+
+    fn mode(&self, s: S) -> usize { argmax_first(self.probabilities(s)).0 }
+}
+
+impl<S, A, B> Handler<StateActionUpdate<S, A>> for LinearSoftmax<B>
+where
+    A: std::borrow::Borrow<usize>,
+    B: Basis<S, Value = Features>,
+{
+    type Response = ();
+    type Error = crate::fa::linear::Error;
+
+    fn handle(&mut self, msg: StateActionUpdate<S, A>) -> crate::fa::linear::Result<()> {
+        let phi = self.project(msg.state)?;
+        let jac = self.grad_log_of(&phi, *msg.action.borrow());
+
+        self.theta.scaled_add(msg.error, &jac);
+
+        Ok(())
+    }
+}
+
+impl<'m, D, B> Handler<ScaledGradientUpdate<&'m ArrayBase<D, Ix2>>> for LinearSoftmax<B>
+where D: Data<Elem = f64>
+{
+    type Response = ();
+    type Error = Error;
+
+    fn handle(&mut self, msg: ScaledGradientUpdate<&'m ArrayBase<D, Ix2>>) -> Result<(), Error> {
+        self.theta.scaled_add(msg.alpha, msg.jacobian);
+
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::{
         fa::{
             linear::{
-                basis::{Basis, Polynomial},
+                basis::{Basis, Closure, Polynomial},
                 optim::SGD,
                 LFA,
             },
             mocking::MockQ,
+            tabular::Table,
         },
     };
-    use rand::thread_rng;
+    use approx::assert_abs_diff_eq;
+    use rand::{rngs::StdRng, thread_rng, SeedableRng};
     use std::f64::consts::E;
 
     #[test]
@@ -255,40 +518,190 @@ mod tests {
         }
     }
 
-    // #[test]
-    // fn test_2d() {
-        // let p = Softmax::new(MockQ::new_shared(None), 1.0);
-        // let mut rng = thread_rng();
-        // let mut counts = vec![0.0, 0.0];
-
-        // for _ in 0..50000 {
-            // counts[p.sample(&mut rng, &vec![0.0, 1.0])] += 1.0;
-        // }
-
-        // let means: Vec<f64> = counts.into_iter().map(|v| v / 50000.0).collect();
-
-        // assert!(compare_floats(
-            // means,
-            // &[1.0 / (1.0 + E), E / (1.0 + E)],
-            // 1e-2
-        // ));
-    // }
+    #[test]
+    fn test_2d() {
+        let p = Softmax::new(MockQ::new_shared(None), 1.0);
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut counts = [0.0, 0.0];
 
-    // #[test]
-    // fn test_probabilites_1() {
-        // let p = Softmax::new(MockQ::new_shared(None), 1.0);
-
-        // assert!(compare_floats(
-            // p.evaluate((&vec![0.0, 1.0],)),
-            // &[1.0 / (1.0 + E), E / (1.0 + E)],
-            // 1e-6
-        // ));
-        // assert!(compare_floats(
-            // p.evaluate((&vec![0.0, 2.0],)),
-            // &[1.0 / (1.0 + E * E), E * E / (1.0 + E * E)],
-            // 1e-6
-        // ));
-    // }
+        for _ in 0..50000 {
+            counts[p.sample(&mut rng, &vec![0.0, 1.0])] += 1.0;
+        }
+
+        assert_abs_diff_eq!(counts[0] / 50000.0, 1.0 / (1.0 + E), epsilon = 1e-2);
+        assert_abs_diff_eq!(counts[1] / 50000.0, E / (1.0 + E), epsilon = 1e-2);
+    }
+
+    #[test]
+    fn test_probabilites_1() {
+        let p = Softmax::new(MockQ::new_shared(None), 1.0);
+
+        p.evaluate((&vec![0.0, 1.0],))
+            .into_iter()
+            .zip([1.0 / (1.0 + E), E / (1.0 + E)].iter())
+            .for_each(|(x, y)| assert_abs_diff_eq!(x, y, epsilon = 1e-6));
+
+        p.evaluate((&vec![0.0, 2.0],))
+            .into_iter()
+            .zip([1.0 / (1.0 + E * E), E * E / (1.0 + E * E)].iter())
+            .for_each(|(x, y)| assert_abs_diff_eq!(x, y, epsilon = 1e-6));
+    }
+
+    #[test]
+    fn test_entropy() {
+        let p = Softmax::standard(Table::dense(Array2::zeros((1, 3))));
+
+        assert!((p.entropy(&0) - 3.0f64.ln()).abs() < 1e-10);
+        assert!(p.grad_entropy(&0).iter().all(|&g| g.abs() < 1e-10));
+
+        let p = Softmax::standard(Table::dense(ndarray::array![[1.0, 0.0]]));
+        let grad = p.grad_entropy(&0);
+
+        // Entropy increases by moving the preferences closer together.
+        assert!(p.entropy(&0) < 2.0f64.ln());
+        assert!(grad[[0, 0]] < 0.0 && grad[[0, 1]] > 0.0);
+    }
+
+    #[test]
+    fn test_clone_for_eval() {
+        let p = Softmax::new(MockQ::new_shared(None), 1.0).clone_for_eval();
+
+        assert_eq!(p.evaluate((&vec![0.0, 1e-3, -5.0],)), vec![0.0, 1.0, 0.0]);
+        assert_eq!(p.evaluate((&vec![1e6, -1e6],)), vec![1.0, 0.0]);
+        assert_eq!(p.evaluate((&vec![2.0, -1.0, 2.0],)), vec![0.5, 0.0, 0.5]);
+    }
+
+    #[test]
+    fn test_overflow() {
+        let p = Softmax::new(MockQ::new_shared(None), 1.0);
+        let ps = p.evaluate((&vec![1e6, 1e6 - 1.0],));
+
+        assert!(ps.iter().all(|p| p.is_finite()));
+        assert_abs_diff_eq!(ps[0], E / (1.0 + E), epsilon = 1e-6);
+    }
+
+    #[test]
+    fn test_tau_limits() {
+        let qs = vec![0.0, 1.0, 0.5];
+
+        let mut p = Softmax::new(MockQ::new_shared(None), 1.0)
+            .with_tau_schedule(Parameter::exponential(1.0, 1e-3, 1e-3));
+
+        // Annealing towards zero approaches the greedy distribution...
+        p.tau.step();
+        p.evaluate((&qs,))
+            .into_iter()
+            .zip([0.0, 1.0, 0.0].iter())
+            .for_each(|(x, y)| assert_abs_diff_eq!(x, y, epsilon = 1e-6));
+
+        // ...and increasing towards infinity approaches the uniform distribution.
+        p.tau = Parameter::constant(1e9);
+        p.evaluate((&qs,))
+            .into_iter()
+            .for_each(|x| assert_abs_diff_eq!(x, 1.0 / 3.0, epsilon = 1e-6));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_zero_tau_schedule() {
+        Softmax::new(MockQ::new_shared(None), 1.0).with_tau_schedule(Parameter::constant(0.0));
+    }
+
+    type LinearBasis = Closure<fn(&usize) -> crate::fa::linear::Result<Features>>;
+
+    fn linear_softmax(tau: f64) -> LinearSoftmax<LinearBasis> {
+        let basis: LinearBasis = Closure::new(3, |s: &usize| {
+            Ok(Features::dense(vec![1.0, *s as f64, -0.5]))
+        });
+        let mut p = LinearSoftmax::new(basis, 3, tau);
+
+        p.theta = ndarray::array![[0.1, -0.2, 0.3], [0.5, 0.0, -0.4], [-0.3, 0.2, 0.7]];
+        p
+    }
+
+    #[test]
+    fn test_linear_probabilities() {
+        let p = linear_softmax(1.0);
+        let prefs = p.preferences(&2);
+        let probs = p.probabilities(&2);
+
+        assert_abs_diff_eq!(prefs[0], 0.1 + 1.0 + 0.15, epsilon = 1e-12);
+        assert_abs_diff_eq!(probs.iter().sum::<f64>(), 1.0, epsilon = 1e-12);
+        assert_abs_diff_eq!(probs[1] / probs[0], (prefs[1] - prefs[0]).exp(), epsilon = 1e-12);
+        assert_eq!(p.mode(&2), 0);
+        assert_eq!(p.evaluate((&2, 1)), probs[1]);
+    }
+
+    #[test]
+    fn test_linear_grad_log_finite_difference() {
+        let eps = 1e-6;
+
+        for &tau in [1.0, 0.5].iter() {
+            let p = linear_softmax(tau);
+
+            for s in 0..3 {
+                for a in 0..3 {
+                    let jac = p.grad_log((&s, a));
+
+                    for (idx, g) in jac.indexed_iter() {
+                        let mut hi = linear_softmax(tau);
+                        let mut lo = linear_softmax(tau);
+
+                        hi.theta[idx] += eps;
+                        lo.theta[idx] -= eps;
+
+                        let fd = (hi.probabilities(&s)[a].ln() - lo.probabilities(&s)[a].ln())
+                            / (2.0 * eps);
+
+                        assert_abs_diff_eq!(*g, fd, epsilon = 1e-6);
+                    }
+
+                    let grad = p.grad((&s, a));
+                    let pa = p.probabilities(&s)[a];
+
+                    jac.iter()
+                        .zip(grad.iter())
+                        .for_each(|(gl, g)| assert_abs_diff_eq!(gl * pa, g, epsilon = 1e-12));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_linear_update() {
+        let mut p = linear_softmax(1.0);
+        let before = p.probabilities(&1)[2];
+
+        p.handle(StateActionUpdate {
+            state: &1,
+            action: 2,
+            error: 0.5,
+        })
+        .unwrap();
+
+        assert!(p.probabilities(&1)[2] > before);
+    }
+
+    #[test]
+    fn test_linear_update_propagates_basis_error() {
+        let basis = Closure::new(3, |s: &usize| {
+            if *s < 3 {
+                Ok(Features::dense(vec![1.0, *s as f64, -0.5]))
+            } else {
+                Err(crate::fa::linear::Error::index_error(*s, 3))
+            }
+        });
+        let mut p = LinearSoftmax::new(basis, 3, 1.0);
+
+        assert!(p
+            .handle(StateActionUpdate {
+                state: &3,
+                action: 0,
+                error: 1.0,
+            })
+            .is_err());
+        assert_eq!(p.theta, Array2::<f64>::zeros((3, 3)));
+    }
 
     // #[test]
     // fn test_probabilities_2() {
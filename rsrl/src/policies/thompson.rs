@@ -0,0 +1,212 @@
+use crate::{fa::StateActionUpdate, policies::Policy, Enumerable, Function, Handler};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use rand_distr::{Beta, Distribution};
+
+// Number of posterior draws used to estimate action probabilities.
+const N_PROBABILITY_SAMPLES: usize = 1000;
+
+// Seed of the posterior draws used to estimate action probabilities.
+const PROBABILITY_SEED: u64 = 0;
+
+/// Thompson sampling for Bernoulli bandits.
+///
+/// Maintains an independent `Beta(α, β)` posterior over the success
+/// probability of each action, and selects the action with the highest draw
+/// from its posterior. Rewards are passed as the `error` of a
+/// `StateActionUpdate` and should lie in `[0, 1]`: a reward `r` increments `α`
+/// by `r` and `β` by `1 - r`, after clipping `r` to `[0, 1]`. The state is
+/// ignored.
+///
+/// Since the probability of selecting each action has no closed form, the
+/// `Function` implementations return a Monte Carlo estimate. The estimate is
+/// drawn from a fixed seed, so that it depends only on the posterior.
+///
+/// # References
+/// - Thompson, W. R. (1933). On the likelihood that one unknown probability
+///   exceeds another in view of the evidence of two samples. Biometrika,
+///   25(3/4), 285-294.
+#[derive(Clone, Debug)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate")
+)]
+pub struct ThompsonSampling {
+    alphas: Vec<f64>,
+    betas: Vec<f64>,
+}
+
+impl ThompsonSampling {
+    /// Construct a new policy with a uniform `Beta(1, 1)` prior on each action.
+    pub fn new(n_actions: usize) -> Self { ThompsonSampling::with_prior(n_actions, 1.0, 1.0) }
+
+    /// Construct a new policy with a `Beta(alpha, beta)` prior on each action.
+    ///
+    /// # Panics
+    /// If `alpha` or `beta` is not strictly positive.
+    pub fn with_prior(n_actions: usize, alpha: f64, beta: f64) -> Self {
+        assert!(alpha > 0.0 && beta > 0.0, "Beta prior parameters must be positive.");
+
+        ThompsonSampling {
+            alphas: vec![alpha; n_actions],
+            betas: vec![beta; n_actions],
+        }
+    }
+
+    /// Return the `α` parameter of each action's posterior.
+    pub fn alphas(&self) -> &[f64] { &self.alphas }
+
+    /// Return the `β` parameter of each action's posterior.
+    pub fn betas(&self) -> &[f64] { &self.betas }
+
+    fn draw<R: Rng + ?Sized>(&self, rng: &mut R) -> usize {
+        self.alphas
+            .iter()
+            .zip(self.betas.iter())
+            .map(|(&a, &b)| Beta::new(a, b).unwrap().sample(rng))
+            .enumerate()
+            .fold((0, f64::MIN), |(i, x), (j, y)| if y > x { (j, y) } else { (i, x) })
+            .0
+    }
+}
+
+impl<S> Function<(S,)> for ThompsonSampling {
+    type Output = Vec<f64>;
+
+    fn evaluate(&self, _: (S,)) -> Vec<f64> {
+        let mut rng = StdRng::seed_from_u64(PROBABILITY_SEED);
+        let mut ps = vec![0.0; self.alphas.len()];
+
+        for _ in 0..N_PROBABILITY_SAMPLES {
+            ps[self.draw(&mut rng)] += 1.0 / N_PROBABILITY_SAMPLES as f64;
+        }
+
+        ps
+    }
+}
+
+impl<S, A: std::borrow::Borrow<usize>> Function<(S, A)> for ThompsonSampling {
+    type Output = f64;
+
+    fn evaluate(&self, (s, a): (S, A)) -> f64 { self.evaluate((s,))[*a.borrow()] }
+}
+
+impl<S> Enumerable<(S,)> for ThompsonSampling {
+    fn len(&self, _: (S,)) -> usize { self.alphas.len() }
+
+    fn evaluate_index(&self, (s,): (S,), index: usize) -> f64 { self.evaluate((s, index)) }
+}
+
+impl<S> Policy<S> for ThompsonSampling {
+    type Action = usize;
+
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R, _: S) -> usize { self.draw(rng) }
+
+    fn mode(&self, _: S) -> usize {
+        self.alphas
+            .iter()
+            .zip(self.betas.iter())
+            .map(|(&a, &b)| a / (a + b))
+            .enumerate()
+            .fold((0, f64::MIN), |(i, x), (j, y)| if y > x { (j, y) } else { (i, x) })
+            .0
+    }
+}
+
+impl<S, A: std::borrow::Borrow<usize>> Handler<StateActionUpdate<S, A>> for ThompsonSampling {
+    type Response = ();
+    type Error = ();
+
+    fn handle(&mut self, msg: StateActionUpdate<S, A>) -> Result<(), ()> {
+        let a = *msg.action.borrow();
+        let r = msg.error.clamp(0.0, 1.0);
+
+        self.alphas[a] += r;
+        self.betas[a] += 1.0 - r;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ThompsonSampling;
+    use crate::{
+        fa::StateActionUpdate,
+        policies::{EnumerablePolicy, Policy},
+        Handler,
+    };
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    fn pull(p: &mut ThompsonSampling, a: usize, r: f64) {
+        p.handle_unchecked(StateActionUpdate {
+            state: (),
+            action: a,
+            error: r,
+        });
+    }
+
+    #[test]
+    fn test_posterior_update() {
+        let mut p = ThompsonSampling::new(2);
+
+        pull(&mut p, 0, 1.0);
+        pull(&mut p, 1, 0.0);
+        pull(&mut p, 1, 0.0);
+
+        assert_eq!(p.alphas(), &[2.0, 1.0]);
+        assert_eq!(p.betas(), &[1.0, 3.0]);
+        assert_eq!(p.mode(()), 0);
+
+        let ps = p.probabilities(());
+
+        assert!((ps.iter().sum::<f64>() - 1.0).abs() < 1e-6);
+        assert!(ps[0] > ps[1]);
+    }
+
+    #[test]
+    fn test_deterministic_probabilities() {
+        let mut p = ThompsonSampling::new(3);
+
+        pull(&mut p, 0, 1.0);
+        pull(&mut p, 2, 0.0);
+
+        assert_eq!(p.probabilities(()), p.probabilities(()));
+    }
+
+    #[test]
+    fn test_rewards_clipped() {
+        let mut p = ThompsonSampling::new(2);
+
+        pull(&mut p, 0, 2.0);
+        pull(&mut p, 1, -1.0);
+
+        assert_eq!(p.alphas(), &[2.0, 1.0]);
+        assert_eq!(p.betas(), &[1.0, 2.0]);
+        assert!((p.probabilities(()).iter().sum::<f64>() - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_invalid_prior() { ThompsonSampling::with_prior(2, 0.0, 1.0); }
+
+    #[test]
+    fn test_bandit_convergence() {
+        let arms = [0.2, 0.5, 0.8];
+
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut p = ThompsonSampling::new(3);
+        let mut counts = [0usize; 3];
+
+        for _ in 0..2000 {
+            let a = p.sample(&mut rng, ());
+            let r = if rng.gen_bool(arms[a]) { 1.0 } else { 0.0 };
+
+            counts[a] += 1;
+            pull(&mut p, a, r);
+        }
+
+        assert_eq!(p.mode(()), 2);
+        assert!(counts[2] > 4 * (counts[0] + counts[1]));
+    }
+}
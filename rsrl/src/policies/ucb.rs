@@ -0,0 +1,195 @@
+use crate::{
+    fa::StateActionUpdate,
+    policies::Policy,
+    utils::{argmax_first, argmaxima},
+    Enumerable,
+    Function,
+    Handler,
+};
+use rand::{seq::SliceRandom, Rng};
+
+/// Upper confidence bound (UCB1) action selection.
+///
+/// Maintains a visit count, `N_a`, and sample mean reward, `Q_a`, for each
+/// action and selects `argmax_a Q_a + c sqrt(ln t / N_a)`, where `t` is the
+/// total number of pulls. Any action that has not yet been taken is selected
+/// before all others. The statistics are updated by passing the observed
+/// reward as the `error` of a `StateActionUpdate`; the state is ignored.
+///
+/// # References
+/// - Auer, P., Cesa-Bianchi, N., & Fischer, P. (2002). Finite-time analysis of
+///   the multiarmed bandit problem. Machine Learning, 47(2-3), 235-256.
+#[derive(Clone, Debug)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate")
+)]
+pub struct UCB1 {
+    pub c: f64,
+
+    counts: Vec<usize>,
+    means: Vec<f64>,
+}
+
+impl UCB1 {
+    pub fn new(n_actions: usize, c: f64) -> Self {
+        UCB1 {
+            c,
+
+            counts: vec![0; n_actions],
+            means: vec![0.0; n_actions],
+        }
+    }
+
+    /// Return the number of times each action has been taken.
+    pub fn counts(&self) -> &[usize] { &self.counts }
+
+    /// Return the sample mean reward of each action.
+    pub fn means(&self) -> &[f64] { &self.means }
+
+    /// Return the upper confidence bound of each action; unvisited actions
+    /// have an infinite bound.
+    pub fn bounds(&self) -> Vec<f64> {
+        let ln_t = (self.counts.iter().sum::<usize>() as f64).ln();
+
+        self.counts
+            .iter()
+            .zip(self.means.iter())
+            .map(|(&n, &q)| {
+                if n == 0 {
+                    f64::INFINITY
+                } else {
+                    q + self.c * (ln_t / n as f64).sqrt()
+                }
+            })
+            .collect()
+    }
+
+    /// Return the actions that UCB1 would select between: every unvisited
+    /// action if there are any, otherwise those with the largest bound.
+    ///
+    /// Unvisited actions are handled explicitly, rather than through their
+    /// infinite bounds, since ties between infinities cannot be resolved by
+    /// comparing their differences.
+    fn candidates(&self) -> Vec<usize> {
+        let unvisited: Vec<usize> = (0..self.counts.len())
+            .filter(|&a| self.counts[a] == 0)
+            .collect();
+
+        if unvisited.is_empty() {
+            argmaxima(self.bounds()).0
+        } else {
+            unvisited
+        }
+    }
+}
+
+impl<S> Function<(S,)> for UCB1 {
+    type Output = Vec<f64>;
+
+    fn evaluate(&self, _: (S,)) -> Vec<f64> {
+        let mut ps = vec![0.0; self.counts.len()];
+
+        let maxima = self.candidates();
+
+        let p = 1.0 / maxima.len() as f64;
+        for i in maxima {
+            ps[i] = p;
+        }
+
+        ps
+    }
+}
+
+impl<S, A: std::borrow::Borrow<usize>> Function<(S, A)> for UCB1 {
+    type Output = f64;
+
+    fn evaluate(&self, (s, a): (S, A)) -> f64 { self.evaluate((s,))[*a.borrow()] }
+}
+
+impl<S> Enumerable<(S,)> for UCB1 {
+    fn len(&self, _: (S,)) -> usize { self.counts.len() }
+
+    fn evaluate_index(&self, (s,): (S,), index: usize) -> f64 { self.evaluate((s, index)) }
+}
+
+impl<S> Policy<S> for UCB1 {
+    type Action = usize;
+
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R, _: S) -> usize {
+        *self
+            .candidates()
+            .choose(rng)
+            .expect("No valid actions to choose from in `UCB1::sample`.")
+    }
+
+    fn mode(&self, _: S) -> usize { argmax_first(self.means.iter().cloned()).0 }
+}
+
+impl<S, A: std::borrow::Borrow<usize>> Handler<StateActionUpdate<S, A>> for UCB1 {
+    type Response = ();
+    type Error = ();
+
+    fn handle(&mut self, msg: StateActionUpdate<S, A>) -> Result<(), ()> {
+        let a = *msg.action.borrow();
+
+        self.counts[a] += 1;
+        self.means[a] += (msg.error - self.means[a]) / self.counts[a] as f64;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::UCB1;
+    use crate::{
+        fa::StateActionUpdate,
+        policies::{EnumerablePolicy, Policy},
+        Handler,
+    };
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    fn pull(p: &mut UCB1, a: usize, r: f64) {
+        p.handle_unchecked(StateActionUpdate {
+            state: (),
+            action: a,
+            error: r,
+        });
+    }
+
+    #[test]
+    fn test_unvisited_first() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut p = UCB1::new(3, 2.0);
+
+        pull(&mut p, 0, 10.0);
+        pull(&mut p, 0, 10.0);
+
+        assert_ne!(p.sample(&mut rng, ()), 0);
+        assert_eq!(p.probabilities(()), vec![0.0, 0.5, 0.5]);
+
+        pull(&mut p, 1, 10.0);
+
+        assert_eq!(p.sample(&mut rng, ()), 2);
+    }
+
+    #[test]
+    fn test_bandit_convergence() {
+        let arms = [0.2, 0.5, 0.8];
+
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut p = UCB1::new(3, 2.0f64.sqrt());
+
+        for _ in 0..2000 {
+            let a = p.sample(&mut rng, ());
+            let r = if rng.gen_bool(arms[a]) { 1.0 } else { 0.0 };
+
+            pull(&mut p, a, r);
+        }
+
+        assert_eq!(p.mode(()), 2);
+        assert!(p.counts()[2] > p.counts()[0] + p.counts()[1]);
+    }
+}
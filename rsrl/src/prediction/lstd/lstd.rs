@@ -1,11 +1,11 @@
 use crate::{
-    domains::Batch,
+    domains::{Batch, Transition},
     fa::linear::{basis::Basis, Features},
     utils::pinv,
     Handler,
     Parameterised,
 };
-use ndarray::{Array1, Array2, Axis};
+use ndarray::{Array1, Array2};
 use ndarray_linalg::Solve;
 
 #[derive(Debug, Parameterised)]
@@ -37,6 +37,40 @@ impl<B: spaces::Space> LSTD<B> {
 }
 
 impl<B> LSTD<B> {
+    /// Accumulate the statistics `A` and `b` from a single transition without
+    /// recomputing the weights; call [`LSTD::solve`] to do so.
+    ///
+    /// `A ← A + φ(s) (φ(s) - γ φ(s'))ᵀ` and `b ← b + r φ(s)`, where `φ(s')` is
+    /// taken to be zero for terminal transitions.
+    pub fn handle_sample<'m, S, A>(
+        &mut self,
+        t: &'m Transition<S, A>,
+    ) -> Result<(), crate::fa::linear::Error>
+    where
+        B: Basis<&'m S, Value = Features>,
+    {
+        let (s, ns) = t.states();
+
+        let phi_s = self.basis.project(s)?.into_dense();
+        let pd = if t.terminated() {
+            phi_s.clone()
+        } else {
+            &phi_s - &(self.gamma * self.basis.project(ns)?.into_dense())
+        };
+
+        self.b.scaled_add(t.reward, &phi_s);
+
+        for (mut row, &x) in self.a.outer_iter_mut().zip(phi_s.iter()) {
+            if x != 0.0 {
+                row.scaled_add(x, &pd);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Recompute the weights by solving `A θ = b`, falling back to the
+    /// pseudo-inverse of `A` if it is singular.
     pub fn solve(&mut self) {
         let theta = self
             .a
@@ -57,26 +91,72 @@ where B: Basis<&'m S, Value = Features>
 
     fn handle(&mut self, batch: &'m Batch<S, A>) -> Result<(), Self::Error> {
         for t in batch {
-            let (s, ns) = t.states();
+            self.handle_sample(t)?;
+        }
 
-            let phi_s = self.basis.project(s)?.into_dense();
+        self.solve();
+
+        Ok(())
+    }
+}
 
-            self.b.scaled_add(t.reward, &phi_s);
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{domains::Observation, fa::linear::basis::Closure};
+
+    #[test]
+    fn test_random_walk_fixed_point() {
+        // Five-state random walk, terminating with reward 1 off the right end
+        // and 0 off the left; the true values are (s + 1) / 6.
+        let basis = Closure::new(5, |s: &usize| {
+            let mut phi = Array1::zeros(5);
+
+            phi[*s] = 1.0;
+
+            Ok(Features::Dense(phi))
+        });
+        let mut lstd = LSTD::new(basis, 1.0);
+
+        for s in 0..5usize {
+            for &right in [false, true].iter() {
+                let (to, reward) = match (s, right) {
+                    (0, false) => (Observation::Terminal(0), 0.0),
+                    (4, true) => (Observation::Terminal(4), 1.0),
+                    (s, false) => (Observation::Full(s - 1), 0.0),
+                    (s, true) => (Observation::Full(s + 1), 0.0),
+                };
+
+                lstd.handle_sample(&Transition {
+                    from: Observation::Full(s),
+                    action: (),
+                    reward,
+                    to,
+                })
+                .unwrap();
+            }
+        }
 
-            if t.terminated() {
-                let phi_s = phi_s.insert_axis(Axis(1));
+        // The true value function solves A θ = b, up to the regulariser on A.
+        let v: Array1<f64> = (0..5).map(|s| (s + 1) as f64 / 6.0).collect();
 
-                self.a += &phi_s.view().dot(&phi_s.t());
-            } else {
-                let phi_ns = self.basis.project(ns)?.into_dense();
-                let pd = (self.gamma * phi_ns - &phi_s).insert_axis(Axis(0));
+        for (row, b) in lstd.a.outer_iter().zip(lstd.b.iter()) {
+            let av: f64 = row.iter().zip(v.iter()).map(|(a, v)| a * v).sum();
 
-                self.a -= &phi_s.insert_axis(Axis(1)).dot(&pd);
-            }
+            assert!((av - b).abs() < 1e-5);
         }
 
-        self.solve();
+        // Weights are only updated on solve.
+        assert_eq!(lstd.theta, Array1::<f64>::zeros(5));
 
-        Ok(())
+        // Solving requires LAPACK, which is only linked with the blas feature.
+        #[cfg(feature = "blas")]
+        {
+            lstd.solve();
+
+            for (theta, v) in lstd.theta.iter().zip(v.iter()) {
+                assert!((theta - v).abs() < 1e-4);
+            }
+        }
     }
 }
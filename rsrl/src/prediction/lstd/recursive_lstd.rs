@@ -3,9 +3,19 @@ use crate::{
     fa::linear::{basis::Basis, Features},
     Handler,
 };
-use ndarray::{Array1, Array2, Axis};
+use ndarray::{Array1, Array2, ArrayView1};
 use spaces::Space;
 
+/// Recursive least-squares temporal-difference learning (RLSTD).
+///
+/// The inverse of the LSTD matrix, `C = A⁻¹`, is maintained incrementally
+/// using the Sherman–Morrison formula, so the weights are available after
+/// every transition without a full solve. The matrix is initialised to
+/// `I / δ`, which corresponds to ridge regularisation of `A` by `δ I`.
+///
+/// # References
+/// - Bradtke, S. J., Barto, A. G. (1996). Linear least-squares algorithms for
+///   temporal difference learning. Machine Learning, 22(1-3), 33–57.
 #[derive(Debug, Parameterised)]
 pub struct RecursiveLSTD<B> {
     pub basis: B,
@@ -14,6 +24,9 @@ pub struct RecursiveLSTD<B> {
 
     pub gamma: f64,
 
+    /// Ridge regularisation applied to `A`, `δ`.
+    pub regularisation: f64,
+
     c_mat: Array2<f64>,
 }
 
@@ -26,12 +39,30 @@ impl<B: Space> RecursiveLSTD<B> {
             theta: Array1::zeros(n_features),
 
             gamma,
+            regularisation: 1e-5,
 
-            c_mat: Array2::eye(n_features) * 1e-5,
+            c_mat: Array2::eye(n_features) / 1e-5,
         }
     }
 }
 
+impl<B> RecursiveLSTD<B> {
+    /// Set the ridge regularisation, `δ`, and reset the inverse to `I / δ`.
+    ///
+    /// This discards any statistics accumulated so far, and so should be
+    /// called before learning begins.
+    pub fn with_regularisation(mut self, delta: f64) -> Self {
+        self.regularisation = delta;
+        self.c_mat = Array2::eye(self.theta.len()) / delta;
+        self
+    }
+
+    /// Return the current estimate of the inverse LSTD matrix, `A⁻¹`.
+    pub fn inverse(&self) -> &Array2<f64> { &self.c_mat }
+}
+
+fn dot(x: ArrayView1<f64>, y: ArrayView1<f64>) -> f64 { x.iter().zip(y).map(|(a, b)| a * b).sum() }
+
 impl<'m, S, A, B> Handler<&'m Transition<S, A>> for RecursiveLSTD<B>
 where B: Basis<&'m S, Value = Features>
 {
@@ -41,53 +72,109 @@ where B: Basis<&'m S, Value = Features>
     fn handle(&mut self, t: &'m Transition<S, A>) -> Result<(), Self::Error> {
         let (s, ns) = t.states();
 
-        let phi_s = self.basis.project(s)?;
-        let theta_s = phi_s.dot(&self.theta);
+        let phi_s = self.basis.project(s)?.into_dense();
+        let theta_s = dot(phi_s.view(), self.theta.view());
 
-        if t.terminated() {
-            // (D x 1)
-            let phi_s = phi_s.into_dense().insert_axis(Axis(1));
+        // d = φ(s) - γφ(s'), with φ(s') = 0 for terminal transitions:
+        let (pd, residual) = if t.terminated() {
+            (phi_s.clone(), t.reward - theta_s)
+        } else {
+            let phi_ns = self.basis.project(ns)?.into_dense();
+            let theta_ns = dot(phi_ns.view(), self.theta.view());
 
-            // (D x 1) <- (D x D) . (D x 1)
-            let v = self.c_mat.dot(&phi_s);
+            (&phi_s - &(self.gamma * phi_ns), t.reward + self.gamma * theta_ns - theta_s)
+        };
 
-            // (1 x D) <- (D x 1)T
-            let g = v.t();
+        // v = C φ(s) and g = Cᵀ d:
+        let v: Array1<f64> = self.c_mat.outer_iter().map(|row| dot(row, phi_s.view())).collect();
+        let g: Array1<f64> =
+            self.c_mat.gencolumns().into_iter().map(|col| dot(col, pd.view())).collect();
 
-            // (1 x 1) <- (1 x D) . (D x 1)
-            let a = 1.0 + unsafe { g.dot(&phi_s).uget((0, 0)) };
-            let residual = t.reward - theta_s;
+        let a = 1.0 + dot(pd.view(), v.view());
 
-            self.c_mat.fill(0.0);
-            self.theta.scaled_add(residual / a, &v);
-        } else {
-            let phi_ns = self.basis.project(ns)?;
-            let theta_ns = phi_ns.dot(&self.theta);
+        // C ← C - v gᵀ / (1 + dᵀ C φ(s)):
+        for (mut row, &vi) in self.c_mat.outer_iter_mut().zip(v.iter()) {
+            row.scaled_add(-vi / a, &g);
+        }
+
+        self.theta.scaled_add(residual / a, &v);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{domains::Observation, fa::linear::basis::Closure};
+
+    fn random_walk() -> Vec<Transition<usize, ()>> {
+        (0..5usize)
+            .flat_map(|s| {
+                vec![
+                    if s == 0 {
+                        (Observation::Terminal(0), 0.0)
+                    } else {
+                        (Observation::Full(s - 1), 0.0)
+                    },
+                    if s == 4 {
+                        (Observation::Terminal(4), 1.0)
+                    } else {
+                        (Observation::Full(s + 1), 0.0)
+                    },
+                ]
+                .into_iter()
+                .map(move |(to, reward)| Transition {
+                    from: Observation::Full(s),
+                    action: (),
+                    reward,
+                    to,
+                })
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_tracks_batch_solution() {
+        let basis = Closure::new(5, |s: &usize| {
+            let mut phi = Array1::zeros(5);
 
-            // (D x 1)
-            let phi_s = phi_s.into_dense().insert_axis(Axis(1));
-            let phi_ns = phi_ns.into_dense().insert_axis(Axis(1));
+            phi[*s] = 1.0;
 
-            let pd = (-self.gamma * phi_ns) + &phi_s;
+            Ok(Features::Dense(phi))
+        });
+        let mut rlstd = RecursiveLSTD::new(basis, 1.0).with_regularisation(0.1);
 
-            // (1 x D) <- ((D x D) . (D x 1))T
-            //  Note: self.permuted_axes([1, 0]) is equivalent to taking the transpose.
-            let g = self.c_mat.dot(&pd).permuted_axes([1, 0]);
+        // Batch statistics, with A regularised to match.
+        let mut a_mat = Array2::<f64>::eye(5) * 0.1;
+        let mut b_vec = Array1::<f64>::zeros(5);
 
-            // (1 x 1) <- (1 x D) . (D x 1)
-            let a = 1.0 + unsafe { g.dot(&phi_s).uget((0, 0)) };
+        let transitions = random_walk();
 
-            // (D x 1) <- (D x D) . (D x 1)
-            let v = self.c_mat.dot(&phi_s);
-            let residual = t.reward + self.gamma * theta_ns - theta_s;
+        for _ in 0..50 {
+            for t in transitions.iter() {
+                rlstd.handle(t).unwrap();
 
-            // (D x D) <- (D x 1) . (1 x D)
-            let vg = v.dot(&g);
+                let (s, ns) = t.states();
 
-            self.c_mat.scaled_add(-1.0 / a, &vg);
-            self.theta.scaled_add(residual / a, &v);
+                a_mat[(*s, *s)] += 1.0;
+                b_vec[*s] += t.reward;
+
+                if !t.terminated() {
+                    a_mat[(*s, *ns)] -= 1.0;
+                }
+
+                // The weights solve the batch system after every sample.
+                for (row, b) in a_mat.outer_iter().zip(b_vec.iter()) {
+                    let a_theta = dot(row, rlstd.theta.view());
+
+                    assert!((a_theta - b).abs() < 1e-8);
+                }
+            }
         }
 
-        Ok(())
+        for s in 0..5 {
+            assert!((rlstd.theta[s] - (s + 1) as f64 / 6.0).abs() < 1e-2);
+        }
     }
 }
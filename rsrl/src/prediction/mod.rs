@@ -1,7 +1,96 @@
 //! Prediction agents module.
+use crate::{
+    domains::{Observation, Transition},
+    Function,
+};
+
 pub mod lstd;
 pub mod mc;
 pub mod td;
 
 // TODO:
 // Implement the algorithms discussed in https://arxiv.org/pdf/1304.3999.pdf
+
+/// Compute the mean squared TD error of a state-value function over a batch
+/// of (typically held-out) transitions.
+///
+/// For each transition the TD error is `r + γ V(s') - V(s)`, where `V(s')` is
+/// taken to be zero if `s'` is terminal. The residual is zero if and only if
+/// `v_func` satisfies the sample Bellman equation on every transition, which
+/// makes it a useful convergence diagnostic independent of the training loss.
+/// Returns zero for an empty batch.
+pub fn bellman_residual<'m, S, A, V>(
+    v_func: &V,
+    gamma: f64,
+    transitions: &'m [Transition<S, A>],
+) -> f64
+where
+    V: Function<(&'m S,), Output = f64>,
+{
+    if transitions.is_empty() {
+        return 0.0;
+    }
+
+    let sse: f64 = transitions
+        .iter()
+        .map(|t| {
+            let nv = match t.to {
+                Observation::Terminal(_) => 0.0,
+                Observation::Full(ref to) | Observation::Partial(ref to) => {
+                    v_func.evaluate((to,))
+                },
+            };
+            let td_error = t.reward + gamma * nv - v_func.evaluate((t.from.state(),));
+
+            td_error * td_error
+        })
+        .sum();
+
+    sse / transitions.len() as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fa::tabular::Table;
+    use ndarray::Array1;
+
+    fn chain() -> Vec<Transition<usize, ()>> {
+        // Deterministic chain 0 -> 1 -> 2 (terminal) with unit rewards.
+        vec![
+            Transition {
+                from: Observation::Full(0),
+                action: (),
+                reward: 1.0,
+                to: Observation::Full(1),
+            },
+            Transition {
+                from: Observation::Full(1),
+                action: (),
+                reward: 1.0,
+                to: Observation::Terminal(2),
+            },
+        ]
+    }
+
+    #[test]
+    fn test_bellman_residual_exact() {
+        let v = Table::dense(Array1::from(vec![1.5, 1.0, 0.0]));
+
+        assert_eq!(bellman_residual(&v, 0.5, &chain()), 0.0);
+        assert_eq!(bellman_residual(&v, 0.5, &[] as &[Transition<usize, ()>]), 0.0);
+    }
+
+    #[test]
+    fn test_bellman_residual_inexact() {
+        let v = Table::dense(Array1::zeros(3));
+
+        // Both TD errors equal 1.
+        assert_eq!(bellman_residual(&v, 0.5, &chain()), 1.0);
+
+        let v = Table::dense(Array1::from(vec![1.5, 2.0, 0.0]));
+
+        // Errors of 0.5 and -1.
+        assert!((bellman_residual(&v, 0.5, &chain()) - 0.625).abs() < 1e-12);
+    }
+}
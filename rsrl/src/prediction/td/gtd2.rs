@@ -2,6 +2,7 @@ use crate::{
     domains::Transition,
     fa::ScaledGradientUpdate,
     params::BufferMut,
+    prediction::td::WeightedTransition,
     Differentiable,
     Handler,
 };
@@ -18,6 +19,25 @@ pub struct Response<T, W> {
     pub td_error: f64,
 }
 
+/// Gradient temporal-difference learning (GTD2).
+///
+/// The primary (`fa_theta`) and secondary (`fa_w`) weights are updated on two
+/// timescales; the step sizes, `alpha` and `beta`, are those of the respective
+/// function approximators' optimisers.
+///
+/// Off-policy samples may be weighted by the importance sampling ratio, `ρ`,
+/// between the target and behaviour policies via a [`WeightedTransition`]:
+///
+/// `w ← w + β (ρδ - φᵀw) φ`,
+///
+/// `θ ← θ + α ρ (φ - γφ') φᵀw`.
+///
+/// # References
+/// - Sutton, R. S., Maei, H. R., Precup, D., Bhatnagar, S., Silver, D.,
+///   Szepesvári, Cs., & Wiewiora, E. (2009). Fast gradient-descent methods for
+///   temporal-difference learning with linear function approximation. In
+///   Proceedings of the 26th International Conference on Machine Learning (pp.
+///   993-1000).
 #[derive(Clone, Debug, Parameterised)]
 #[cfg_attr(
     feature = "serde",
@@ -25,17 +45,21 @@ pub struct Response<T, W> {
     serde(crate = "serde_crate")
 )]
 pub struct GTD2<F> {
+    /// Value function approximator.
     #[weights]
     pub fa_theta: F,
+
+    /// Secondary weights approximating the expected TD error.
     pub fa_w: F,
 
+    /// Discount factor.
     pub gamma: f64,
 }
 
 type SGU<'m, S, F> = ScaledGradientUpdate<<F as Differentiable<(&'m S,)>>::Jacobian>;
 type SGURef<'m, 'j, S, F> = ScaledGradientUpdate<&'j <F as Differentiable<(&'m S,)>>::Jacobian>;
 
-impl<'m, S, A, F> Handler<&'m Transition<S, A>> for GTD2<F>
+impl<'m, S, A, F> Handler<WeightedTransition<'m, S, A>> for GTD2<F>
 where
     F: Differentiable<(&'m S,), Output = f64> + Handler<SGU<'m, S, F>>,
     F: for<'j> Handler<
@@ -48,7 +72,11 @@ where
         Response<<F as Handler<SGU<'m, S, F>>>::Response, <F as Handler<SGU<'m, S, F>>>::Response>;
     type Error = <F as Handler<SGU<'m, S, F>>>::Error;
 
-    fn handle(&mut self, t: &'m Transition<S, A>) -> Result<Self::Response, Self::Error> {
+    fn handle(
+        &mut self,
+        msg: WeightedTransition<'m, S, A>,
+    ) -> Result<Self::Response, Self::Error> {
+        let t = msg.transition;
         let (s, ns) = t.states();
 
         let w_s = self.fa_w.evaluate((s,));
@@ -64,16 +92,18 @@ where
         let mut grad_s = self.fa_theta.grad((s,));
 
         let res_w = self.fa_w.handle(ScaledGradientUpdate {
-            alpha: td_error - w_s,
+            alpha: msg.ratio * td_error - w_s,
             jacobian: &grad_s,
         })?;
 
-        let grad_ns = self.fa_theta.grad((ns,));
+        if !t.terminated() {
+            let grad_ns = self.fa_theta.grad((ns,));
 
-        grad_s.merge_inplace(&grad_ns, |x, y| x - self.gamma * y);
+            grad_s.merge_inplace(&grad_ns, |x, y| x - self.gamma * y);
+        }
 
         let res_theta = self.fa_theta.handle(ScaledGradientUpdate {
-            alpha: w_s,
+            alpha: msg.ratio * w_s,
             jacobian: grad_s,
         })?;
 
@@ -84,3 +114,116 @@ where
         })
     }
 }
+
+impl<'m, S, A, F> Handler<&'m Transition<S, A>> for GTD2<F>
+where
+    F: Differentiable<(&'m S,), Output = f64> + Handler<SGU<'m, S, F>>,
+    F: for<'j> Handler<
+        SGURef<'m, 'j, S, F>,
+        Response = <F as Handler<SGU<'m, S, F>>>::Response,
+        Error = <F as Handler<SGU<'m, S, F>>>::Error,
+    >,
+{
+    type Response = <Self as Handler<WeightedTransition<'m, S, A>>>::Response;
+    type Error = <Self as Handler<WeightedTransition<'m, S, A>>>::Error;
+
+    fn handle(&mut self, transition: &'m Transition<S, A>) -> Result<Self::Response, Self::Error> {
+        self.handle(WeightedTransition {
+            transition,
+            ratio: 1.0,
+        })
+    }
+}
+
+// Evaluating the dense branch of `Features::dot` requires a BLAS implementation.
+#[cfg(all(test, feature = "blas"))]
+mod tests {
+    use super::*;
+    use crate::{
+        domains::Observation,
+        fa::linear::{basis::Closure, optim::SGD, Features, ScalarLFA},
+        prediction::td::TDLambda,
+        traces::Trace,
+        Function,
+    };
+    use ndarray::arr1;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    const ALPHA: f64 = 0.005;
+    const GAMMA: f64 = 0.99;
+
+    type TestLFA = ScalarLFA<Closure<fn(&usize) -> crate::fa::linear::Result<Features>>, SGD>;
+
+    // Baird's counterexample: the six upper states have features 2e_i + e_7
+    // and the lower state e_6 + 2e_7. The updates take unit steps along the
+    // gradient, so the step size is folded into the features as φ = √α x.
+    fn features(s: &usize) -> crate::fa::linear::Result<Features> {
+        let x = if *s < 6 { vec![(*s, 2.0), (7, 1.0)] } else { vec![(6, 1.0), (7, 2.0)] };
+
+        Ok(Features::sparse(8, x.into_iter().map(|(i, x): (usize, f64)| (i, ALPHA.sqrt() * x))))
+    }
+
+    fn baird() -> TestLFA {
+        let mut fa = ScalarLFA::scalar(Closure::new(8, features as fn(&_) -> _), SGD(1.0));
+
+        fa.weights = arr1(&[1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 10.0, 1.0]) / ALPHA.sqrt();
+        fa
+    }
+
+    // The behaviour policy takes the dashed action, to a random upper state,
+    // with probability 6/7, and the solid action, to the lower state, with
+    // probability 1/7. The target policy always takes the solid action, so
+    // the importance sampling ratios are 0 and 7, respectively.
+    fn sample(rng: &mut StdRng) -> (Transition<usize, usize>, f64) {
+        let s = rng.gen_range(0, 7);
+        let (a, ns, ratio) =
+            if rng.gen_bool(1.0 / 7.0) { (1, 6, 7.0) } else { (0, rng.gen_range(0, 6), 0.0) };
+
+        let t = Transition {
+            from: Observation::Full(s),
+            action: a,
+            reward: 0.0,
+            to: Observation::Full(ns),
+        };
+
+        (t, ratio)
+    }
+
+    // All true values are zero.
+    fn rmsve(fa: &TestLFA) -> f64 {
+        ((0..7).map(|s| Function::evaluate(fa, (&s,)).powi(2)).sum::<f64>() / 7.0).sqrt()
+    }
+
+    #[test]
+    fn test_baird_counterexample() {
+        let mut rng = StdRng::seed_from_u64(0);
+
+        let mut td = TDLambda::new(baird(), Trace::accumulating(8, 0.0, 0.0), GAMMA);
+        let mut gtd2 = GTD2 {
+            fa_theta: baird(),
+            fa_w: baird(),
+            gamma: GAMMA,
+        };
+
+        gtd2.fa_w.weights.fill(0.0);
+
+        let initial = rmsve(&gtd2.fa_theta);
+
+        for _ in 0..5000 {
+            let (t, ratio) = sample(&mut rng);
+            let weighted = WeightedTransition {
+                transition: &t,
+                ratio,
+            };
+
+            td.handle(weighted).unwrap();
+            gtd2.handle(weighted).unwrap();
+
+            assert!(rmsve(&gtd2.fa_theta) < 2.0 * initial);
+        }
+
+        // Off-policy TD(0) diverges, whereas GTD2 reduces the error.
+        assert!(rmsve(&td.fa_theta) > 10.0 * initial);
+        assert!(rmsve(&gtd2.fa_theta) < initial);
+    }
+}
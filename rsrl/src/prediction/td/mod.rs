@@ -1,8 +1,70 @@
+use crate::{domains::Transition, policies::EnumerablePolicy, Function};
+use std::ops::Index;
+
+/// Transition paired with the importance sampling ratio, `π(a|s) / b(a|s)`,
+/// between a target policy and the behaviour policy that generated it.
+#[derive(Clone, Copy, Debug)]
+pub struct WeightedTransition<'m, S, A> {
+    pub transition: &'m Transition<S, A>,
+    pub ratio: f64,
+}
+
+impl<'m, S> WeightedTransition<'m, S, usize> {
+    /// Construct a weighted transition with the importance sampling ratio
+    /// between the `target` and `behaviour` policies.
+    ///
+    /// Returns `None` if the action taken has zero probability under the
+    /// `behaviour` policy, in which case the ratio is undefined.
+    pub fn from_policies<P, B>(
+        transition: &'m Transition<S, usize>,
+        target: &P,
+        behaviour: &B,
+    ) -> Option<Self>
+    where
+        P: EnumerablePolicy<&'m S>,
+        B: EnumerablePolicy<&'m S>,
+
+        <P as Function<(&'m S,)>>::Output: Index<usize, Output = f64> + IntoIterator<Item = f64>,
+        <<P as Function<(&'m S,)>>::Output as IntoIterator>::IntoIter: ExactSizeIterator,
+
+        <B as Function<(&'m S,)>>::Output: Index<usize, Output = f64> + IntoIterator<Item = f64>,
+        <<B as Function<(&'m S,)>>::Output as IntoIterator>::IntoIter: ExactSizeIterator,
+    {
+        let s = transition.from.state();
+        let b = behaviour.probabilities(s)[transition.action];
+
+        if b > 0.0 {
+            Some(WeightedTransition {
+                transition,
+                ratio: target.probabilities(s)[transition.action] / b,
+            })
+        } else {
+            None
+        }
+    }
+}
+
+/// Transition paired with a vector of rewards, one per objective.
+///
+/// The scalar `reward` of the underlying transition is ignored.
+#[derive(Clone, Debug)]
+pub struct VectorTransition<'m, S, A> {
+    pub transition: &'m Transition<S, A>,
+    pub rewards: Vec<f64>,
+}
+
 // Semi-gradient methods:
 pub mod td;
 pub mod td_lambda;
+pub mod true_online_td_lambda;
+pub mod vector_td;
 
-pub use self::{td::TD, td_lambda::TDLambda};
+pub use self::{
+    td::TD,
+    td_lambda::TDLambda,
+    true_online_td_lambda::TrueOnlineTDLambda,
+    vector_td::VectorTD,
+};
 
 // Full-gradient methods:
 pub mod gtd2;
@@ -15,7 +77,61 @@ pub use self::{gtd2::GTD2, tdc::TDC};
 // ETD(lambda) - https://arxiv.org/pdf/1503.04269.pdf
 // HTD(lambda) - https://arxiv.org/pdf/1602.08771.pdf
 // PTD(lambda) - http://proceedings.mlr.press/v32/sutton14.pdf
-// True online TD(lambda) - http://proceedings.mlr.press/v32/seijen14.pdf
 // True online ETD(lambda) - https://arxiv.org/pdf/1602.08771.pdf
 // True online ETD(beta, lambda) - https://arxiv.org/pdf/1602.08771.pdf
 // True online HTD(lambda) - https://arxiv.org/pdf/1602.08771.pdf
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        domains::Observation,
+        fa::mocking::MockQ,
+        policies::{Greedy, Random, Softmax},
+    };
+
+    #[test]
+    fn test_ratio_from_policies() {
+        let target = Greedy::new(MockQ::new_shared(Some(vec![1.0, 0.0])));
+        let behaviour = Random::new(2);
+
+        let mut t = Transition {
+            from: Observation::Full(vec![]),
+            action: 0,
+            reward: 0.0,
+            to: Observation::Full(vec![]),
+        };
+
+        assert_eq!(WeightedTransition::from_policies(&t, &target, &behaviour).unwrap().ratio, 2.0);
+
+        t.action = 1;
+
+        assert_eq!(WeightedTransition::from_policies(&t, &target, &behaviour).unwrap().ratio, 0.0);
+        assert_eq!(
+            WeightedTransition::from_policies(&t, &behaviour, &behaviour).unwrap().ratio,
+            1.0
+        );
+
+        // Actions the behaviour policy could not have taken have no ratio.
+        assert!(WeightedTransition::from_policies(&t, &behaviour, &target).is_none());
+    }
+
+    #[test]
+    fn test_softmax_ratio() {
+        let target = Greedy::new(MockQ::new_shared(Some(vec![1.0, 0.0])));
+        let behaviour = Softmax::standard(MockQ::new_shared(Some(vec![1.0, 0.0])));
+
+        let t = Transition {
+            from: Observation::Full(vec![]),
+            action: 0,
+            reward: 0.0,
+            to: Observation::Full(vec![]),
+        };
+
+        // The ratio is taken between action probabilities, not action values.
+        let p = 1.0f64.exp() / (1.0f64.exp() + 1.0);
+        let ratio = WeightedTransition::from_policies(&t, &target, &behaviour).unwrap().ratio;
+
+        assert!((ratio - 1.0 / p).abs() < 1e-12);
+    }
+}
@@ -1,6 +1,7 @@
 use crate::{
     domains::{Observation, Transition},
     fa::StateUpdate,
+    utils::clip_error,
     Function,
     Handler,
 };
@@ -27,6 +28,26 @@ pub struct TD<V> {
     pub v_func: V,
 
     pub gamma: f64,
+
+    /// Optional bound, `δ`, on the magnitude of the TD error used in updates.
+    pub error_clip: Option<f64>,
+}
+
+impl<V> TD<V> {
+    pub fn new(v_func: V, gamma: f64) -> Self {
+        TD {
+            v_func,
+            gamma,
+            error_clip: None,
+        }
+    }
+
+    /// Clip the TD error to `[-delta, delta]` before updating the value
+    /// function; the reported `td_error` is left unclipped.
+    pub fn with_error_clip(mut self, delta: f64) -> Self {
+        self.error_clip = Some(delta);
+        self
+    }
 }
 
 impl<'m, S, A, V> Handler<&'m Transition<S, A>> for TD<V>
@@ -49,7 +70,7 @@ where V: Function<(&'m S,), Output = f64> + Handler<StateUpdate<&'m S, f64>>
         self.v_func
             .handle(StateUpdate {
                 state: from,
-                error: td_error,
+                error: clip_error(td_error, self.error_clip),
             })
             .map(|r| Response {
                 td_error,
@@ -57,3 +78,39 @@ where V: Function<(&'m S,), Output = f64> + Handler<StateUpdate<&'m S, f64>>
             })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fa::tabular::Table;
+    use ndarray::Array1;
+
+    fn update(agent: &mut TD<Table<Array1<f64>>>, reward: f64) -> f64 {
+        agent.v_func = Table::dense(Array1::zeros(2));
+        agent.handle(&Transition {
+            from: Observation::Full(0usize),
+            action: (),
+            reward,
+            to: Observation::Terminal(1),
+        }).unwrap().td_error
+    }
+
+    #[test]
+    fn test_error_clip() {
+        let mut agent = TD::new(Table::dense(Array1::zeros(2)), 0.9).with_error_clip(1.0);
+
+        assert_eq!(update(&mut agent, 5.0), 5.0);
+        assert_eq!(agent.v_func.evaluate((0,)), 1.0);
+
+        assert_eq!(update(&mut agent, -5.0), -5.0);
+        assert_eq!(agent.v_func.evaluate((0,)), -1.0);
+
+        assert_eq!(update(&mut agent, 0.5), 0.5);
+        assert_eq!(agent.v_func.evaluate((0,)), 0.5);
+
+        agent.error_clip = None;
+
+        assert_eq!(update(&mut agent, 5.0), 5.0);
+        assert_eq!(agent.v_func.evaluate((0,)), 5.0);
+    }
+}
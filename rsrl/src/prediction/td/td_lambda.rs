@@ -1,7 +1,11 @@
 use crate::{
     domains::{Observation, Transition},
     fa::ScaledGradientUpdate,
+    params::BufferMut,
+    prediction::td::WeightedTransition,
+    schedule::Parameter,
     traces,
+    utils::clip_grad_norm,
     Differentiable,
     Handler,
 };
@@ -27,12 +31,47 @@ pub struct TDLambda<F, T> {
     pub fa_theta: F,
     pub trace: T,
 
-    pub gamma: f64,
+    /// Discount factor of the TD target, advanced by a single `step` at the
+    /// end of each episode; constant by default.
+    pub gamma: Parameter,
+
+    /// Optional bound on the L2 norm of each weight update.
+    pub grad_clip_norm: Option<f64>,
+}
+
+impl<F, T> TDLambda<F, T> {
+    pub fn new(fa_theta: F, trace: T, gamma: f64) -> Self {
+        TDLambda {
+            fa_theta,
+            trace,
+
+            gamma: Parameter::constant(gamma),
+            grad_clip_norm: None,
+        }
+    }
+
+    /// Anneal the discount factor of the TD target according to `schedule`.
+    ///
+    /// A schedule that starts low and increases toward the true discount can
+    /// speed up early learning by shortening the effective horizon. Note that
+    /// the decay rate of `trace` is set by its own update rule and is left
+    /// unchanged.
+    pub fn with_gamma_schedule(mut self, schedule: Parameter) -> Self {
+        self.gamma = schedule;
+        self
+    }
+
+    /// Rescale each weight update, `δ e`, such that its global L2 norm is at
+    /// most `max_norm`; the reported `td_error` is left unscaled.
+    pub fn with_grad_clip_norm(mut self, max_norm: f64) -> Self {
+        self.grad_clip_norm = Some(max_norm);
+        self
+    }
 }
 
 type Tr<S, F, R> = traces::Trace<<F as Differentiable<(S,)>>::Jacobian, R>;
 
-impl<'m, S, A, F, R> Handler<&'m Transition<S, A>> for TDLambda<F, Tr<&'m S, F, R>>
+impl<'m, S, A, F, R> Handler<WeightedTransition<'m, S, A>> for TDLambda<F, Tr<&'m S, F, R>>
 where
     F: Differentiable<(&'m S,), Output = f64> +
         for<'j> Handler<ScaledGradientUpdate<&'j Tr<&'m S, F, R>>>,
@@ -41,33 +80,40 @@ where
     type Response = Response;
     type Error = ();
 
-    fn handle(&mut self, transition: &'m Transition<S, A>) -> Result<Self::Response, Self::Error> {
+    fn handle(
+        &mut self,
+        msg: WeightedTransition<'m, S, A>,
+    ) -> Result<Self::Response, Self::Error> {
+        let transition = msg.transition;
         let from = transition.from.state();
 
         let pred = self.fa_theta.evaluate((from,));
         let grad = self.fa_theta.grad((from,));
 
+        // Per-decision importance sampling, e = ρ(γλe + ∇v(s)):
         self.trace.update(&grad);
+        self.trace.buffer.map_inplace(|x| msg.ratio * x);
 
         match transition.to {
             Observation::Terminal(_) => {
                 let td_error = transition.reward - pred;
 
                 self.fa_theta.handle(ScaledGradientUpdate {
-                    alpha: td_error,
+                    alpha: clip_grad_norm(td_error, &self.trace, self.grad_clip_norm),
                     jacobian: &self.trace,
                 }).map_err(|_| ())?;
 
                 self.trace.reset();
+                self.gamma.step();
 
                 Ok(Response { td_error, })
             },
             Observation::Full(ref to) | Observation::Partial(ref to) => {
                 let td_error =
-                    transition.reward + self.gamma * self.fa_theta.evaluate((to,)) - pred;
+                    transition.reward + self.gamma.value() * self.fa_theta.evaluate((to,)) - pred;
 
                 self.fa_theta.handle(ScaledGradientUpdate {
-                    alpha: td_error,
+                    alpha: clip_grad_norm(td_error, &self.trace, self.grad_clip_norm),
                     jacobian: &self.trace,
                 }).map_err(|_| ())?;
 
@@ -76,3 +122,121 @@ where
         }
     }
 }
+
+impl<'m, S, A, F, R> Handler<&'m Transition<S, A>> for TDLambda<F, Tr<&'m S, F, R>>
+where
+    F: Differentiable<(&'m S,), Output = f64> +
+        for<'j> Handler<ScaledGradientUpdate<&'j Tr<&'m S, F, R>>>,
+    R: traces::UpdateRule<<F as Differentiable<(&'m S,)>>::Jacobian>,
+{
+    type Response = Response;
+    type Error = ();
+
+    fn handle(&mut self, transition: &'m Transition<S, A>) -> Result<Self::Response, Self::Error> {
+        self.handle(WeightedTransition {
+            transition,
+            ratio: 1.0,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{fa::tabular::Table, traces::Trace, Function};
+    use ndarray::Array1;
+
+    #[test]
+    fn test_grad_clip_norm() {
+        let t = Transition {
+            from: Observation::Full(0usize),
+            action: (),
+            reward: 10.0,
+            to: Observation::Terminal(1),
+        };
+
+        let mut agent = TDLambda::new(
+            Table::dense(Array1::zeros(2)),
+            Trace::accumulating(2, 0.9, 0.5),
+            0.9,
+        )
+        .with_grad_clip_norm(1.0);
+
+        assert_eq!(agent.handle(&t).unwrap().td_error, 10.0);
+        assert!((agent.fa_theta.evaluate((0,)) - 1.0).abs() < 1e-12);
+
+        agent.grad_clip_norm = None;
+
+        assert_eq!(agent.handle(&t).unwrap().td_error, 9.0);
+        assert!((agent.fa_theta.evaluate((0,)) - 10.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_gamma_schedule() {
+        let step = Transition {
+            from: Observation::Full(0usize),
+            action: (),
+            reward: 0.0,
+            to: Observation::Full(1),
+        };
+        let last = Transition {
+            from: Observation::Full(1usize),
+            action: (),
+            reward: 0.0,
+            to: Observation::Terminal(1),
+        };
+
+        let v = || Table::dense(ndarray::arr1(&[0.0, 1.0]));
+        let mut agent = TDLambda::new(v(), Trace::accumulating(2, 0.0, 0.0), 0.9)
+            .with_gamma_schedule(Parameter::linear(0.5, 0.9, 2));
+
+        // Non-terminal steps bootstrap with the current discount and leave the
+        // schedule untouched; the terminal step of each episode advances it.
+        for &gamma in [0.5, 0.7, 0.9, 0.9].iter() {
+            agent.fa_theta = v();
+
+            assert!((agent.gamma.value() - gamma).abs() < 1e-12);
+            assert!((agent.handle(&step).unwrap().td_error - gamma).abs() < 1e-12);
+            assert!((agent.gamma.value() - gamma).abs() < 1e-12);
+
+            agent.handle(&last).unwrap();
+        }
+
+        assert!((agent.gamma.value() - 0.9).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_unit_ratio() {
+        let episode: Vec<_> = [(0, 1, 0.0), (1, 2, 1.0), (2, 1, -1.0), (1, 0, 2.0)]
+            .iter()
+            .map(|&(s, ns, r)| Transition {
+                from: Observation::Full(s),
+                action: (),
+                reward: r,
+                to: if ns == 0 { Observation::Terminal(ns) } else { Observation::Full(ns) },
+            })
+            .collect();
+
+        let agent = || {
+            TDLambda::new(Table::dense(Array1::zeros(3)), Trace::accumulating(3, 0.9, 0.8), 0.9)
+        };
+        let mut on_policy = agent();
+        let mut weighted = agent();
+
+        for _ in 0..3 {
+            for t in episode.iter() {
+                let td_error = on_policy.handle(t).unwrap().td_error;
+                let msg = WeightedTransition {
+                    transition: t,
+                    ratio: 1.0,
+                };
+
+                assert_eq!(weighted.handle(msg).unwrap().td_error, td_error);
+            }
+
+            for s in 0..3 {
+                assert_eq!(weighted.fa_theta.evaluate((s,)), on_policy.fa_theta.evaluate((s,)));
+            }
+        }
+    }
+}
@@ -3,6 +3,7 @@ use crate::{
     domains::Transition,
     fa::{GradientUpdate, StateUpdate},
     params::BufferMut,
+    prediction::td::WeightedTransition,
     Differentiable,
     Handler,
 };
@@ -32,6 +33,23 @@ pub enum Error<EQ, ET> {
 }
 
 /// Temporal-difference learning with gradient correction.
+///
+/// As with [`GTD2`](super::GTD2), the secondary step size, `beta`, is that of
+/// the `td_est` approximator's optimiser.
+///
+/// Off-policy samples may be weighted by the importance sampling ratio, `ρ`,
+/// between the target and behaviour policies via a [`WeightedTransition`]:
+///
+/// `w ← w + β (ρδ - φᵀw) φ`,
+///
+/// `θ ← θ + α ρ (δφ - γφ' φᵀw)`.
+///
+/// # References
+/// - Sutton, R. S., Maei, H. R., Precup, D., Bhatnagar, S., Silver, D.,
+///   Szepesvári, Cs., & Wiewiora, E. (2009). Fast gradient-descent methods for
+///   temporal-difference learning with linear function approximation. In
+///   Proceedings of the 26th International Conference on Machine Learning (pp.
+///   993-1000).
 #[derive(Clone, Debug, Parameterised)]
 #[cfg_attr(
     feature = "serde",
@@ -52,7 +70,7 @@ pub struct TDC<F> {
 type StateMessage<'m, S> = StateUpdate<&'m S, f64>;
 type GradientMessage<'m, S, F> = GradientUpdate<<F as Differentiable<(&'m S,)>>::Jacobian>;
 
-impl<'m, S, A, F> Handler<&'m Transition<S, A>> for TDC<F>
+impl<'m, S, A, F> Handler<WeightedTransition<'m, S, A>> for TDC<F>
 where F: Differentiable<(&'m S,), Output = f64>
         + Handler<StateMessage<'m, S>>
         + Handler<GradientMessage<'m, S, F>>
@@ -66,7 +84,11 @@ where F: Differentiable<(&'m S,), Output = f64>
         <F as Handler<StateMessage<'m, S>>>::Error
     >;
 
-    fn handle(&mut self, t: &'m Transition<S, A>) -> Result<Self::Response, Self::Error> {
+    fn handle(
+        &mut self,
+        msg: WeightedTransition<'m, S, A>,
+    ) -> Result<Self::Response, Self::Error> {
+        let t = msg.transition;
         let (s, ns) = t.states();
 
         let w_s = self.td_est.evaluate((s,));
@@ -81,14 +103,18 @@ where F: Differentiable<(&'m S,), Output = f64>
         let res_td = self.td_est
             .handle(StateUpdate {
                 state: s,
-                error: td_error - w_s,
+                error: msg.ratio * td_error - w_s,
             })
             .map_err(|e| Error::TDEstError(e))?;
 
         let grad_s = self.q_func.grad((s,));
-        let grad_ns = self.q_func.grad((ns,));
+        let grad = if t.terminated() {
+            grad_s.map_into(|x| msg.ratio * td_error * x)
+        } else {
+            let grad_ns = self.q_func.grad((ns,));
 
-        let grad = grad_s.merge(&grad_ns, |x, y| td_error * x - w_s * y);
+            grad_s.merge(&grad_ns, |x, y| msg.ratio * (td_error * x - self.gamma * w_s * y))
+        };
         let res_q = self.q_func.handle(GradientUpdate(grad)).map_err(|e| Error::QFuncError(e))?;
 
         Ok(Response {
@@ -99,3 +125,105 @@ where F: Differentiable<(&'m S,), Output = f64>
         })
     }
 }
+
+impl<'m, S, A, F> Handler<&'m Transition<S, A>> for TDC<F>
+where F: Differentiable<(&'m S,), Output = f64>
+        + Handler<StateMessage<'m, S>>
+        + Handler<GradientMessage<'m, S, F>>
+{
+    type Response = <Self as Handler<WeightedTransition<'m, S, A>>>::Response;
+    type Error = <Self as Handler<WeightedTransition<'m, S, A>>>::Error;
+
+    fn handle(&mut self, transition: &'m Transition<S, A>) -> Result<Self::Response, Self::Error> {
+        self.handle(WeightedTransition {
+            transition,
+            ratio: 1.0,
+        })
+    }
+}
+
+// Evaluating the dense branch of `Features::dot` requires a BLAS implementation.
+#[cfg(all(test, feature = "blas"))]
+mod tests {
+    use super::*;
+    use crate::{
+        domains::Observation,
+        fa::linear::{basis::Closure, optim::SGD, Features, ScalarLFA},
+        Function,
+    };
+    use ndarray::arr1;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    const ALPHA: f64 = 0.005;
+    const GAMMA: f64 = 0.99;
+
+    type TestLFA = ScalarLFA<Closure<fn(&usize) -> crate::fa::linear::Result<Features>>, SGD>;
+
+    // Baird's counterexample: the six upper states have features 2e_i + e_7
+    // and the lower state e_6 + 2e_7. The primary weights take unit steps
+    // along the gradient, so the step size is folded into the features as
+    // φ = √α x; the secondary step size is then that of the optimiser times α.
+    fn features(s: &usize) -> crate::fa::linear::Result<Features> {
+        let x = if *s < 6 { vec![(*s, 2.0), (7, 1.0)] } else { vec![(6, 1.0), (7, 2.0)] };
+
+        Ok(Features::sparse(8, x.into_iter().map(|(i, x): (usize, f64)| (i, ALPHA.sqrt() * x))))
+    }
+
+    fn baird() -> TestLFA {
+        let mut fa = ScalarLFA::scalar(Closure::new(8, features as fn(&_) -> _), SGD(1.0));
+
+        fa.weights = arr1(&[1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 10.0, 1.0]) / ALPHA.sqrt();
+        fa
+    }
+
+    // The behaviour policy takes the dashed action, to a random upper state,
+    // with probability 6/7, and the solid action, to the lower state, with
+    // probability 1/7. The target policy always takes the solid action, so
+    // the importance sampling ratios are 0 and 7, respectively.
+    fn sample(rng: &mut StdRng) -> (Transition<usize, usize>, f64) {
+        let s = rng.gen_range(0, 7);
+        let (a, ns, ratio) =
+            if rng.gen_bool(1.0 / 7.0) { (1, 6, 7.0) } else { (0, rng.gen_range(0, 6), 0.0) };
+
+        let t = Transition {
+            from: Observation::Full(s),
+            action: a,
+            reward: 0.0,
+            to: Observation::Full(ns),
+        };
+
+        (t, ratio)
+    }
+
+    // All true values are zero.
+    fn rmsve(fa: &TestLFA) -> f64 {
+        ((0..7).map(|s| Function::evaluate(fa, (&s,)).powi(2)).sum::<f64>() / 7.0).sqrt()
+    }
+
+    #[test]
+    fn test_baird_counterexample() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut tdc = TDC {
+            q_func: baird(),
+            td_est: baird(),
+            gamma: GAMMA,
+        };
+
+        tdc.td_est.weights.fill(0.0);
+
+        let initial = rmsve(&tdc.q_func);
+
+        for _ in 0..5000 {
+            let (t, ratio) = sample(&mut rng);
+
+            tdc.handle(WeightedTransition {
+                transition: &t,
+                ratio,
+            }).unwrap();
+
+            assert!(rmsve(&tdc.q_func) < 2.0 * initial);
+        }
+
+        assert!(rmsve(&tdc.q_func) < initial);
+    }
+}
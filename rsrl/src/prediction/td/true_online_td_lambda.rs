@@ -0,0 +1,264 @@
+use crate::{
+    domains::Transition,
+    fa::linear::{basis::Basis, Features},
+    Handler,
+    Parameterised,
+};
+use ndarray::Array1;
+
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate")
+)]
+pub struct Response {
+    pub td_error: f64,
+}
+
+/// True online variant of the TD(lambda) algorithm.
+///
+/// # References
+/// - [Van Seijen, H., & Sutton, R. S. (2014). True online TD(lambda). In
+///   International Conference on Machine Learning (pp.
+///   692-700).](http://proceedings.mlr.press/v32/seijen14.pdf)
+/// - [Van Seijen, H., Mahmood, A. R., Pilarski, P. M., Machado, M. C., &
+///   Sutton, R. S. (2016). True online temporal-difference learning. Journal of
+///   Machine Learning Research, 17(145), 1-40.](https://arxiv.org/pdf/1512.04087.pdf)
+#[derive(Clone, Debug, Parameterised)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate")
+)]
+pub struct TrueOnlineTDLambda<B> {
+    pub basis: B,
+    #[weights]
+    pub theta: Array1<f64>,
+
+    pub alpha: f64,
+    pub gamma: f64,
+    pub lambda: f64,
+
+    trace: Array1<f64>,
+    v_old: f64,
+}
+
+impl<B: spaces::Space> TrueOnlineTDLambda<B> {
+    pub fn new(basis: B, alpha: f64, gamma: f64, lambda: f64) -> Self {
+        let n_features: usize = basis.dim().into();
+
+        TrueOnlineTDLambda {
+            basis,
+            theta: Array1::zeros(n_features),
+
+            alpha,
+            gamma,
+            lambda,
+
+            trace: Array1::zeros(n_features),
+            v_old: 0.0,
+        }
+    }
+}
+
+impl<'m, S, A, B> Handler<&'m Transition<S, A>> for TrueOnlineTDLambda<B>
+where B: Basis<&'m S, Value = Features>
+{
+    type Response = Response;
+    type Error = crate::fa::linear::Error;
+
+    fn handle(&mut self, t: &'m Transition<S, A>) -> Result<Response, Self::Error> {
+        let (s, ns) = t.states();
+
+        let phi_s = self.basis.project(s)?;
+        let v = phi_s.dot(&self.theta);
+        let nv = if t.terminated() {
+            0.0
+        } else {
+            self.basis.project(ns)?.dot(&self.theta)
+        };
+
+        let td_error = t.reward + self.gamma * nv - v;
+
+        // Update dutch trace with latest feature vector:
+        let rate = self.gamma * self.lambda;
+        let dotted = phi_s.dot(&self.trace);
+
+        self.trace *= rate;
+        phi_s.scaled_addto(1.0 - self.alpha * rate * dotted, &mut self.trace);
+
+        // Update weight vector:
+        self.theta
+            .scaled_add(self.alpha * (td_error + v - self.v_old), &self.trace);
+        phi_s.scaled_addto(-self.alpha * (v - self.v_old), &mut self.theta);
+
+        if t.terminated() {
+            self.trace.fill(0.0);
+            self.v_old = 0.0;
+        } else {
+            self.v_old = nv;
+        }
+
+        Ok(Response { td_error })
+    }
+}
+
+// Evaluating the dense branch of `Features::dot` requires a BLAS implementation.
+#[cfg(all(test, feature = "blas"))]
+mod tests {
+    use super::*;
+    use crate::{
+        domains::Observation,
+        fa::linear::{basis::Closure, optim::SGD, ScalarLFA},
+        prediction::td::TDLambda,
+        traces::Trace,
+    };
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    const ALPHA: f64 = 0.1;
+    const GAMMA: f64 = 1.0;
+    const LAMBDA: f64 = 0.8;
+
+    type TestBasis = Closure<fn(&usize) -> crate::fa::linear::Result<Features>>;
+
+    fn one_hot() -> TestBasis {
+        Closure::new(5, |s: &usize| Ok(Features::sparse(5, vec![(*s, 1.0)])))
+    }
+
+    // Five-state random walk starting in the middle, terminating with reward 1
+    // off the right end and 0 off the left; the true values are (s + 1) / 6.
+    fn episode(rng: &mut StdRng) -> Vec<Transition<usize, ()>> {
+        let mut s = 2;
+        let mut ep = vec![];
+
+        loop {
+            let (to, reward) = match (s, rng.gen_bool(0.5)) {
+                (0, false) => (Observation::Terminal(0), 0.0),
+                (4, true) => (Observation::Terminal(4), 1.0),
+                (s, false) => (Observation::Full(s - 1), 0.0),
+                (s, true) => (Observation::Full(s + 1), 0.0),
+            };
+            let terminal = to.is_terminal();
+            let from = Observation::Full(s);
+
+            s = *to.state();
+            ep.push(Transition {
+                from,
+                action: (),
+                reward,
+                to,
+            });
+
+            if terminal {
+                return ep;
+            }
+        }
+    }
+
+    // Truncated λ-return, G^{λ|h}_t, bootstrapping from the online weight
+    // sequence, `ws`, where `ws[k]` holds the weights after `k` steps.
+    fn lambda_return(ep: &[Transition<usize, ()>], ws: &[Array1<f64>], t: usize, h: usize) -> f64 {
+        let n_step = |n: usize| {
+            let g: f64 = (0..n).map(|k| GAMMA.powi(k as i32) * ep[t + k].reward).sum();
+
+            if t + n == ep.len() {
+                g
+            } else {
+                g + GAMMA.powi(n as i32) * ws[t + n - 1][*ep[t + n].from.state()]
+            }
+        };
+        let g: f64 =
+            (1..h - t).map(|n| (1.0 - LAMBDA) * LAMBDA.powi(n as i32 - 1) * n_step(n)).sum();
+
+        g + LAMBDA.powi((h - t - 1) as i32) * n_step(h - t)
+    }
+
+    // Online λ-return algorithm: at each horizon, `h`, every update of the
+    // episode is redone from the weights held at the start of the episode.
+    fn online_lambda_return(theta: &Array1<f64>, ep: &[Transition<usize, ()>]) -> Array1<f64> {
+        let mut ws = vec![theta.clone()];
+
+        for h in 1..=ep.len() {
+            let mut w = theta.clone();
+
+            for t in 0..h {
+                let s = *ep[t].from.state();
+
+                w[s] += ALPHA * (lambda_return(ep, &ws, t, h) - w[s]);
+            }
+
+            ws.push(w);
+        }
+
+        ws.pop().unwrap()
+    }
+
+    #[test]
+    fn test_online_lambda_return() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut agent = TrueOnlineTDLambda::new(one_hot(), ALPHA, GAMMA, LAMBDA);
+        let mut expected = Array1::zeros(5);
+
+        for _ in 0..100 {
+            let ep = episode(&mut rng);
+
+            expected = online_lambda_return(&expected, &ep);
+
+            for t in ep.iter() {
+                agent.handle(t).unwrap();
+            }
+
+            for (theta, w) in agent.theta.iter().zip(expected.iter()) {
+                assert!((theta - w).abs() < 1e-10);
+            }
+        }
+
+        for (s, theta) in agent.theta.iter().enumerate() {
+            assert!((theta - (s + 1) as f64 / 6.0).abs() < 0.15);
+        }
+    }
+
+    #[test]
+    fn test_td_lambda() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut agent = TrueOnlineTDLambda::new(one_hot(), ALPHA, GAMMA, LAMBDA);
+        // TDLambda takes unit steps along its trace, so the step size is folded
+        // into the features: with φ(s) = √α e_s, each update moves v(s) by αδ.
+        let basis: TestBasis =
+            Closure::new(5, |s: &usize| Ok(Features::sparse(5, vec![(*s, ALPHA.sqrt())])));
+        let mut td_lambda = TDLambda::new(
+            ScalarLFA::scalar(basis, SGD(1.0)),
+            Trace::accumulating(5, GAMMA, LAMBDA),
+            GAMMA,
+        );
+        let mut expected = Array1::zeros(5);
+        let mut max_gap: f64 = 0.0;
+
+        for _ in 0..100 {
+            let ep = episode(&mut rng);
+
+            expected = online_lambda_return(&expected, &ep);
+
+            for t in ep.iter() {
+                agent.handle(t).unwrap();
+                td_lambda.handle(t).unwrap();
+            }
+
+            // Accumulating TD(λ) only approximates the online λ-return...
+            for (theta, w) in td_lambda.fa_theta.weights.iter().zip(expected.iter()) {
+                max_gap = max_gap.max((ALPHA.sqrt() * theta - w).abs());
+            }
+        }
+
+        assert!(max_gap > 1e-3);
+
+        // ...but both methods approach the true values.
+        for s in 0..5 {
+            let v = (s + 1) as f64 / 6.0;
+
+            assert!((agent.theta[s] - v).abs() < 0.15);
+            assert!((ALPHA.sqrt() * td_lambda.fa_theta.weights[s] - v).abs() < 0.15);
+        }
+    }
+}
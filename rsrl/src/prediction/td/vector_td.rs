@@ -0,0 +1,122 @@
+use super::VectorTransition;
+use crate::{domains::Observation, fa::StateUpdate, Function, Handler};
+
+#[derive(Clone, Debug)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate")
+)]
+pub struct Response<R> {
+    pub td_errors: Vec<f64>,
+    pub vfunc_response: R,
+}
+
+/// Multi-objective TD(0) prediction.
+///
+/// The value function predicts one value per objective, e.g. a `VectorLFA`
+/// with one column of weights per objective or a `Table` over an `Array2`.
+/// Each update computes an independent TD error for every objective, and
+/// these are applied together under the shared state representation.
+#[derive(Clone, Debug, Parameterised)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate")
+)]
+pub struct VectorTD<V> {
+    #[weights]
+    pub v_func: V,
+
+    pub gamma: f64,
+}
+
+impl<V> VectorTD<V> {
+    pub fn new(v_func: V, gamma: f64) -> Self { VectorTD { v_func, gamma } }
+}
+
+impl<'m, S, A, V> Handler<VectorTransition<'m, S, A>> for VectorTD<V>
+where V: Function<(&'m S,), Output = Vec<f64>> + Handler<StateUpdate<&'m S, Vec<f64>>>
+{
+    type Response = Response<V::Response>;
+    type Error = V::Error;
+
+    fn handle(&mut self, msg: VectorTransition<'m, S, A>) -> Result<Self::Response, Self::Error> {
+        let from = msg.transition.from.state();
+        let preds = self.v_func.evaluate((from,));
+
+        let td_errors: Vec<f64> = match msg.transition.to {
+            Observation::Terminal(_) => {
+                msg.rewards.iter().zip(preds.iter()).map(|(r, p)| r - p).collect()
+            },
+            Observation::Full(ref to) | Observation::Partial(ref to) => msg
+                .rewards
+                .iter()
+                .zip(preds.iter())
+                .zip(self.v_func.evaluate((to,)))
+                .map(|((r, p), nv)| r + self.gamma * nv - p)
+                .collect(),
+        };
+
+        self.v_func
+            .handle(StateUpdate {
+                state: from,
+                error: td_errors.clone(),
+            })
+            .map(|r| Response {
+                td_errors,
+                vfunc_response: r,
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{domains::Transition, fa::tabular::Table};
+    use ndarray::Array2;
+
+    #[test]
+    fn test_independent_objectives() {
+        let mut agent = VectorTD::new(Table::dense(Array2::zeros((2, 2))), 0.5);
+
+        let terminal = Transition {
+            from: Observation::Full(0usize),
+            action: (),
+            reward: 0.0,
+            to: Observation::Terminal(1),
+        };
+        let res = agent
+            .handle(VectorTransition {
+                transition: &terminal,
+                rewards: vec![1.0, -2.0],
+            })
+            .unwrap();
+
+        assert_eq!(res.td_errors, vec![1.0, -2.0]);
+        assert_eq!(agent.v_func.evaluate((0,)), vec![1.0, -2.0]);
+
+        // Self-loop in state 1 with constant rewards; V(1) -> r / (1 - γ).
+        let looping = Transition {
+            from: Observation::Full(1usize),
+            action: (),
+            reward: 0.0,
+            to: Observation::Full(1),
+        };
+
+        for _ in 0..100 {
+            agent
+                .handle(VectorTransition {
+                    transition: &looping,
+                    rewards: vec![1.0, 3.0],
+                })
+                .unwrap();
+        }
+
+        let v = agent.v_func.evaluate((1,));
+
+        assert!((v[0] - 2.0).abs() < 1e-10);
+        assert!((v[1] - 6.0).abs() < 1e-10);
+        assert_eq!(agent.v_func.evaluate((0,)), vec![1.0, -2.0]);
+    }
+}
@@ -0,0 +1,694 @@
+//! Experience replay buffers.
+use crate::{domains::Transition, schedule::Parameter};
+use rand::Rng;
+use std::collections::VecDeque;
+
+/// Fixed-capacity ring buffer of transitions for experience replay.
+///
+/// Once the buffer is full, each new transition overwrites the oldest entry.
+#[derive(Clone, Debug)]
+pub struct ReplayBuffer<S, A> {
+    capacity: usize,
+    transitions: Vec<Transition<S, A>>,
+    head: usize,
+}
+
+impl<S, A> ReplayBuffer<S, A> {
+    /// Construct a new, empty replay buffer.
+    ///
+    /// # Arguments
+    ///
+    /// * `capacity` - The maximum number of transitions held at any one time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is zero.
+    pub fn new(capacity: usize) -> Self {
+        assert!(capacity > 0, "Replay buffer capacity must be positive.");
+
+        ReplayBuffer {
+            capacity,
+            transitions: Vec::with_capacity(capacity),
+            head: 0,
+        }
+    }
+
+    /// Return the maximum number of transitions that can be stored.
+    pub fn capacity(&self) -> usize { self.capacity }
+
+    /// Return the number of transitions currently stored.
+    pub fn len(&self) -> usize { self.transitions.len() }
+
+    /// Return true if the buffer holds no transitions.
+    pub fn is_empty(&self) -> bool { self.transitions.is_empty() }
+
+    /// Return true if the buffer has reached its capacity.
+    pub fn is_full(&self) -> bool { self.transitions.len() == self.capacity }
+
+    /// Store a transition, evicting the oldest entry if the buffer is full.
+    pub fn push(&mut self, transition: Transition<S, A>) {
+        if self.is_full() {
+            self.transitions[self.head] = transition;
+        } else {
+            self.transitions.push(transition);
+        }
+
+        self.head = (self.head + 1) % self.capacity;
+    }
+
+    /// Sample a minibatch of transitions uniformly at random, with
+    /// replacement.
+    ///
+    /// The returned batch always contains `batch_size` transitions unless the
+    /// buffer is empty, in which case it is empty too.
+    pub fn sample<R: Rng + ?Sized>(
+        &self,
+        rng: &mut R,
+        batch_size: usize,
+    ) -> Vec<&Transition<S, A>>
+    {
+        if self.is_empty() {
+            return vec![];
+        }
+
+        (0..batch_size)
+            .map(|_| &self.transitions[rng.gen_range(0, self.transitions.len())])
+            .collect()
+    }
+
+    /// Iterate over the stored transitions in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = &Transition<S, A>> { self.transitions.iter() }
+}
+
+/// Replay buffer of n-step transitions.
+///
+/// One-step transitions are pushed as they are experienced and assembled into
+/// n-step transitions over a sliding window: the reward of each stored
+/// transition is the discounted return `Σ_{k=0}^{n-1} γ^k r_{t+k+1}`, and its
+/// successor is the state `n` steps later, `s_{t+n}`. Targets should therefore
+/// bootstrap with a discount of `γ^n`; see
+/// [`bootstrap_discount`](NStepReplayBuffer::bootstrap_discount).
+///
+/// When a terminal transition is pushed, the window is flushed such that every
+/// remaining transition is stored with the (shorter) return up to the end of
+/// the episode and the terminal state as its successor.
+///
+/// # References
+/// - Hessel, M., et al. (2018). Rainbow: Combining improvements in deep
+///   reinforcement learning. In Proceedings of the AAAI Conference on
+///   Artificial Intelligence.
+#[derive(Clone, Debug)]
+pub struct NStepReplayBuffer<S, A> {
+    n_steps: usize,
+    gamma: f64,
+
+    window: VecDeque<Transition<S, A>>,
+    buffer: ReplayBuffer<S, A>,
+}
+
+impl<S: Clone, A: Clone> NStepReplayBuffer<S, A> {
+    /// Construct a new, empty n-step replay buffer.
+    ///
+    /// # Arguments
+    ///
+    /// * `capacity` - The maximum number of n-step transitions held at any
+    ///   one time.
+    /// * `n_steps` - The number of steps, `n`, spanned by each transition.
+    /// * `gamma` - The discount factor used to accumulate rewards.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either `capacity` or `n_steps` is zero.
+    pub fn new(capacity: usize, n_steps: usize, gamma: f64) -> Self {
+        assert!(n_steps > 0, "Number of steps must be positive.");
+
+        NStepReplayBuffer {
+            n_steps,
+            gamma,
+
+            window: VecDeque::with_capacity(n_steps),
+            buffer: ReplayBuffer::new(capacity),
+        }
+    }
+
+    /// Return the number of steps spanned by each transition.
+    pub fn n_steps(&self) -> usize { self.n_steps }
+
+    /// Return the discount to apply to the value of the bootstrap state, `γ^n`.
+    pub fn bootstrap_discount(&self) -> f64 { self.gamma.powi(self.n_steps as i32) }
+
+    /// Return the maximum number of n-step transitions that can be stored.
+    pub fn capacity(&self) -> usize { self.buffer.capacity() }
+
+    /// Return the number of n-step transitions currently stored.
+    pub fn len(&self) -> usize { self.buffer.len() }
+
+    /// Return true if the buffer holds no n-step transitions.
+    pub fn is_empty(&self) -> bool { self.buffer.is_empty() }
+
+    /// Store a one-step transition, completing the n-step transition that
+    /// started `n - 1` steps earlier, if any, or flushing the window if the
+    /// episode has terminated.
+    pub fn push(&mut self, transition: Transition<S, A>) {
+        let terminated = transition.terminated();
+
+        self.window.push_back(transition);
+
+        if terminated {
+            while !self.window.is_empty() {
+                self.store_front();
+            }
+        } else if self.window.len() == self.n_steps {
+            self.store_front();
+        }
+    }
+
+    /// Discard any partially assembled transitions.
+    ///
+    /// This should be called when an episode ends without reaching a terminal
+    /// state (e.g. due to a time limit), since the last `n - 1` transitions
+    /// cannot then be completed.
+    pub fn end_episode(&mut self) { self.window.clear(); }
+
+    fn store_front(&mut self) {
+        let last = self.window.back().unwrap().to.clone();
+        let reward = self
+            .window
+            .iter()
+            .rev()
+            .fold(0.0, |acc, t| t.reward + self.gamma * acc);
+        let first = self.window.pop_front().unwrap();
+
+        self.buffer.push(Transition {
+            from: first.from,
+            action: first.action,
+            reward,
+            to: last,
+        });
+    }
+
+    /// Sample a minibatch of n-step transitions uniformly at random, with
+    /// replacement.
+    pub fn sample<R: Rng + ?Sized>(
+        &self,
+        rng: &mut R,
+        batch_size: usize,
+    ) -> Vec<&Transition<S, A>>
+    {
+        self.buffer.sample(rng, batch_size)
+    }
+
+    /// Iterate over the stored n-step transitions in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = &Transition<S, A>> { self.buffer.iter() }
+}
+
+// Default floor on the magnitude of prioritised replay priorities.
+const DEFAULT_MIN_PRIORITY: f64 = 1e-6;
+
+/// Binary sum-tree over a fixed number of non-negative leaf values.
+#[derive(Clone, Debug)]
+struct SumTree {
+    n_leaves: usize,
+    nodes: Vec<f64>,
+}
+
+impl SumTree {
+    fn new(capacity: usize) -> Self {
+        let n_leaves = capacity.next_power_of_two();
+
+        SumTree {
+            n_leaves,
+            nodes: vec![0.0; 2 * n_leaves],
+        }
+    }
+
+    fn total(&self) -> f64 { self.nodes[1] }
+
+    fn get(&self, index: usize) -> f64 { self.nodes[self.n_leaves + index] }
+
+    fn set(&mut self, index: usize, value: f64) {
+        let mut i = self.n_leaves + index;
+
+        self.nodes[i] = value;
+
+        while i > 1 {
+            i /= 2;
+            self.nodes[i] = self.nodes[2 * i] + self.nodes[2 * i + 1];
+        }
+    }
+
+    /// Find the leaf whose cumulative range contains `mass`.
+    fn find(&self, mut mass: f64) -> usize {
+        let mut i = 1;
+
+        while i < self.n_leaves {
+            let left = self.nodes[2 * i];
+
+            if mass < left || self.nodes[2 * i + 1] <= 0.0 {
+                i *= 2;
+            } else {
+                mass -= left;
+                i = 2 * i + 1;
+            }
+        }
+
+        i - self.n_leaves
+    }
+}
+
+/// Transition drawn from a [`PrioritizedReplayBuffer`].
+#[derive(Debug)]
+pub struct PrioritizedSample<'a, S, A> {
+    /// Position of the transition in the buffer; see
+    /// [`PrioritizedReplayBuffer::update_priorities`].
+    pub index: usize,
+
+    /// Normalised importance-sampling weight.
+    pub weight: f64,
+
+    /// The sampled transition.
+    pub transition: &'a Transition<S, A>,
+}
+
+/// Fixed-capacity replay buffer with proportional prioritisation.
+///
+/// Transitions are sampled with probability `p_i^α / Σ_k p_k^α`, using a
+/// sum-tree for `O(log n)` sampling and priority updates. The bias introduced
+/// by non-uniform sampling is corrected with importance-sampling weights
+/// `(N P(i))^-β`, normalised by the largest weight in the batch. Both `α` and
+/// `β` may be annealed according to a [`Parameter`] schedule, advanced by
+/// [`step`](PrioritizedReplayBuffer::step).
+///
+/// Priorities are floored at a small positive value (see
+/// [`with_min_priority`](PrioritizedReplayBuffer::with_min_priority)), such
+/// that every transition may be sampled and has a finite weight.
+///
+/// # References
+/// - Schaul, T., Quan, J., Antonoglou, I., & Silver, D. (2016). Prioritized
+///   experience replay. In Proceedings of the International Conference on
+///   Learning Representations.
+#[derive(Clone, Debug)]
+pub struct PrioritizedReplayBuffer<S, A> {
+    /// Importance-sampling exponent, read on every call to
+    /// [`sample`](PrioritizedReplayBuffer::sample).
+    pub beta: Parameter,
+
+    alpha: Parameter,
+    min_priority: f64,
+
+    capacity: usize,
+    transitions: Vec<Transition<S, A>>,
+    priorities: Vec<f64>,
+    head: usize,
+    tree: SumTree,
+}
+
+impl<S, A> PrioritizedReplayBuffer<S, A> {
+    /// Construct a new, empty prioritised replay buffer.
+    ///
+    /// # Arguments
+    ///
+    /// * `capacity` - The maximum number of transitions held at any one time.
+    /// * `alpha` - Priority exponent; `0` recovers uniform sampling.
+    /// * `beta` - Importance-sampling exponent; `1` fully corrects the bias.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is zero.
+    pub fn new(capacity: usize, alpha: f64, beta: f64) -> Self {
+        assert!(capacity > 0, "Replay buffer capacity must be positive.");
+
+        PrioritizedReplayBuffer {
+            beta: Parameter::constant(beta),
+
+            alpha: Parameter::constant(alpha),
+            min_priority: DEFAULT_MIN_PRIORITY,
+
+            capacity,
+            transitions: Vec::with_capacity(capacity),
+            priorities: Vec::with_capacity(capacity),
+            head: 0,
+            tree: SumTree::new(capacity),
+        }
+    }
+
+    /// Floor the magnitude of every priority at `min_priority`, which defaults
+    /// to `1e-6`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `min_priority` is not strictly positive.
+    pub fn with_min_priority(mut self, min_priority: f64) -> Self {
+        assert!(min_priority > 0.0, "Minimum priority must be positive.");
+
+        self.min_priority = min_priority;
+        self.refresh();
+        self
+    }
+
+    /// Anneal the priority exponent, `α`, according to `schedule`.
+    pub fn with_alpha_schedule(mut self, schedule: Parameter) -> Self {
+        self.alpha = schedule;
+        self.refresh();
+        self
+    }
+
+    /// Anneal the importance-sampling exponent, `β`, according to `schedule`.
+    pub fn with_beta_schedule(mut self, schedule: Parameter) -> Self {
+        self.beta = schedule;
+        self
+    }
+
+    /// Return the current priority exponent, `α`.
+    pub fn alpha(&self) -> f64 { self.alpha.value() }
+
+    /// Advance the schedules of both `α` and `β` by a single step.
+    ///
+    /// The sampling probabilities of every stored transition are recomputed
+    /// if `α` changes.
+    pub fn step(&mut self) {
+        let alpha = self.alpha.value();
+
+        self.alpha.step();
+        self.beta.step();
+
+        if self.alpha.value() != alpha {
+            self.refresh();
+        }
+    }
+
+    fn leaf(&self, priority: f64) -> f64 {
+        priority.abs().max(self.min_priority).powf(self.alpha.value())
+    }
+
+    fn refresh(&mut self) {
+        for i in 0..self.priorities.len() {
+            self.tree.set(i, self.leaf(self.priorities[i]));
+        }
+    }
+
+    /// Return the maximum number of transitions that can be stored.
+    pub fn capacity(&self) -> usize { self.capacity }
+
+    /// Return the number of transitions currently stored.
+    pub fn len(&self) -> usize { self.transitions.len() }
+
+    /// Return true if the buffer holds no transitions.
+    pub fn is_empty(&self) -> bool { self.transitions.is_empty() }
+
+    /// Return true if the buffer has reached its capacity.
+    pub fn is_full(&self) -> bool { self.transitions.len() == self.capacity }
+
+    /// Store a transition with a given priority (typically `|δ| + ε`),
+    /// evicting the oldest entry if the buffer is full.
+    pub fn push(&mut self, transition: Transition<S, A>, priority: f64) {
+        if self.is_full() {
+            self.transitions[self.head] = transition;
+            self.priorities[self.head] = priority;
+        } else {
+            self.transitions.push(transition);
+            self.priorities.push(priority);
+        }
+
+        self.tree.set(self.head, self.leaf(priority));
+        self.head = (self.head + 1) % self.capacity;
+    }
+
+    /// Sample a minibatch of transitions in proportion to their priorities.
+    ///
+    /// The total priority mass is split into `batch_size` equal segments and
+    /// one transition is drawn from each. The batch is empty if the buffer is
+    /// empty.
+    pub fn sample<R: Rng + ?Sized>(
+        &self,
+        rng: &mut R,
+        batch_size: usize,
+    ) -> Vec<PrioritizedSample<'_, S, A>>
+    {
+        let total = self.tree.total();
+
+        if self.is_empty() || total <= 0.0 {
+            return vec![];
+        }
+
+        let n = self.transitions.len() as f64;
+        let beta = self.beta.value();
+        let segment = total / batch_size as f64;
+
+        let mut batch: Vec<PrioritizedSample<S, A>> = (0..batch_size)
+            .map(|i| {
+                let mass = segment * (i as f64 + rng.gen::<f64>());
+                let index = self.tree.find(mass.min(total)).min(self.transitions.len() - 1);
+                let prob = self.tree.get(index) / total;
+
+                PrioritizedSample {
+                    index,
+                    weight: (n * prob).powf(-beta),
+                    transition: &self.transitions[index],
+                }
+            })
+            .collect();
+
+        let max_weight = batch.iter().fold(0.0f64, |acc, s| acc.max(s.weight));
+
+        if max_weight > 0.0 {
+            for s in batch.iter_mut() {
+                s.weight /= max_weight;
+            }
+        }
+
+        batch
+    }
+
+    /// Overwrite the priorities of previously sampled transitions.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `indices` and `priorities` differ in length, or if any index
+    /// is out of bounds.
+    pub fn update_priorities(&mut self, indices: &[usize], priorities: &[f64]) {
+        assert_eq!(indices.len(), priorities.len());
+
+        for (&i, &p) in indices.iter().zip(priorities.iter()) {
+            assert!(i < self.transitions.len(), "Replay index out of bounds.");
+
+            self.priorities[i] = p;
+            self.tree.set(i, self.leaf(p));
+        }
+    }
+
+    /// Iterate over the stored transitions in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = &Transition<S, A>> { self.transitions.iter() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domains::Observation;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    fn make_transition(i: usize) -> Transition<usize, usize> {
+        Transition {
+            from: Observation::Full(i),
+            action: 0,
+            reward: i as f64,
+            to: Observation::Full(i + 1),
+        }
+    }
+
+    #[test]
+    fn test_eviction() {
+        let mut buffer = ReplayBuffer::new(3);
+
+        for i in 0..3 {
+            buffer.push(make_transition(i));
+        }
+
+        assert!(buffer.is_full());
+        assert_eq!(buffer.len(), 3);
+
+        buffer.push(make_transition(3));
+        buffer.push(make_transition(4));
+
+        assert_eq!(buffer.len(), 3);
+
+        let mut rewards: Vec<f64> = buffer.iter().map(|t| t.reward).collect();
+        rewards.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+        assert_eq!(rewards, vec![2.0, 3.0, 4.0]);
+    }
+
+    #[test]
+    fn test_sample_larger_than_len() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut buffer = ReplayBuffer::new(10);
+
+        assert!(buffer.sample(&mut rng, 5).is_empty());
+
+        buffer.push(make_transition(0));
+        buffer.push(make_transition(1));
+
+        let batch = buffer.sample(&mut rng, 5);
+
+        assert_eq!(batch.len(), 5);
+        assert!(batch.iter().all(|t| t.reward == 0.0 || t.reward == 1.0));
+    }
+
+    #[test]
+    fn test_sum_tree() {
+        let mut tree = SumTree::new(3);
+
+        tree.set(0, 1.0);
+        tree.set(1, 2.0);
+        tree.set(2, 3.0);
+
+        assert_eq!(tree.total(), 6.0);
+        assert_eq!(tree.find(0.5), 0);
+        assert_eq!(tree.find(1.5), 1);
+        assert_eq!(tree.find(5.9), 2);
+        assert_eq!(tree.find(6.0), 2);
+    }
+
+    #[test]
+    fn test_prioritized_frequencies() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut buffer = PrioritizedReplayBuffer::new(4, 1.0, 1.0);
+
+        buffer.push(make_transition(0), 1.0);
+        buffer.push(make_transition(1), 9.0);
+
+        let mut counts = [0usize; 2];
+
+        for _ in 0..1000 {
+            for s in buffer.sample(&mut rng, 4) {
+                counts[s.index] += 1;
+            }
+        }
+
+        assert!(counts[1] > 5 * counts[0]);
+    }
+
+    #[test]
+    fn test_prioritized_weights_and_updates() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut buffer = PrioritizedReplayBuffer::new(2, 1.0, 1.0);
+
+        buffer.push(make_transition(0), 1.0);
+        buffer.push(make_transition(1), 3.0);
+
+        let batch = buffer.sample(&mut rng, 8);
+
+        assert_eq!(batch.len(), 8);
+        assert!(batch.iter().all(|s| s.weight > 0.0 && s.weight <= 1.0));
+
+        for s in batch.iter() {
+            if s.index == 1 {
+                assert!(s.weight < 1.0);
+            }
+        }
+
+        buffer.update_priorities(&[0, 1], &[0.0, 1.0]);
+
+        assert!(buffer.sample(&mut rng, 8).iter().all(|s| s.index == 1));
+    }
+
+    #[test]
+    fn test_prioritized_zero_priority() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut buffer = PrioritizedReplayBuffer::new(2, 1.0, 1.0).with_min_priority(0.5);
+
+        buffer.push(make_transition(0), 0.0);
+        buffer.push(make_transition(1), 1.0);
+
+        let batch = buffer.sample(&mut rng, 64);
+
+        assert_eq!(batch.len(), 64);
+        assert!(batch.iter().all(|s| s.weight.is_finite() && s.weight > 0.0));
+
+        // P(0) = 1/3 and P(1) = 2/3, so the weights are in the ratio 2:1.
+        for s in batch.iter() {
+            let expected = if s.index == 0 { 1.0 } else { 0.5 };
+
+            assert!((s.weight - expected).abs() < 1e-10);
+        }
+    }
+
+    #[test]
+    fn test_prioritized_schedules() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut buffer = PrioritizedReplayBuffer::new(2, 1.0, 1.0)
+            .with_alpha_schedule(Parameter::linear(0.0, 1.0, 1))
+            .with_beta_schedule(Parameter::linear(0.5, 1.0, 1));
+
+        buffer.push(make_transition(0), 1.0);
+        buffer.push(make_transition(1), 9.0);
+
+        assert_eq!(buffer.alpha(), 0.0);
+
+        // Sampling is uniform, so every weight is one.
+        assert!(buffer.sample(&mut rng, 16).iter().all(|s| (s.weight - 1.0).abs() < 1e-10));
+
+        buffer.step();
+
+        assert_eq!(buffer.alpha(), 1.0);
+        assert_eq!(buffer.beta.value(), 1.0);
+
+        let weights: Vec<f64> = buffer.sample(&mut rng, 16).iter().map(|s| s.weight).collect();
+
+        assert!(weights.iter().any(|&w| (w - 1.0 / 9.0).abs() < 1e-10));
+    }
+
+    #[test]
+    fn test_n_step_returns() {
+        let mut buffer = NStepReplayBuffer::new(10, 3, 0.5);
+
+        // Rewards 0, 1, 2, 3 along the chain 0 -> 1 -> 2 -> 3 -> 4.
+        for i in 0..4 {
+            buffer.push(make_transition(i));
+        }
+
+        assert_eq!(buffer.len(), 2);
+        assert_eq!(buffer.bootstrap_discount(), 0.125);
+
+        let mut ts: Vec<_> = buffer.iter().cloned().collect();
+
+        ts.sort_by_key(|t| *t.from.state());
+
+        // 0 + 0.5 * 1 + 0.25 * 2, bootstrapping from state 3.
+        assert_eq!((*ts[0].from.state(), ts[0].reward, *ts[0].to.state()), (0, 1.0, 3));
+
+        // 1 + 0.5 * 2 + 0.25 * 3, bootstrapping from state 4.
+        assert_eq!((*ts[1].from.state(), ts[1].reward, *ts[1].to.state()), (1, 2.75, 4));
+        assert!(!ts[1].terminated());
+    }
+
+    #[test]
+    fn test_n_step_flush() {
+        let mut buffer = NStepReplayBuffer::new(10, 3, 0.5);
+
+        buffer.push(make_transition(0));
+        buffer.push(make_transition(1));
+        buffer.push(Transition {
+            from: Observation::Full(2),
+            action: 0,
+            reward: 4.0,
+            to: Observation::Terminal(3),
+        });
+
+        let mut ts: Vec<_> = buffer.iter().cloned().collect();
+
+        ts.sort_by_key(|t| *t.from.state());
+
+        assert_eq!(ts.len(), 3);
+        assert!(ts.iter().all(|t| t.terminated() && *t.to.state() == 3));
+        assert_eq!(ts.iter().map(|t| t.reward).collect::<Vec<_>>(), vec![1.5, 3.0, 4.0]);
+
+        // The window restarts with the next episode.
+        buffer.push(make_transition(0));
+        buffer.push(make_transition(1));
+        buffer.end_episode();
+        buffer.push(make_transition(5));
+
+        assert_eq!(buffer.len(), 3);
+    }
+}
@@ -0,0 +1,183 @@
+//! Annealing schedules for scalar hyperparameters.
+//!
+//! A `Parameter` holds the current value of a hyperparameter such as a
+//! learning rate, exploration rate or temperature, along with the rule used
+//! to update it each time `step` is called.
+//!
+//! ```
+//! use rsrl::schedule::Parameter;
+//!
+//! let mut epsilon = Parameter::linear(1.0, 0.1, 10);
+//!
+//! for _ in 0..5 {
+//!     epsilon.step();
+//! }
+//!
+//! assert!((epsilon.value() - 0.55).abs() < 1e-7);
+//! ```
+
+/// Scalar hyperparameter with an explicit annealing schedule.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate")
+)]
+pub enum Parameter {
+    /// Fixed value that is unaffected by `step`.
+    Constant(f64),
+
+    /// Geometric decay, `x <- max(decay * x, floor)`.
+    Exponential { value: f64, decay: f64, floor: f64 },
+
+    /// Linear interpolation from `initial` to `target` over `steps` steps.
+    Linear { initial: f64, target: f64, steps: usize, count: usize },
+
+    /// Polynomial interpolation, `(initial - target) * (1 - t / steps)^power +
+    /// target`, which is held at `target` once `steps` steps have elapsed.
+    Polynomial { initial: f64, target: f64, steps: usize, power: f64, count: usize },
+}
+
+impl Parameter {
+    pub fn constant(value: f64) -> Self { Parameter::Constant(value) }
+
+    pub fn exponential(initial: f64, decay: f64, floor: f64) -> Self {
+        Parameter::Exponential {
+            value: initial.max(floor),
+            decay,
+            floor,
+        }
+    }
+
+    pub fn linear(initial: f64, target: f64, steps: usize) -> Self {
+        Parameter::Linear {
+            initial,
+            target,
+            steps,
+            count: 0,
+        }
+    }
+
+    pub fn polynomial(initial: f64, target: f64, steps: usize, power: f64) -> Self {
+        Parameter::Polynomial {
+            initial,
+            target,
+            steps,
+            power,
+            count: 0,
+        }
+    }
+
+    /// Return the current value of the parameter.
+    pub fn value(&self) -> f64 {
+        match *self {
+            Parameter::Constant(value) => value,
+            Parameter::Exponential { value, .. } => value,
+            Parameter::Linear {
+                initial,
+                target,
+                steps,
+                count,
+            } => Self::interpolate(initial, target, steps, 1.0, count),
+            Parameter::Polynomial {
+                initial,
+                target,
+                steps,
+                power,
+                count,
+            } => Self::interpolate(initial, target, steps, power, count),
+        }
+    }
+
+    /// Advance the schedule by a single step.
+    pub fn step(&mut self) {
+        match self {
+            Parameter::Constant(_) => {},
+            Parameter::Exponential { value, decay, floor } => {
+                *value = (*value * *decay).max(*floor);
+            },
+            Parameter::Linear { steps, count, .. }
+            | Parameter::Polynomial { steps, count, .. } => {
+                *count = (*count + 1).min(*steps);
+            },
+        }
+    }
+
+    fn interpolate(initial: f64, target: f64, steps: usize, power: f64, count: usize) -> f64 {
+        if steps == 0 {
+            return target;
+        }
+
+        let remaining = 1.0 - count.min(steps) as f64 / steps as f64;
+
+        (initial - target) * remaining.powf(power) + target
+    }
+}
+
+impl From<f64> for Parameter {
+    fn from(value: f64) -> Parameter { Parameter::Constant(value) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Parameter;
+
+    fn value_after(mut p: Parameter, n: usize) -> f64 {
+        for _ in 0..n {
+            p.step();
+        }
+
+        p.value()
+    }
+
+    #[test]
+    fn test_constant() {
+        let p = Parameter::constant(0.3);
+
+        assert_eq!(value_after(p, 0), 0.3);
+        assert_eq!(value_after(p, 10), 0.3);
+        assert_eq!(Parameter::from(0.3), p);
+    }
+
+    #[test]
+    fn test_exponential() {
+        let p = Parameter::exponential(1.0, 0.5, 0.1);
+
+        assert_eq!(value_after(p, 0), 1.0);
+        assert_eq!(value_after(p, 1), 0.5);
+        assert_eq!(value_after(p, 3), 0.125);
+        assert_eq!(value_after(p, 4), 0.1);
+        assert_eq!(value_after(p, 100), 0.1);
+    }
+
+    #[test]
+    fn test_linear() {
+        let p = Parameter::linear(1.0, 0.0, 4);
+
+        assert_eq!(value_after(p, 0), 1.0);
+        assert_eq!(value_after(p, 1), 0.75);
+        assert_eq!(value_after(p, 2), 0.5);
+        assert_eq!(value_after(p, 4), 0.0);
+        assert_eq!(value_after(p, 100), 0.0);
+    }
+
+    #[test]
+    fn test_polynomial() {
+        let p = Parameter::polynomial(1.0, 0.0, 4, 2.0);
+
+        assert_eq!(value_after(p, 0), 1.0);
+        assert_eq!(value_after(p, 1), 0.5625);
+        assert_eq!(value_after(p, 2), 0.25);
+        assert_eq!(value_after(p, 4), 0.0);
+        assert_eq!(value_after(p, 100), 0.0);
+
+        let q = Parameter::polynomial(0.1, 1.0, 4, 1.0);
+
+        assert!((value_after(q, 2) - 0.55).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_zero_steps() {
+        assert_eq!(Parameter::linear(1.0, 0.2, 0).value(), 0.2);
+    }
+}
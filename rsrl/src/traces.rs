@@ -3,12 +3,27 @@ use crate::params::{Buffer, BufferMut};
 use ndarray::{ArrayBase, Array, Dimension, IntoDimension, DataMut};
 
 /// Eligibility trace buffer.
+///
+/// On large feature spaces, decayed traces accumulate many components that
+/// are negligibly small but non-zero. Setting a pruning threshold (see
+/// [`Trace::with_pruning`]) drops such components after every update, keeping
+/// the trace sparse.
+#[derive(Clone, Debug)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate")
+)]
 pub struct Trace<B: BufferMut, R: UpdateRule<B>> {
     /// Internal gradient buffer.
     pub buffer: B,
 
     /// Eligibility update rule.
     pub update_rule: R,
+
+    /// Magnitude below which components are pruned after each update, if any.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub prune_threshold: Option<f64>,
 }
 
 impl<B, R> Trace<B, R>
@@ -34,7 +49,19 @@ where
     /// });
     /// ```
     pub fn new(buffer: B, update_rule: R) -> Self {
-        Trace { buffer, update_rule, }
+        Trace { buffer, update_rule, prune_threshold: None, }
+    }
+
+    /// Construct a new eligibility trace which prunes every component whose
+    /// magnitude falls below `threshold`.
+    ///
+    /// # Arguments
+    ///
+    /// * `buffer` - A gradient buffer instance.
+    /// * `update_rule` - The eligibility update rule.
+    /// * `threshold` - Pruning threshold.
+    pub fn with_pruning(buffer: B, update_rule: R, threshold: f64) -> Self {
+        Trace { buffer, update_rule, prune_threshold: Some(threshold), }
     }
 
     /// Construct a new eligibility trace with empty gradient buffer.
@@ -65,9 +92,41 @@ impl<B: BufferMut> Trace<B, Accumulate> {
     }
 }
 
+impl<B: BufferMut> Trace<B, Replace> {
+    /// Construct a new eligibility trace with empty buffer and max-based
+    /// replacement rule.
+    ///
+    /// # Arguments
+    ///
+    /// * `dim` - Dimensionality of the buffer.
+    /// * `gamma` - Discount factor.
+    /// * `lambda` - Forgetting rate.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use approx::assert_abs_diff_eq;
+    /// use rsrl::{params::Vector, traces::{Trace, Replace}};
+    ///
+    /// let mut trace = Trace::<Vector, Replace>::max_replacing(1, 0.95, 0.7);
+    ///
+    /// trace.update(&Vector::ones(1));
+    /// trace.update(&Vector::ones(1));
+    /// assert_abs_diff_eq!(trace.buffer[0], 1.0);
+    /// ```
+    pub fn max_replacing<D>(dim: D, gamma: f64, lambda: f64) -> Self
+    where D: IntoDimension<Dim = B::Dim>,
+    {
+        Trace::zeros(dim, Replace { gamma, lambda, })
+    }
+}
+
 impl<B: BufferMut> Trace<B, Saturate> {
     /// Construct a new eligibility trace with empty buffer and replacement rule.
     ///
+    /// This is the historical name of [`Trace::saturating`], and uses the
+    /// saturation rule; see [`Trace::max_replacing`] for the max-based rule.
+    ///
     /// # Arguments
     ///
     /// * `dim` - Dimensionality of the buffer.
@@ -75,6 +134,19 @@ impl<B: BufferMut> Trace<B, Saturate> {
     /// * `lambda` - Forgetting rate.
     pub fn replacing<D>(dim: D, gamma: f64, lambda: f64) -> Self
     where D: IntoDimension<Dim = B::Dim>,
+    {
+        Trace::saturating(dim, gamma, lambda)
+    }
+
+    /// Construct a new eligibility trace with empty buffer and saturation rule.
+    ///
+    /// # Arguments
+    ///
+    /// * `dim` - Dimensionality of the buffer.
+    /// * `gamma` - Discount factor.
+    /// * `lambda` - Forgetting rate.
+    pub fn saturating<D>(dim: D, gamma: f64, lambda: f64) -> Self
+    where D: IntoDimension<Dim = B::Dim>,
     {
         Trace::zeros(dim, Saturate { gamma, lambda, })
     }
@@ -125,7 +197,11 @@ where
     /// assert_abs_diff_eq!(trace.buffer[0], 0.665);
     /// ```
     pub fn update(&mut self, buffer: &B) {
-        self.update_rule.update_trace(&mut self.buffer, buffer)
+        self.update_rule.update_trace(&mut self.buffer, buffer);
+
+        if let Some(threshold) = self.prune_threshold {
+            self.buffer.prune(threshold);
+        }
     }
 
     /// Reset the trace to zeros.
@@ -147,6 +223,25 @@ where
     /// assert_abs_diff_eq!(trace.buffer[0], 0.0);
     /// ```
     pub fn reset(&mut self) { self.buffer.reset() }
+
+    /// Return the number of components in the trace, i.e. the dimensionality
+    /// of the underlying gradient buffer.
+    pub fn len(&self) -> usize { self.buffer.raw_dim().size() }
+
+    /// Returns true if the trace has no components.
+    pub fn is_empty(&self) -> bool { self.len() == 0 }
+
+    /// Return the forgetting rate, `λ`, of the update rule, if it has one.
+    pub fn lambda(&self) -> Option<f64> { self.update_rule.lambda() }
+
+    /// Return the number of active components in the trace; see
+    /// [`Buffer::n_active`].
+    pub fn n_active(&self) -> usize { self.buffer.n_active() }
+
+    /// Return the largest magnitude of any component of the trace.
+    pub fn max_abs(&self) -> f64 {
+        self.buffer.to_dense().iter().fold(0.0, |acc, x| acc.max(x.abs()))
+    }
 }
 
 impl<B: BufferMut, R: UpdateRule<B>> Buffer for Trace<B, R> {
@@ -168,6 +263,8 @@ impl<B: BufferMut, R: UpdateRule<B>> Buffer for Trace<B, R> {
         self.buffer.scaled_addto(alpha, weights)
     }
 
+    fn n_active(&self) -> usize { self.buffer.n_active() }
+
     fn to_dense(&self) -> Array<f64, B::Dim> { self.buffer.to_dense() }
 
     fn into_dense(self) -> Array<f64, B::Dim> { self.buffer.into_dense() }
@@ -182,9 +279,20 @@ pub trait UpdateRule<B: BufferMut> {
     /// * `trace` - Mutable reference to the internal trace buffer.
     /// * `buffer` - New gradient buffer instance.
     fn update_trace(&self, trace: &mut B, buffer: &B);
+
+    /// Return the forgetting rate, `λ`, of the rule, if it has one.
+    ///
+    /// Defaults to `None`, for rules that are not parameterised by `λ`.
+    fn lambda(&self) -> Option<f64> { None }
 }
 
 /// Accumulating eligibility trace rule.
+#[derive(Clone, Debug)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate")
+)]
 pub struct Accumulate {
     /// Discount factor.
     pub gamma: f64,
@@ -199,9 +307,50 @@ impl<B: BufferMut> UpdateRule<B> for Accumulate {
 
         trace.merge_inplace(buffer, |x, y| rate * x + y)
     }
+
+    fn lambda(&self) -> Option<f64> { Some(self.lambda) }
+}
+
+/// Replacing eligibility trace rule.
+///
+/// Each component of the decayed trace is replaced by the corresponding
+/// component of the new buffer whenever the latter is larger, i.e. `e = max(γλe,
+/// φ)`. For binary features (e.g. tile coding) this caps the eligibility of an
+/// active feature at one, however often it is revisited.
+#[derive(Clone, Debug)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate")
+)]
+pub struct Replace {
+    /// Discount factor.
+    pub gamma: f64,
+
+    /// Forgetting rate.
+    pub lambda: f64,
+}
+
+impl<B: BufferMut> UpdateRule<B> for Replace {
+    fn update_trace(&self, trace: &mut B, buffer: &B) {
+        let rate = self.gamma * self.lambda;
+
+        trace.merge_inplace(buffer, |x, y| f64::max(rate * x, y))
+    }
+
+    fn lambda(&self) -> Option<f64> { Some(self.lambda) }
 }
 
-/// Replacing (saturating) eligibility trace rule.
+/// Saturating eligibility trace rule.
+///
+/// Accumulates as per [`Accumulate`], but clips each component to the interval
+/// `[-1, 1]`.
+#[derive(Clone, Debug)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate")
+)]
 pub struct Saturate {
     /// Discount factor.
     pub gamma: f64,
@@ -217,9 +366,17 @@ impl<B: BufferMut> UpdateRule<B> for Saturate {
         trace
             .merge_inplace(buffer, |x, y| f64::max(-1.0, f64::min(1.0, rate * x + y)));
     }
+
+    fn lambda(&self) -> Option<f64> { Some(self.lambda) }
 }
 
 /// Dutch eligibility trace rule.
+#[derive(Clone, Debug)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(crate = "serde_crate")
+)]
 pub struct Dutch {
     /// Learning rate.
     pub alpha: f64,
@@ -237,4 +394,163 @@ impl<B: BufferMut> UpdateRule<B> for Dutch {
 
         trace.merge_inplace(buffer, |x, y| rate * x + y)
     }
+
+    fn lambda(&self) -> Option<f64> { Some(self.lambda) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::params::{Sparse, Vector};
+    use ndarray::{arr1, Array2};
+
+    fn repeat<R: UpdateRule<Vector>>(mut trace: Trace<Vector, R>) -> Vector {
+        let phi = arr1(&[1.0, 0.0, 0.5]);
+
+        for _ in 0..3 {
+            trace.update(&phi);
+        }
+
+        trace.buffer
+    }
+
+    #[test]
+    fn test_repeated_accumulating() {
+        let e = repeat(Trace::accumulating(3, 1.0, 0.5));
+
+        assert_eq!(e, arr1(&[1.75, 0.0, 0.875]));
+    }
+
+    #[test]
+    fn test_repeated_replacing() {
+        let e = repeat(Trace::max_replacing(3, 1.0, 0.5));
+
+        assert_eq!(e, arr1(&[1.0, 0.0, 0.5]));
+    }
+
+    #[test]
+    fn test_replacing_saturates() {
+        let e = repeat(Trace::replacing(3, 1.0, 0.5));
+
+        assert_eq!(e, arr1(&[1.0, 0.0, 0.875]));
+    }
+
+    #[test]
+    fn test_repeated_saturating() {
+        let e = repeat(Trace::saturating(3, 1.0, 0.5));
+
+        assert_eq!(e, arr1(&[1.0, 0.0, 0.875]));
+    }
+
+    #[test]
+    fn test_repeated_dutch() {
+        let e = repeat(Trace::dutch(3, 0.5, 1.0, 0.5));
+
+        assert_eq!(e, arr1(&[1.3125, 0.0, 0.65625]));
+    }
+
+    #[test]
+    fn test_accessors_and_reset() {
+        let mut trace: Trace<Vector, _> = Trace::accumulating(3, 1.0, 0.5);
+
+        assert_eq!(trace.len(), 3);
+        assert!(!trace.is_empty());
+        assert_eq!(trace.lambda(), Some(0.5));
+        assert_eq!(trace.max_abs(), 0.0);
+
+        trace.update(&arr1(&[1.0, 0.0, -2.0]));
+
+        assert_eq!(trace.max_abs(), 2.0);
+
+        trace.reset();
+
+        assert_eq!(trace.buffer, arr1(&[0.0, 0.0, 0.0]));
+        assert_eq!(trace.max_abs(), 0.0);
+        assert_eq!(trace.len(), 3);
+
+        assert_eq!(Trace::<Vector, _>::dutch(4, 0.1, 0.9, 0.3).lambda(), Some(0.3));
+    }
+
+    #[test]
+    fn test_default_lambda() {
+        struct Clear;
+
+        impl UpdateRule<Vector> for Clear {
+            fn update_trace(&self, trace: &mut Vector, buffer: &Vector) { trace.assign(buffer); }
+        }
+
+        assert_eq!(Trace::zeros(2, Clear).lambda(), None);
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn test_deserialise_without_pruning() {
+        let mut trace: Trace<Vector, _> = Trace::accumulating(2, 1.0, 0.5);
+
+        trace.update(&arr1(&[1.0, 0.5]));
+
+        // Traces serialised before pruning was introduced lack the field.
+        let mut value = serde_json::to_value(&trace).unwrap();
+
+        value.as_object_mut().unwrap().remove("prune_threshold");
+
+        let loaded: Trace<Vector, Accumulate> = serde_json::from_value(value).unwrap();
+
+        assert_eq!(loaded.buffer, trace.buffer);
+        assert_eq!(loaded.prune_threshold, None);
+    }
+
+    #[test]
+    fn test_pruning() {
+        let rule = Accumulate {
+            gamma: 1.0,
+            lambda: 0.5,
+        };
+        let phi = Sparse::new((3, 1), vec![([0, 0], 1.0), ([1, 0], 0.1)].into_iter().collect())
+            .unwrap();
+        let zero = Sparse::zeros((3, 1));
+
+        let mut pruned = Trace::with_pruning(Sparse::zeros((3, 1)), rule.clone(), 0.02);
+        let mut unpruned = Trace::new(Sparse::zeros((3, 1)), rule);
+
+        pruned.update(&phi);
+        unpruned.update(&phi);
+
+        assert_eq!(pruned.n_active(), 2);
+
+        // 0.1 * 0.5^2 = 0.025 remains above the threshold...
+        for _ in 0..2 {
+            pruned.update(&zero);
+            unpruned.update(&zero);
+        }
+
+        assert_eq!(pruned.n_active(), 2);
+
+        // ...but 0.1 * 0.5^3 = 0.0125 does not.
+        pruned.update(&zero);
+        unpruned.update(&zero);
+
+        assert_eq!(pruned.n_active(), 1);
+        assert_eq!(unpruned.n_active(), 2);
+        assert_eq!(
+            pruned.to_dense(),
+            Array2::from_shape_vec((3, 1), vec![0.125, 0.0, 0.0]).unwrap()
+        );
+
+        // Dense buffers are zeroed rather than shrunk.
+        let mut dense = Trace::with_pruning(Vector::zeros(2), Accumulate {
+            gamma: 1.0,
+            lambda: 0.5,
+        }, 0.1);
+
+        dense.update(&arr1(&[1.0, 0.15]));
+
+        assert_eq!(dense.n_active(), 2);
+
+        dense.update(&arr1(&[0.0, 0.0]));
+
+        assert_eq!(dense.buffer, arr1(&[0.5, 0.0]));
+        assert_eq!(dense.n_active(), 1);
+        assert_eq!(dense.len(), 2);
+    }
 }
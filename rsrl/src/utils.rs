@@ -1,4 +1,5 @@
 #![allow(dead_code)]
+use crate::params::Buffer;
 use ndarray::Array2;
 use rand::{seq::SliceRandom, thread_rng, Rng};
 use std::f64;
@@ -78,6 +79,45 @@ where
     (maximum, value)
 }
 
+/// Return the index of the largest value in `vals`, breaking ties uniformly at
+/// random using `rng`.
+pub fn argmax_ties<R: Rng + ?Sized>(rng: &mut R, vals: &[f64]) -> usize {
+    argmax_choose_rng(rng, vals.iter().copied()).0
+}
+
+/// Return the index of the largest value in `vals`, breaking ties in favour of
+/// the lowest index.
+///
+/// As with [`argmax_first`], index `0` is returned if `vals` is empty or none
+/// of its values exceed `f64::MIN` (e.g. all are `-inf`).
+pub fn argmax_ties_first(vals: &[f64]) -> usize {
+    argmaxima(vals.iter().copied()).0.first().copied().unwrap_or(0)
+}
+
+/// Clip an error signal to the interval `[-delta, delta]`, if a bound is given.
+pub fn clip_error(error: f64, delta: Option<f64>) -> f64 {
+    match delta {
+        Some(d) => error.max(-d).min(d),
+        None => error,
+    }
+}
+
+/// Scale the step size of an update, `alpha * jacobian`, such that the global
+/// L2 norm of the update does not exceed `max_norm`, if a bound is given.
+///
+/// Unlike [`clip_error`], this bounds the magnitude of the change to the
+/// weights as a whole, regardless of how it is distributed between them.
+pub fn clip_grad_norm<J: Buffer>(alpha: f64, jacobian: &J, max_norm: Option<f64>) -> f64 {
+    match max_norm {
+        Some(m) => {
+            let norm = alpha.abs() * jacobian.l2_norm();
+
+            if norm > m { alpha * m / norm } else { alpha }
+        },
+        None => alpha,
+    }
+}
+
 /// Compute the pseudo-inverse of a real matrix using SVD.
 pub fn pinv(m: &Array2<f64>) -> Result<Array2<f64>, ndarray_linalg::error::LinalgError> {
     use ndarray::Axis;
@@ -114,3 +154,57 @@ pub fn pinv(m: &Array2<f64>) -> Result<Array2<f64>, ndarray_linalg::error::Linal
         vt.t().dot(&(&u.t() * &sinv))
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn test_argmax_ties_uniform() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut counts = [0usize; 4];
+
+        for _ in 0..10000 {
+            counts[argmax_ties(&mut rng, &[1.0, 1.0, 1.0, 1.0])] += 1;
+        }
+
+        for &c in counts.iter() {
+            assert!((c as f64 / 10000.0 - 0.25).abs() < 0.03);
+        }
+
+        for _ in 0..100 {
+            assert_eq!(argmax_ties(&mut rng, &[0.0, 2.0, 1.0, 2.0 - 1e-3]), 1);
+        }
+    }
+
+    #[test]
+    fn test_argmax_ties_first() {
+        assert_eq!(argmax_ties_first(&[1.0, 1.0, 1.0, 1.0]), 0);
+        assert_eq!(argmax_ties_first(&[0.0, 3.0, 1.0, 3.0]), 1);
+        assert_eq!(argmax_ties_first(&[-5.0, -2.0]), 1);
+    }
+
+    #[test]
+    fn test_argmax_ties_first_degenerate() {
+        assert_eq!(argmax_ties_first(&[]), 0);
+        assert_eq!(argmax_ties_first(&[f64::NEG_INFINITY, f64::NEG_INFINITY]), 0);
+        assert_eq!(argmax_ties_first(&[f64::NEG_INFINITY, -1.0]), 1);
+    }
+
+    #[test]
+    fn test_clip_grad_norm() {
+        let grad = ndarray::Array1::from(vec![3.0, 4.0]);
+
+        // A large update, of norm 50, is scaled down to the bound...
+        let alpha = clip_grad_norm(10.0, &grad, Some(1.0));
+
+        assert!((alpha - 0.2).abs() < 1e-12);
+        assert!(((alpha * grad.l2_norm()) - 1.0).abs() < 1e-12);
+        assert!((clip_grad_norm(-10.0, &grad, Some(1.0)) + 0.2).abs() < 1e-12);
+
+        // ...while a small one, of norm 0.5, is left untouched.
+        assert_eq!(clip_grad_norm(0.1, &grad, Some(1.0)), 0.1);
+        assert_eq!(clip_grad_norm(10.0, &grad, None), 10.0);
+    }
+}
@@ -1,4 +1,4 @@
-use super::{runge_kutta4, Domain, Observation, Reward};
+use super::{runge_kutta4, Domain, Observation, Reward, StartDistribution};
 use crate::{
     consts::{G, PI_OVER_2},
     spaces::{discrete::Ordinal, real::Interval, ProductSpace},
@@ -46,11 +46,26 @@ make_index!(StateIndex [
 /// length of one link above the base.
 ///
 /// See [https://www.math24.net/double-pendulum/](https://www.math24.net/double-pendulum/)
-pub struct Acrobot([f64; 4]);
+#[derive(Clone, Debug)]
+pub struct Acrobot([f64; 4], Option<StartDistribution>);
 
 impl Acrobot {
     pub fn new(theta1: f64, theta2: f64, dtheta1: f64, dtheta2: f64) -> Acrobot {
-        Acrobot([theta1, theta2, dtheta1, dtheta2])
+        Acrobot([theta1, theta2, dtheta1, dtheta2], None)
+    }
+
+    /// Return a new instance whose initial state is drawn from `start` on each
+    /// reset, rather than being fixed at rest.
+    ///
+    /// The distribution is over `[theta1, theta2, dtheta1, dtheta2]`.
+    ///
+    /// # Panics
+    /// If `start` is not 4-dimensional.
+    pub fn with_start_distribution(mut self, start: StartDistribution) -> Acrobot {
+        assert_eq!(start.dim(), 4, "Acrobot start distribution must be 4-dimensional.");
+
+        self.1 = Some(start);
+        self
     }
 
     fn is_terminal(theta1: f64, theta2: f64) -> bool {
@@ -127,6 +142,17 @@ impl Domain for Acrobot {
         }
     }
 
+    fn reset(&mut self) {
+        self.0 = match self.1 {
+            Some(ref mut start) => {
+                let s = start.sample();
+
+                [s[0], s[1], s[2], s[3]]
+            },
+            None => [0.0; 4],
+        };
+    }
+
     fn step(&mut self, action: &usize) -> (Observation<Vec<f64>>, Reward) {
         self.update_state(*action);
 
@@ -149,12 +175,23 @@ impl Domain for Acrobot {
     }
 
     fn action_space(&self) -> Ordinal { Ordinal::new(3) }
+
+    fn render(&self) -> Option<String> {
+        Some(format!(
+            "theta1: {:.4}, theta2: {:.4}, dtheta1: {:.4}, dtheta2: {:.4}",
+            self.0[StateIndex::THETA1],
+            self.0[StateIndex::THETA2],
+            self.0[StateIndex::DTHETA1],
+            self.0[StateIndex::DTHETA2],
+        ))
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::{Domain, Observation};
+    use rand::{rngs::StdRng, SeedableRng};
 
     #[test]
     fn test_initial_observation() {
@@ -172,4 +209,48 @@ mod tests {
 
         assert!(!m.emit().is_terminal());
     }
+
+    #[test]
+    fn test_reset() {
+        let mut m = Acrobot::new(PI, 0.0, 0.0, 0.0);
+        let (ns, _) = m.step(&1);
+
+        assert!(ns.is_terminal());
+
+        m.reset();
+
+        match m.emit() {
+            Observation::Full(ref state) => assert_eq!(state, &vec![0.0; 4]),
+            _ => panic!("Should yield a non-terminal initial state."),
+        }
+    }
+
+    #[test]
+    fn test_random_start() {
+        let low = vec![-0.1; 4];
+        let high = vec![0.1; 4];
+
+        let starts = |seed: u64| -> Vec<Vec<f64>> {
+            let rng = StdRng::seed_from_u64(seed);
+            let start = StartDistribution::new(low.clone(), high.clone(), rng);
+            let mut m = Acrobot::default().with_start_distribution(start);
+
+            (0..5)
+                .map(|_| {
+                    m.reset();
+                    m.emit().state().clone()
+                })
+                .collect()
+        };
+
+        let s1 = starts(0);
+
+        assert_eq!(s1, starts(0));
+        assert_ne!(s1, starts(1));
+
+        for (i, s) in s1.iter().enumerate() {
+            assert!(s.iter().all(|x| x.abs() <= 0.1));
+            assert!(s1[i + 1..].iter().all(|other| other != s));
+        }
+    }
 }
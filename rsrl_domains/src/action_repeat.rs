@@ -0,0 +1,122 @@
+use super::{Action, Domain, Observation, Reward, State};
+
+/// Wrapper that repeats each action a fixed number of times.
+///
+/// Every call to `step` applies the given action to the wrapped domain `k`
+/// times, returning the final observation along with the (undiscounted) sum of
+/// the rewards. Repetition stops early if a terminal state is reached. This
+/// reduces the number of decisions an agent must make per episode, which can
+/// make domains with fine-grained dynamics, such as `Acrobot`, much easier to
+/// learn.
+#[derive(Clone, Debug)]
+pub struct ActionRepeat<D> {
+    domain: D,
+    k: usize,
+}
+
+impl<D: Domain> ActionRepeat<D> {
+    /// Construct a wrapper that applies each action `k` times.
+    ///
+    /// # Panics
+    /// If `k` is zero.
+    pub fn new(domain: D, k: usize) -> ActionRepeat<D> {
+        assert!(k > 0, "ActionRepeat requires at least one repetition.");
+
+        ActionRepeat { domain, k }
+    }
+
+    /// Return the number of repetitions per step.
+    pub fn repetitions(&self) -> usize { self.k }
+
+    /// Return a reference to the wrapped domain.
+    pub fn inner(&self) -> &D { &self.domain }
+
+    /// Consume the wrapper and return the underlying domain.
+    pub fn into_inner(self) -> D { self.domain }
+}
+
+impl<D: Domain> Domain for ActionRepeat<D> {
+    type StateSpace = D::StateSpace;
+    type ActionSpace = D::ActionSpace;
+
+    fn emit(&self) -> Observation<State<D>> { self.domain.emit() }
+
+    fn reset(&mut self) { self.domain.reset() }
+
+    fn step(&mut self, action: &Action<Self>) -> (Observation<State<D>>, Reward) {
+        let (mut to, mut reward) = self.domain.step(action);
+
+        for _ in 1..self.k {
+            if to.is_terminal() {
+                break;
+            }
+
+            let (ns, r) = self.domain.step(action);
+
+            to = ns;
+            reward += r;
+        }
+
+        (to, reward)
+    }
+
+    fn state_space(&self) -> Self::StateSpace { self.domain.state_space() }
+
+    fn action_space(&self) -> Self::ActionSpace { self.domain.action_space() }
+
+    fn discount(&self) -> f64 { self.domain.discount() }
+
+    fn is_episodic(&self) -> bool { self.domain.is_episodic() }
+
+    fn render(&self) -> Option<String> { self.domain.render() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ActionRepeat;
+    use crate::{Acrobot, Domain, GridWorld};
+
+    #[test]
+    fn test_single_repeat() {
+        let mut base = Acrobot::default();
+        let mut wrapped = ActionRepeat::new(Acrobot::default(), 1);
+
+        for &a in [0, 2, 2, 1, 0].iter() {
+            let (ns, r) = base.step(&a);
+            let (ns_wrapped, r_wrapped) = wrapped.step(&a);
+
+            assert_eq!(ns.state(), ns_wrapped.state());
+            assert_eq!(r, r_wrapped);
+        }
+    }
+
+    #[test]
+    fn test_triple_repeat() {
+        let mut base = Acrobot::default();
+        let mut wrapped = ActionRepeat::new(Acrobot::default(), 3);
+
+        for &a in [2, 0].iter() {
+            let mut reward = 0.0;
+
+            for _ in 0..3 {
+                reward += base.step(&a).1;
+            }
+
+            let (ns, r) = wrapped.step(&a);
+
+            assert_eq!(ns.state(), base.emit().state());
+            assert_eq!(r, reward);
+        }
+    }
+
+    #[test]
+    fn test_early_termination() {
+        let mut wrapped = ActionRepeat::new(GridWorld::new(3, 1, [0, 0], &[[2, 0]], 5.0), 4);
+
+        let (ns, r) = wrapped.step(&3);
+
+        assert!(ns.is_terminal());
+        assert_eq!(*ns.state(), 2);
+        assert_eq!(r, -1.0 + 5.0);
+    }
+}
@@ -0,0 +1,235 @@
+use crate::{spaces::discrete::Ordinal, Domain, Observation, Reward};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::f64::consts::PI;
+
+/// Reward distribution of a single bandit arm.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Arm {
+    /// Reward of 1 with probability `p`, and 0 otherwise.
+    Bernoulli(f64),
+
+    /// Normally distributed reward with the given mean and standard deviation.
+    Gaussian { mean: f64, std: f64 },
+}
+
+impl Arm {
+    /// Return the expected reward of pulling the arm.
+    pub fn mean(&self) -> f64 {
+        match *self {
+            Arm::Bernoulli(p) => p,
+            Arm::Gaussian { mean, .. } => mean,
+        }
+    }
+
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> f64 {
+        match *self {
+            Arm::Bernoulli(p) => {
+                if rng.gen_bool(p) {
+                    1.0
+                } else {
+                    0.0
+                }
+            },
+            Arm::Gaussian { mean, std } => {
+                // Box-Muller transform; `u1` is drawn from (0, 1] to keep the
+                // logarithm finite.
+                let u1 = 1.0 - rng.gen::<f64>();
+                let u2 = rng.gen::<f64>();
+
+                mean + std * (-2.0 * u1.ln()).sqrt() * (2.0 * PI * u2).cos()
+            },
+        }
+    }
+}
+
+/// Multi-armed bandit with configurable reward distributions.
+///
+/// The domain has a single state, and each action pulls the corresponding
+/// arm, yielding a reward drawn independently from that arm's distribution.
+/// By default the task is continuing and never terminates; with
+/// [`Bandit::with_horizon`] it instead terminates after a fixed number of
+/// pulls.
+///
+/// # Technical details
+/// The **state** is always 0.
+///
+/// The **action** is the index of the arm to pull.
+#[derive(Clone, Debug)]
+pub struct Bandit {
+    arms: Vec<Arm>,
+    horizon: Option<usize>,
+
+    n_pulls: usize,
+
+    rng: StdRng,
+}
+
+impl Bandit {
+    /// Construct a new instance with the given arms.
+    ///
+    /// # Panics
+    /// If there are no arms, if a Bernoulli arm has a probability outside of
+    /// `[0, 1]`, or if a Gaussian arm has a negative standard deviation.
+    pub fn new(arms: Vec<Arm>) -> Bandit { Bandit::with_rng(arms, StdRng::from_entropy()) }
+
+    /// Construct a new instance which draws rewards from the given `rng`.
+    pub fn with_rng(arms: Vec<Arm>, rng: StdRng) -> Bandit {
+        assert!(!arms.is_empty(), "Bandit requires at least one arm.");
+
+        for arm in arms.iter() {
+            match *arm {
+                Arm::Bernoulli(p) => assert!(
+                    (0.0..=1.0).contains(&p),
+                    "Bernoulli arm probability must lie in [0, 1], got {}.",
+                    p
+                ),
+                Arm::Gaussian { std, .. } => assert!(
+                    std >= 0.0,
+                    "Gaussian arm standard deviation must be non-negative, got {}.",
+                    std
+                ),
+            }
+        }
+
+        Bandit {
+            arms,
+            horizon: None,
+
+            n_pulls: 0,
+
+            rng,
+        }
+    }
+
+    /// Return a new instance in which each episode terminates after `horizon`
+    /// pulls.
+    pub fn with_horizon(mut self, horizon: usize) -> Bandit {
+        self.horizon = Some(horizon);
+        self
+    }
+
+    /// Return the arms of the bandit.
+    pub fn arms(&self) -> &[Arm] { &self.arms }
+
+    /// Return the number of arms.
+    pub fn n_arms(&self) -> usize { self.arms.len() }
+
+    /// Return the index of the arm with the highest expected reward; ties are
+    /// broken in favour of the lowest index.
+    pub fn optimal_arm(&self) -> usize {
+        (1..self.arms.len()).fold(0, |best, i| {
+            if self.arms[i].mean() > self.arms[best].mean() {
+                i
+            } else {
+                best
+            }
+        })
+    }
+}
+
+impl Domain for Bandit {
+    type StateSpace = Ordinal;
+    type ActionSpace = Ordinal;
+
+    fn emit(&self) -> Observation<usize> {
+        match self.horizon {
+            Some(h) if self.n_pulls >= h => Observation::Terminal(0),
+            _ => Observation::Full(0),
+        }
+    }
+
+    fn reset(&mut self) { self.n_pulls = 0; }
+
+    fn step(&mut self, action: &usize) -> (Observation<usize>, Reward) {
+        let reward = self.arms[*action].sample(&mut self.rng);
+
+        self.n_pulls += 1;
+
+        (self.emit(), reward)
+    }
+
+    fn state_space(&self) -> Ordinal { Ordinal::new(1) }
+
+    fn action_space(&self) -> Ordinal { Ordinal::new(self.arms.len()) }
+
+    fn is_episodic(&self) -> bool { self.horizon.is_some() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Arm, Bandit};
+    use crate::{spaces::discrete::Ordinal, Domain};
+    use rand::{rngs::StdRng, SeedableRng};
+
+    fn bandit() -> Bandit {
+        Bandit::with_rng(
+            vec![
+                Arm::Bernoulli(0.2),
+                Arm::Gaussian {
+                    mean: -1.0,
+                    std: 2.0,
+                },
+                Arm::Bernoulli(0.9),
+                Arm::Gaussian {
+                    mean: 0.5,
+                    std: 0.1,
+                },
+            ],
+            StdRng::seed_from_u64(0),
+        )
+    }
+
+    #[test]
+    fn test_spaces() {
+        let domain = bandit();
+
+        assert_eq!(domain.n_arms(), 4);
+        assert_eq!(domain.state_space(), Ordinal::new(1));
+        assert_eq!(domain.action_space(), Ordinal::new(4));
+        assert_eq!(domain.optimal_arm(), 2);
+        assert!(!domain.is_episodic());
+    }
+
+    #[test]
+    fn test_empirical_means() {
+        let mut domain = bandit();
+        let n = 20000;
+
+        for a in 0..domain.n_arms() {
+            let rewards: Vec<f64> = (0..n).map(|_| domain.step(&a).1).collect();
+            let mean = rewards.iter().sum::<f64>() / n as f64;
+
+            assert!(
+                (mean - domain.arms()[a].mean()).abs() < 0.05,
+                "Arm {} has empirical mean {}.",
+                a,
+                mean
+            );
+
+            if let Arm::Bernoulli(_) = domain.arms()[a] {
+                assert!(rewards.iter().all(|&r| r == 0.0 || r == 1.0));
+            }
+        }
+
+        // Without a horizon the domain never terminates.
+        assert!(!domain.emit().is_terminal());
+    }
+
+    #[test]
+    fn test_horizon() {
+        let mut domain = bandit().with_horizon(3);
+
+        assert!(domain.is_episodic());
+        assert!(!domain.step(&0).0.is_terminal());
+        assert!(!domain.step(&1).0.is_terminal());
+        assert!(domain.step(&2).0.is_terminal());
+
+        domain.reset();
+
+        assert!(!domain.emit().is_terminal());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_invalid_probability() { Bandit::new(vec![Arm::Bernoulli(1.5)]); }
+}
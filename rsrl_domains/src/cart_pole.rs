@@ -20,8 +20,8 @@ const LIMITS_DX: [f64; 2] = [-6.0, 6.0];
 const LIMITS_THETA: [f64; 2] = [-TWELVE_DEGREES, TWELVE_DEGREES];
 const LIMITS_DTHETA: [f64; 2] = [-2.0, 2.0];
 
-const REWARD_STEP: f64 = 0.0;
-const REWARD_TERMINAL: f64 = -1.0;
+const REWARD_STEP: f64 = 1.0;
+const REWARD_TERMINAL: f64 = 0.0;
 
 const ALL_ACTIONS: [f64; 2] = [-1.0 * CART_FORCE, 1.0 * CART_FORCE];
 
@@ -29,6 +29,29 @@ make_index!(StateIndex [
     X => 0, DX => 1, THETA => 2, DTHETA => 3
 ]);
 
+/// Classic cart-pole balancing domain.
+///
+/// A pole is attached by an un-actuated joint to a cart which moves along a
+/// frictionless track. The agent controls the cart by applying a force of +10
+/// or -10 and must keep the pole upright for as long as possible. The episode
+/// terminates when the pole falls more than 12 degrees from vertical, or the
+/// cart leaves the track; a reward of +1 is given for every step survived.
+///
+/// # Technical details
+/// The **state** is represented by a `Vec` with components:
+///
+/// | Index | Name                  | Min    | Max   |
+/// | ----- | --------------------- | ------ | ----- |
+/// | 0     | Cart position         | -2.4   | 2.4   |
+/// | 1     | Cart velocity         | -6.0   | 6.0   |
+/// | 2     | Pole angle            | -12deg | 12deg |
+/// | 3     | Pole angular velocity | -2.0   | 2.0   |
+///
+/// # References
+/// - Barto, A. G., Sutton, R. S., & Anderson, C. W. (1983). Neuronlike adaptive
+///   elements that can solve difficult learning control problems. IEEE
+///   Transactions on Systems, Man, and Cybernetics, (5), 834-846.
+#[derive(Clone, Debug)]
 pub struct CartPole([f64; 4]);
 
 impl CartPole {
@@ -96,6 +119,8 @@ impl Domain for CartPole {
         }
     }
 
+    fn reset(&mut self) { *self = Self::default(); }
+
     fn step(&mut self, action: &usize) -> (Observation<Vec<f64>>, Reward) {
         self.update_state(*action);
 
@@ -140,6 +165,30 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_initial_non_terminal() {
+        let m = CartPole::default();
+
+        assert!(!m.emit().is_terminal());
+        assert_eq!(m.action_space(), Ordinal::new(2));
+    }
+
+    #[test]
+    fn test_survival_reward() {
+        let mut m = CartPole::default();
+
+        let (ns, r) = m.step(&0);
+
+        assert!(!ns.is_terminal());
+        assert_eq!(r, 1.0);
+
+        let mut m = CartPole::new(2.4, 0.0, 0.0, 0.0);
+        let (ns, r) = m.step(&1);
+
+        assert!(ns.is_terminal());
+        assert_eq!(r, 0.0);
+    }
+
     #[test]
     fn test_step_0() {
         let mut m = CartPole::default();
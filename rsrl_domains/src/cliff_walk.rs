@@ -1,5 +1,5 @@
 use super::{
-    grid_world::{GridWorld, Motion},
+    grid_world::{Grid, Motion},
     Domain,
     Observation,
     Reward,
@@ -14,8 +14,9 @@ const ALL_ACTIONS: [Motion; 4] = [
     Motion::West(1),
 ];
 
+#[derive(Clone, Debug)]
 pub struct CliffWalk {
-    gw: GridWorld<()>,
+    gw: Grid<()>,
     loc: [usize; 2],
 }
 
@@ -24,7 +25,7 @@ impl CliffWalk {
         let gw = Array2::from_elem((height, width), ());
 
         CliffWalk {
-            gw: GridWorld::new(gw),
+            gw: Grid::new(gw),
             loc: [0; 2],
         }
     }
@@ -46,6 +47,8 @@ impl Domain for CliffWalk {
         }
     }
 
+    fn reset(&mut self) { self.loc = [0; 2]; }
+
     fn step(&mut self, action: &usize) -> (Observation<[usize; 2]>, Reward) {
         self.loc = self.gw.perform_motion(self.loc, ALL_ACTIONS[*action]);
 
@@ -0,0 +1,108 @@
+use super::{Action, Domain, Observation, Reward, State};
+
+/// Wrapper that clips the reward of each step to a fixed range.
+///
+/// Clipping rewards (typically to `[-1, 1]`) bounds the scale of the targets
+/// seen by an agent, which allows the same hyperparameters to be used across
+/// domains with very different reward magnitudes. Note that this changes the
+/// objective being optimised: only the sign, and not the magnitude, of
+/// rewards beyond the range is preserved.
+#[derive(Clone, Debug)]
+pub struct ClipReward<D> {
+    domain: D,
+
+    min: Reward,
+    max: Reward,
+}
+
+impl<D: Domain> ClipReward<D> {
+    /// Construct a wrapper that clips rewards to `[min, max]`.
+    ///
+    /// # Panics
+    /// If `min` is greater than `max`.
+    pub fn new(domain: D, min: Reward, max: Reward) -> ClipReward<D> {
+        assert!(min <= max, "ClipReward requires min <= max, got [{}, {}].", min, max);
+
+        ClipReward { domain, min, max }
+    }
+
+    /// Construct a wrapper that clips rewards to `[-1, 1]`.
+    pub fn unit(domain: D) -> ClipReward<D> { ClipReward::new(domain, -1.0, 1.0) }
+
+    /// Return the `(min, max)` range to which rewards are clipped.
+    pub fn range(&self) -> (Reward, Reward) { (self.min, self.max) }
+
+    /// Return a reference to the wrapped domain.
+    pub fn inner(&self) -> &D { &self.domain }
+
+    /// Consume the wrapper and return the underlying domain.
+    pub fn into_inner(self) -> D { self.domain }
+}
+
+impl<D: Domain> Domain for ClipReward<D> {
+    type StateSpace = D::StateSpace;
+    type ActionSpace = D::ActionSpace;
+
+    fn emit(&self) -> Observation<State<D>> { self.domain.emit() }
+
+    fn reset(&mut self) { self.domain.reset() }
+
+    fn step(&mut self, action: &Action<Self>) -> (Observation<State<D>>, Reward) {
+        let (to, reward) = self.domain.step(action);
+
+        (to, clip!(self.min, reward, self.max))
+    }
+
+    fn state_space(&self) -> Self::StateSpace { self.domain.state_space() }
+
+    fn action_space(&self) -> Self::ActionSpace { self.domain.action_space() }
+
+    fn discount(&self) -> f64 { self.domain.discount() }
+
+    fn is_episodic(&self) -> bool { self.domain.is_episodic() }
+
+    fn render(&self) -> Option<String> { self.domain.render() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ClipReward;
+    use crate::{Domain, GridWorld};
+
+    #[test]
+    fn test_clipping() {
+        let corridor = GridWorld::new(3, 1, [0, 0], &[[2, 0]], 10.0);
+        let mut domain = ClipReward::new(corridor, -0.5, 1.0);
+
+        // Step penalty of -1 is clipped to -0.5.
+        let t = domain.transition(3);
+
+        assert_eq!(t.reward, -0.5);
+        assert!(!t.terminated());
+
+        // Goal reward of 10 is clipped to 1, and the goal is still terminal.
+        let t = domain.transition(3);
+
+        assert_eq!(t.reward, 1.0);
+        assert!(t.terminated());
+    }
+
+    #[test]
+    fn test_pass_through() {
+        let corridor = GridWorld::new(2, 1, [0, 0], &[[1, 0]], 0.5);
+        let mut domain = ClipReward::unit(corridor);
+
+        assert_eq!(domain.range(), (-1.0, 1.0));
+
+        // Bumping into the wall incurs the unclipped step penalty.
+        let t = domain.transition(2);
+
+        assert_eq!(t.reward, -1.0);
+        assert!(!t.terminated());
+
+        let t = domain.transition(3);
+
+        assert_eq!(t.reward, 0.5);
+        assert!(t.terminated());
+    }
+}
@@ -0,0 +1,158 @@
+use super::{Action, Domain, Observation, Reward};
+use crate::spaces::{discrete::Ordinal, real::Interval, BoundedSpace, ProductSpace};
+
+/// Wrapper that discretises the state of a bounded domain onto a finite grid.
+///
+/// Each component of the wrapped domain's state is binned into one of a fixed
+/// number of equal-width buckets spanning the bounds of the corresponding
+/// dimension of its `ProductSpace<Interval>`, and the resulting multi-index is
+/// flattened (in row-major order) into a single `Ordinal` state. This allows
+/// tabular agents to be applied directly to continuous domains such as
+/// `MountainCar` and `Acrobot`. Values outside the original bounds are clamped
+/// into the end buckets.
+#[derive(Clone, Debug)]
+pub struct Discretize<D> {
+    domain: D,
+
+    bounds: Vec<(f64, f64)>,
+    buckets: Vec<usize>,
+}
+
+impl<D: Domain<StateSpace = ProductSpace<Interval>>> Discretize<D> {
+    /// Construct a wrapper using the given number of buckets per dimension.
+    ///
+    /// # Panics
+    /// If any dimension of the domain's state space is unbounded, if the
+    /// number of dimensions does not match `buckets.len()`, or if any entry
+    /// of `buckets` is zero.
+    pub fn new(domain: D, buckets: &[usize]) -> Discretize<D> {
+        let bounds: Vec<(f64, f64)> = domain
+            .state_space()
+            .iter()
+            .map(|d| match (d.inf(), d.sup()) {
+                (Some(lb), Some(ub)) => (lb, ub),
+                _ => panic!("Discretize requires a bounded state space, got {:?}.", d),
+            })
+            .collect();
+
+        assert_eq!(
+            bounds.len(),
+            buckets.len(),
+            "Discretize requires one bucket count per state dimension."
+        );
+        assert!(buckets.iter().all(|&n| n > 0), "Discretize requires non-zero bucket counts.");
+
+        Discretize {
+            domain,
+
+            bounds,
+            buckets: buckets.to_vec(),
+        }
+    }
+
+    /// Construct a wrapper using `n` buckets for every dimension.
+    ///
+    /// # Panics
+    /// If any dimension of the domain's state space is unbounded, or if `n`
+    /// is zero.
+    pub fn uniform(domain: D, n: usize) -> Discretize<D> {
+        let n_dims = domain.state_space().iter().len();
+
+        Discretize::new(domain, &vec![n; n_dims])
+    }
+
+    /// Return the number of buckets along each dimension.
+    pub fn buckets(&self) -> &[usize] { &self.buckets }
+
+    /// Return a reference to the wrapped domain.
+    pub fn inner(&self) -> &D { &self.domain }
+
+    /// Consume the wrapper and return the underlying domain.
+    pub fn into_inner(self) -> D { self.domain }
+
+    /// Return the index of the bucket containing value `x` along dimension
+    /// `dim`.
+    pub fn bucket(&self, dim: usize, x: f64) -> usize {
+        let (lb, ub) = self.bounds[dim];
+        let n = self.buckets[dim];
+        let i = ((x - lb) / (ub - lb) * n as f64).floor();
+
+        if i <= 0.0 {
+            0
+        } else {
+            (i as usize).min(n - 1)
+        }
+    }
+
+    /// Map a raw state of the wrapped domain onto its ordinal index.
+    pub fn discretise(&self, state: &[f64]) -> usize {
+        state
+            .iter()
+            .enumerate()
+            .fold(0, |acc, (d, &x)| acc * self.buckets[d] + self.bucket(d, x))
+    }
+}
+
+impl<D: Domain<StateSpace = ProductSpace<Interval>>> Domain for Discretize<D> {
+    type StateSpace = Ordinal;
+    type ActionSpace = D::ActionSpace;
+
+    fn emit(&self) -> Observation<usize> { self.domain.emit().map(|s| self.discretise(s)) }
+
+    fn reset(&mut self) { self.domain.reset() }
+
+    fn step(&mut self, action: &Action<Self>) -> (Observation<usize>, Reward) {
+        let (to, reward) = self.domain.step(action);
+
+        (to.map(|s| self.discretise(s)), reward)
+    }
+
+    fn state_space(&self) -> Ordinal { Ordinal::new(self.buckets.iter().product()) }
+
+    fn action_space(&self) -> Self::ActionSpace { self.domain.action_space() }
+
+    fn discount(&self) -> f64 { self.domain.discount() }
+
+    fn is_episodic(&self) -> bool { self.domain.is_episodic() }
+
+    fn render(&self) -> Option<String> { self.domain.render() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Discretize;
+    use crate::{spaces::discrete::Ordinal, Domain, MountainCar};
+
+    #[test]
+    fn test_buckets() {
+        // Position spans [-1.2, 0.6] and velocity [-0.07, 0.07].
+        let domain = Discretize::new(MountainCar::default(), &[9, 4]);
+
+        assert_eq!(domain.state_space(), Ordinal::new(36));
+
+        // Bucket width of 0.2 in position: floor((-0.5 + 1.2) / 0.2) = 3.
+        assert_eq!(domain.bucket(0, -0.5), 3);
+        assert_eq!(domain.bucket(1, 0.01), 2);
+        assert_eq!(domain.discretise(&[-0.5, 0.01]), 3 * 4 + 2);
+
+        // Bounds land in the end buckets, as do out-of-range values.
+        assert_eq!(domain.bucket(0, -1.2), 0);
+        assert_eq!(domain.bucket(0, 0.6), 8);
+        assert_eq!(domain.bucket(1, -1.0), 0);
+        assert_eq!(domain.bucket(1, 1.0), 3);
+        assert_eq!(domain.discretise(&[0.6, 0.07]), 35);
+    }
+
+    #[test]
+    fn test_observations() {
+        let mut domain = Discretize::uniform(MountainCar::default(), 10);
+        let s = domain.inner().emit().state().clone();
+
+        assert_eq!(*domain.emit().state(), domain.discretise(&s));
+
+        let t = domain.transition(2);
+
+        assert!(*t.to.state() < 100);
+        assert_eq!(*t.to.state(), domain.discretise(domain.inner().emit().state()));
+    }
+}
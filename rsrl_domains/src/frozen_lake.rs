@@ -0,0 +1,243 @@
+use crate::{spaces::discrete::Ordinal, Domain, Observation, Reward};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+const REWARD_GOAL: f64 = 1.0;
+
+const DEFAULT_MAP: &str = "SFFF\nFHFH\nFFFH\nHFFG";
+const DEFAULT_SLIP: f64 = 2.0 / 3.0;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Tile {
+    Start,
+    Frozen,
+    Hole,
+    Goal,
+}
+
+/// Stochastic grid world in which the agent slides across a frozen lake.
+///
+/// The lake is described by a map with one row per line, using the characters
+/// `S` (start), `F` (frozen), `H` (hole) and `G` (goal). Both holes and goals
+/// are terminal; entering a goal yields a reward of 1, and every other
+/// transition a reward of 0. The ice is slippery: with probability `1 - slip`
+/// the chosen action is carried out, and otherwise the agent moves in one of
+/// the two perpendicular directions, chosen uniformly at random. Moving off
+/// the edge of the lake leaves the agent where it is.
+///
+/// # Technical details
+/// The **state** is the index of the occupied tile, `row * width + col`,
+/// where row 0 is the first line of the map.
+///
+/// The **actions** are given by:
+///
+/// | Index | Motion |
+/// | ----- | ------ |
+/// | 0     | Up     |
+/// | 1     | Down   |
+/// | 2     | Left   |
+/// | 3     | Right  |
+#[derive(Clone, Debug)]
+pub struct FrozenLake {
+    width: usize,
+    height: usize,
+    tiles: Vec<Tile>,
+
+    slip: f64,
+
+    start: usize,
+    loc: usize,
+
+    rng: StdRng,
+}
+
+impl FrozenLake {
+    /// Construct a new instance from a map and slip probability.
+    ///
+    /// # Panics
+    /// If the map is not rectangular, contains an unknown character or does
+    /// not have exactly one start tile, or if `slip` is not in `[0, 1]`.
+    pub fn new(map: &str, slip: f64) -> FrozenLake {
+        FrozenLake::with_rng(map, slip, StdRng::from_entropy())
+    }
+
+    /// Construct a new instance which draws transitions from the given `rng`.
+    pub fn with_rng(map: &str, slip: f64, rng: StdRng) -> FrozenLake {
+        assert!(
+            (0.0..=1.0).contains(&slip),
+            "Slip probability must lie in [0, 1], got {}.",
+            slip
+        );
+
+        let rows: Vec<&str> = map.lines().map(str::trim).filter(|l| !l.is_empty()).collect();
+        let width = rows.first().map_or(0, |r| r.len());
+
+        assert!(width > 0, "FrozenLake requires a non-empty map.");
+        assert!(rows.iter().all(|r| r.len() == width), "FrozenLake map must be rectangular.");
+
+        let tiles: Vec<Tile> = rows
+            .iter()
+            .flat_map(|r| r.chars())
+            .map(|c| match c {
+                'S' => Tile::Start,
+                'F' => Tile::Frozen,
+                'H' => Tile::Hole,
+                'G' => Tile::Goal,
+                _ => panic!("Unknown FrozenLake tile {:?}.", c),
+            })
+            .collect();
+
+        let starts: Vec<usize> = (0..tiles.len()).filter(|&i| tiles[i] == Tile::Start).collect();
+
+        assert!(starts.len() == 1, "FrozenLake map must have exactly one start tile.");
+
+        FrozenLake {
+            width,
+            height: rows.len(),
+            tiles,
+
+            slip,
+
+            start: starts[0],
+            loc: starts[0],
+
+            rng,
+        }
+    }
+
+    pub fn width(&self) -> usize { self.width }
+
+    pub fn height(&self) -> usize { self.height }
+
+    pub fn slip(&self) -> f64 { self.slip }
+
+    pub fn is_hole(&self, s: usize) -> bool { self.tiles[s] == Tile::Hole }
+
+    pub fn is_goal(&self, s: usize) -> bool { self.tiles[s] == Tile::Goal }
+
+    fn perpendicular(action: usize) -> [usize; 2] {
+        match action {
+            0 | 1 => [2, 3],
+            2 | 3 => [0, 1],
+            _ => panic!("Unknown action {}!", action),
+        }
+    }
+
+    fn move_from(&self, s: usize, action: usize) -> usize {
+        let (row, col) = (s / self.width, s % self.width);
+
+        match action {
+            0 if row > 0 => s - self.width,
+            1 if row + 1 < self.height => s + self.width,
+            2 if col > 0 => s - 1,
+            3 if col + 1 < self.width => s + 1,
+            _ => s,
+        }
+    }
+
+    fn update_state(&mut self, action: usize) {
+        let [p1, p2] = Self::perpendicular(action);
+        let motion = if self.rng.gen_bool(self.slip) {
+            if self.rng.gen_bool(0.5) { p1 } else { p2 }
+        } else {
+            action
+        };
+
+        self.loc = self.move_from(self.loc, motion);
+    }
+}
+
+impl Default for FrozenLake {
+    fn default() -> FrozenLake { FrozenLake::new(DEFAULT_MAP, DEFAULT_SLIP) }
+}
+
+impl Domain for FrozenLake {
+    type StateSpace = Ordinal;
+    type ActionSpace = Ordinal;
+
+    fn emit(&self) -> Observation<usize> {
+        match self.tiles[self.loc] {
+            Tile::Hole | Tile::Goal => Observation::Terminal(self.loc),
+            _ => Observation::Full(self.loc),
+        }
+    }
+
+    fn reset(&mut self) { self.loc = self.start; }
+
+    fn step(&mut self, action: &usize) -> (Observation<usize>, Reward) {
+        self.update_state(*action);
+
+        let reward = if self.is_goal(self.loc) { REWARD_GOAL } else { 0.0 };
+
+        (self.emit(), reward)
+    }
+
+    fn state_space(&self) -> Self::StateSpace { Ordinal::new(self.width * self.height) }
+
+    fn action_space(&self) -> Ordinal { Ordinal::new(4) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FrozenLake;
+    use crate::{spaces::discrete::Ordinal, Domain};
+    use rand::{rngs::StdRng, SeedableRng};
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_default_map() {
+        let fl = FrozenLake::default();
+
+        assert_eq!(fl.state_space(), Ordinal::new(16));
+        assert_eq!(fl.action_space(), Ordinal::new(4));
+        assert_eq!(*fl.emit().state(), 0);
+
+        assert!(fl.is_hole(5) && fl.is_hole(7) && fl.is_hole(11) && fl.is_hole(12));
+        assert!(fl.is_goal(15));
+    }
+
+    #[test]
+    fn test_deterministic_without_slip() {
+        let mut fl = FrozenLake::new("SFFF\nFHFH\nFFFH\nHFFG", 0.0);
+
+        let (ns, r) = fl.step(&0);
+        assert_eq!(*ns.state(), 0);
+        assert_eq!(r, 0.0);
+
+        assert_eq!(*fl.step(&3).0.state(), 1);
+        assert_eq!(*fl.step(&3).0.state(), 2);
+        assert_eq!(*fl.step(&1).0.state(), 6);
+        assert_eq!(*fl.step(&1).0.state(), 10);
+        assert_eq!(*fl.step(&1).0.state(), 14);
+
+        let (ns, r) = fl.step(&3);
+
+        assert!(ns.is_terminal());
+        assert_eq!(*ns.state(), 15);
+        assert_eq!(r, 1.0);
+
+        fl.reset();
+
+        assert_eq!(*fl.step(&1).0.state(), 4);
+
+        let (ns, r) = fl.step(&3);
+
+        assert!(ns.is_terminal());
+        assert_eq!(*ns.state(), 5);
+        assert_eq!(r, 0.0);
+    }
+
+    #[test]
+    fn test_slippery_outcomes_vary() {
+        let outcomes: HashSet<usize> = (0..50)
+            .map(|seed| {
+                let rng = StdRng::seed_from_u64(seed);
+                let mut fl = FrozenLake::with_rng("FFF\nFSF\nFFF", 0.5, rng);
+
+                *fl.step(&0).0.state()
+            })
+            .collect();
+
+        // Up, or slipping left/right; never down.
+        assert_eq!(outcomes, [1, 3, 5].iter().cloned().collect());
+    }
+}
@@ -1,4 +1,11 @@
 #![allow(dead_code)]
+use crate::{
+    spaces::discrete::Ordinal,
+    Domain,
+    Model,
+    Observation,
+    Reward,
+};
 use ndarray::Array2;
 use std::{
     cmp,
@@ -33,14 +40,15 @@ impl Motion {
     }
 }
 
-pub struct GridWorld<T> {
+#[derive(Clone, Debug)]
+pub struct Grid<T> {
     layout: Array2<T>,
 }
 
-impl<T> GridWorld<T> {
-    pub fn new(layout: Array2<T>) -> GridWorld<T> { GridWorld { layout } }
+impl<T> Grid<T> {
+    pub fn new(layout: Array2<T>) -> Grid<T> { Grid { layout } }
 
-    pub fn from_str(layout: &str) -> GridWorld<T>
+    pub fn from_str(layout: &str) -> Grid<T>
     where
         T: FromStr,
         T::Err: Debug,
@@ -57,12 +65,12 @@ impl<T> GridWorld<T> {
         let shape = (m.len(), m[0].len());
         let mvals = m.into_iter().flat_map(|v| v).collect();
 
-        GridWorld {
+        Grid {
             layout: Array2::from_shape_vec(shape, mvals).unwrap(),
         }
     }
 
-    pub fn from_file(path: &str) -> Result<GridWorld<T>, IOError>
+    pub fn from_file(path: &str) -> Result<Grid<T>, IOError>
     where
         T: FromStr,
         T::Err: Debug,
@@ -71,7 +79,7 @@ impl<T> GridWorld<T> {
         let mut buffer = String::new();
 
         match f.read_to_string(&mut buffer) {
-            Ok(_) => Ok(GridWorld::<T>::from_str(&buffer)),
+            Ok(_) => Ok(Grid::<T>::from_str(&buffer)),
             Err(e) => Err(e),
         }
     }
@@ -170,16 +178,223 @@ impl<T> GridWorld<T> {
     }
 }
 
+const REWARD_STEP: f64 = -1.0;
+
+/// Tabular grid world domain.
+///
+/// The agent moves between the cells of a `width` x `height` grid, starting
+/// from a fixed cell, until it reaches one of the goal cells. Movement into a
+/// wall cell, or off the edge of the grid, leaves the agent where it is. Each
+/// step incurs a reward of -1, with the exception of the step that enters a
+/// goal, which yields the (configurable) goal reward.
+///
+/// # Technical details
+/// The **state** is the index of the occupied cell, `y * width + x`, where
+/// `[x, y]` is the cell location with `[0, 0]` in the bottom-left corner.
+///
+/// The **actions** are given by:
+///
+/// | Index | Motion |
+/// | ----- | ------ |
+/// | 0     | Up     |
+/// | 1     | Down   |
+/// | 2     | Left   |
+/// | 3     | Right  |
+#[derive(Clone, Debug)]
+pub struct GridWorld {
+    width: usize,
+    height: usize,
+
+    walls: Vec<bool>,
+    goals: Vec<bool>,
+    goal_reward: f64,
+
+    start: [usize; 2],
+    loc: [usize; 2],
+}
+
+impl GridWorld {
+    pub fn new(
+        width: usize,
+        height: usize,
+        start: [usize; 2],
+        goals: &[[usize; 2]],
+        goal_reward: f64,
+    ) -> GridWorld {
+        let mut gw = GridWorld {
+            width,
+            height,
+
+            walls: vec![false; width * height],
+            goals: vec![false; width * height],
+            goal_reward,
+
+            start,
+            loc: start,
+        };
+
+        for &goal in goals {
+            let i = gw.index(goal);
+
+            gw.goals[i] = true;
+        }
+
+        gw
+    }
+
+    /// Return a new instance with the given cells marked as walls.
+    pub fn with_walls(mut self, walls: &[[usize; 2]]) -> GridWorld {
+        for &wall in walls {
+            let i = self.index(wall);
+
+            self.walls[i] = true;
+        }
+
+        self
+    }
+
+    pub fn width(&self) -> usize { self.width }
+
+    pub fn height(&self) -> usize { self.height }
+
+    pub fn location(&self) -> [usize; 2] { self.loc }
+
+    pub fn is_wall(&self, loc: [usize; 2]) -> bool { self.walls[self.index(loc)] }
+
+    pub fn is_goal(&self, loc: [usize; 2]) -> bool { self.goals[self.index(loc)] }
+
+    fn index(&self, loc: [usize; 2]) -> usize {
+        assert!(
+            loc[0] < self.width && loc[1] < self.height,
+            "Cell {:?} lies outside the grid.",
+            loc
+        );
+
+        loc[1] * self.width + loc[0]
+    }
+
+    fn location_of(&self, s: usize) -> [usize; 2] { [s % self.width, s / self.width] }
+
+    fn next_location(&self, loc: [usize; 2], action: usize) -> [usize; 2] {
+        let [x, y] = loc;
+        let nloc = match action {
+            0 if y + 1 < self.height => [x, y + 1],
+            1 if y > 0 => [x, y - 1],
+            2 if x > 0 => [x - 1, y],
+            3 if x + 1 < self.width => [x + 1, y],
+            0..=3 => loc,
+            _ => panic!("Unknown action {}!", action),
+        };
+
+        if self.is_wall(nloc) { loc } else { nloc }
+    }
+
+    fn observe(&self, loc: [usize; 2]) -> Observation<usize> {
+        let s = self.index(loc);
+
+        if self.goals[s] {
+            Observation::Terminal(s)
+        } else {
+            Observation::Full(s)
+        }
+    }
+
+    fn update_state(&mut self, action: usize) { self.loc = self.next_location(self.loc, action); }
+}
+
+impl Default for GridWorld {
+    fn default() -> GridWorld { GridWorld::new(5, 5, [0, 0], &[[4, 4]], 0.0) }
+}
+
+impl Domain for GridWorld {
+    type StateSpace = Ordinal;
+    type ActionSpace = Ordinal;
+
+    fn emit(&self) -> Observation<usize> { self.observe(self.loc) }
+
+    fn reset(&mut self) { self.loc = self.start; }
+
+    fn step(&mut self, action: &usize) -> (Observation<usize>, Reward) {
+        self.update_state(*action);
+
+        let to = self.emit();
+        let reward = if to.is_terminal() {
+            self.goal_reward
+        } else {
+            REWARD_STEP
+        };
+
+        (to, reward)
+    }
+
+    fn state_space(&self) -> Self::StateSpace { Ordinal::new(self.width * self.height) }
+
+    fn action_space(&self) -> Ordinal { Ordinal::new(4) }
+
+    /// Render the grid as text, one line per row with the top row first.
+    ///
+    /// The agent is drawn as `A`, goals as `G`, walls as `#` and empty cells
+    /// as `.`; an agent standing on a goal is drawn as `A`.
+    fn render(&self) -> Option<String> {
+        let rows: Vec<String> = (0..self.height)
+            .rev()
+            .map(|y| {
+                (0..self.width)
+                    .map(|x| {
+                        let loc = [x, y];
+
+                        if loc == self.loc {
+                            'A'
+                        } else if self.is_goal(loc) {
+                            'G'
+                        } else if self.is_wall(loc) {
+                            '#'
+                        } else {
+                            '.'
+                        }
+                    })
+                    .collect()
+            })
+            .collect();
+
+        Some(rows.join("\n"))
+    }
+}
+
+impl Model for GridWorld {
+    /// Predict the outcome of taking `action` in state `s`.
+    ///
+    /// Goal states are absorbing: acting in one leaves the agent in place with
+    /// zero reward.
+    fn predict(&self, s: &usize, action: &usize) -> (Observation<usize>, Reward) {
+        let loc = self.location_of(*s);
+
+        if self.is_goal(loc) {
+            return (Observation::Terminal(*s), 0.0);
+        }
+
+        let to = self.observe(self.next_location(loc, *action));
+        let reward = if to.is_terminal() {
+            self.goal_reward
+        } else {
+            REWARD_STEP
+        };
+
+        (to, reward)
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{GridWorld, Motion};
+    use super::{Grid, GridWorld, Motion};
+    use crate::{spaces::discrete::Ordinal, Domain, Model};
 
     #[test]
     fn test_from_str() {
         let l = "0 1 0 1 0\n1 0 1 0 1\n0 1 0 1 0\n1 0 1 0 1\n0 1 0 1 0";
 
-        let gw_str = GridWorld::<u8>::from_str(&l);
-        let gw_raw = GridWorld::<u8>::new(array![
+        let gw_str = Grid::<u8>::from_str(&l);
+        let gw_raw = Grid::<u8>::new(array![
             [0, 1, 0, 1, 0],
             [1, 0, 1, 0, 1],
             [0, 1, 0, 1, 0],
@@ -199,7 +414,7 @@ mod tests {
 
     #[test]
     fn test_get() {
-        let gw = GridWorld::new(array![
+        let gw = Grid::new(array![
             [0, 1, 0, 1, 0],
             [1, 0, 1, 0, 1],
             [0, 1, 0, 1, 0],
@@ -218,7 +433,7 @@ mod tests {
 
     #[test]
     fn test_move_ew() {
-        let gw = GridWorld::new(array![
+        let gw = Grid::new(array![
             [0, 0, 0, 0],
             [0, 0, 0, 0],
             [0, 0, 0, 0],
@@ -241,7 +456,7 @@ mod tests {
 
     #[test]
     fn test_move_ns() {
-        let gw = GridWorld::new(array![
+        let gw = Grid::new(array![
             [0, 0, 0, 0, 0],
             [0, 0, 0, 0, 0],
             [0, 0, 0, 0, 0],
@@ -263,7 +478,7 @@ mod tests {
 
     #[test]
     fn test_motion_validation() {
-        let gw = GridWorld::new(array![
+        let gw = Grid::new(array![
             [0, 0, 0, 0, 0],
             [0, 0, 0, 0, 0],
             [0, 0, 0, 0, 0],
@@ -291,7 +506,7 @@ mod tests {
 
     #[test]
     fn test_motions() {
-        let gw = GridWorld::new(array![
+        let gw = Grid::new(array![
             [0, 0, 0, 0, 0],
             [0, 0, 0, 0, 0],
             [0, 0, 0, 0, 0],
@@ -352,4 +567,151 @@ mod tests {
             gw.move_west(loc, 3)
         );
     }
+
+    #[test]
+    fn test_domain_spaces() {
+        let gw = GridWorld::new(4, 3, [0, 0], &[[3, 2]], 10.0);
+
+        assert_eq!(gw.state_space(), Ordinal::new(12));
+        assert_eq!(gw.action_space(), Ordinal::new(4));
+        assert_eq!(*gw.emit().state(), 0);
+    }
+
+    #[test]
+    fn test_domain_edges() {
+        let mut gw = GridWorld::new(3, 3, [0, 0], &[[2, 2]], 10.0);
+
+        let (ns, r) = gw.step(&1);
+        assert_eq!(*ns.state(), 0);
+        assert_eq!(r, -1.0);
+
+        let (ns, _) = gw.step(&2);
+        assert_eq!(*ns.state(), 0);
+
+        let (ns, _) = gw.step(&3);
+        assert_eq!(*ns.state(), 1);
+
+        let (ns, _) = gw.step(&0);
+        assert_eq!(*ns.state(), 4);
+    }
+
+    #[test]
+    fn test_domain_walls() {
+        let mut gw = GridWorld::new(3, 3, [0, 0], &[[2, 2]], 10.0).with_walls(&[[1, 0], [0, 1]]);
+
+        assert!(gw.is_wall([1, 0]));
+
+        let (ns, _) = gw.step(&3);
+        assert_eq!(gw.location(), [0, 0]);
+        assert_eq!(*ns.state(), 0);
+
+        let (ns, _) = gw.step(&0);
+        assert_eq!(gw.location(), [0, 0]);
+        assert!(!ns.is_terminal());
+    }
+
+    #[test]
+    fn test_domain_terminal() {
+        let mut gw = GridWorld::new(3, 3, [1, 2], &[[2, 2], [0, 0]], 10.0);
+
+        assert!(!gw.emit().is_terminal());
+
+        let (ns, r) = gw.step(&3);
+
+        assert!(ns.is_terminal());
+        assert_eq!(*ns.state(), 8);
+        assert_eq!(r, 10.0);
+
+        gw.reset();
+
+        assert!(!gw.emit().is_terminal());
+        assert_eq!(gw.location(), [1, 2]);
+    }
+
+    #[test]
+    fn test_model_matches_step() {
+        let gw = GridWorld::new(3, 3, [0, 0], &[[2, 2]], 10.0).with_walls(&[[1, 1]]);
+
+        for s in 0..9 {
+            let loc = [s % 3, s / 3];
+
+            if gw.is_wall(loc) || gw.is_goal(loc) {
+                continue;
+            }
+
+            for a in 0..4 {
+                let mut domain = GridWorld::new(3, 3, loc, &[[2, 2]], 10.0).with_walls(&[[1, 1]]);
+
+                let (ns, r) = domain.step(&a);
+                let (pns, pr) = gw.predict(&s, &a);
+
+                assert_eq!(*pns.state(), *ns.state());
+                assert_eq!((pns.is_terminal(), pr), (ns.is_terminal(), r));
+            }
+        }
+
+        assert_eq!(gw.possible_transitions(&8, &0).len(), 1);
+        assert_eq!(gw.predict(&8, &0).1, 0.0);
+        assert!(gw.predict(&8, &0).0.is_terminal());
+    }
+
+    #[test]
+    fn test_value_iteration() {
+        let gw = GridWorld::new(3, 3, [0, 0], &[[2, 2]], 0.0);
+        let mut v = vec![0.0; 9];
+
+        for _ in 0..20 {
+            v = (0..9)
+                .map(|s| {
+                    if gw.is_goal([s % 3, s / 3]) {
+                        return 0.0;
+                    }
+
+                    (0..4)
+                        .map(|a| {
+                            gw.possible_transitions(&s, &a)
+                                .into_iter()
+                                .map(|(p, ns, r)| {
+                                    let nv = if ns.is_terminal() { 0.0 } else { v[*ns.state()] };
+
+                                    p * (r + nv)
+                                })
+                                .sum::<f64>()
+                        })
+                        .fold(f64::MIN, f64::max)
+                })
+                .collect();
+        }
+
+        // Undiscounted, so each state is worth minus the number of steps taken
+        // before the one entering the goal.
+        for (s, v) in v.into_iter().enumerate() {
+            let distance = (2 - s % 3) + (2 - s / 3);
+
+            assert_eq!(v, -(distance.saturating_sub(1) as f64));
+        }
+    }
+
+    #[test]
+    fn test_render() {
+        let mut gw = GridWorld::new(4, 3, [0, 0], &[[3, 2]], 0.0).with_walls(&[[1, 1]]);
+
+        assert_eq!(gw.render().unwrap(), "...G\n.#..\nA...");
+
+        gw.step(&3);
+        gw.step(&0);
+
+        assert_eq!(gw.render().unwrap(), "...G\n.#..\n.A..");
+
+        gw.step(&3);
+        gw.step(&0);
+        gw.step(&0);
+
+        let rendered = gw.render().unwrap();
+        let rows: Vec<&str> = rendered.lines().collect();
+
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[0].find('G'), Some(3));
+        assert_eq!(rows[0].find('A'), Some(2));
+    }
 }
@@ -38,6 +38,7 @@ make_index!(StateIndex [
     T1 => 0, T1S => 1, T2 => 2, T2S => 3, V => 4, E => 5
 ]);
 
+#[derive(Clone, Debug)]
 pub struct HIVTreatment {
     eps: [f64; 2],
     state: [f64; 6],
@@ -118,6 +119,8 @@ impl Domain for HIVTreatment {
         Observation::Full(s.collect())
     }
 
+    fn reset(&mut self) { *self = Self::default(); }
+
     fn step(&mut self, action: &usize) -> (Observation<Vec<f64>>, Reward) {
         self.update_state(*action);
 
@@ -145,6 +148,10 @@ impl Domain for HIVTreatment {
     }
 
     fn action_space(&self) -> Ordinal { Ordinal::new(4) }
+
+    fn discount(&self) -> f64 { 0.98 }
+
+    fn is_episodic(&self) -> bool { false }
 }
 
 #[cfg(test)]
@@ -2,10 +2,16 @@
 #[cfg_attr(test, macro_use)]
 extern crate ndarray;
 extern crate rand;
+extern crate rayon;
 extern crate spaces;
 
 use crate::spaces::Space;
-use std::iter;
+use ndarray::{Array1, Array2};
+use std::{
+    fmt::Debug,
+    io::{self, Write},
+    iter,
+};
 
 macro_rules! impl_into {
     (Transition < S, $type:ty > => Transition < S,() >) => {
@@ -19,6 +25,7 @@ macro_rules! make_index {
     ($tname:ident [$($name:ident => $idx:literal),+]) => {
         use std::ops::{Index, IndexMut};
 
+        #[allow(clippy::upper_case_acronyms)]
         #[derive(Debug, Clone, Copy)]
         enum $tname {
             $($name = $idx),+
@@ -49,12 +56,21 @@ macro_rules! make_index {
 pub type Reward = f64;
 
 /// Container class for data associated with a domain observation.
-#[derive(Clone, Copy, Debug)]
+///
+/// Observations compare equal only if both the variant and the state agree.
+/// Those over discrete state representations, such as `usize`, are also `Eq`
+/// and `Hash`, and can therefore be used directly as keys in tabular maps;
+/// continuous representations, such as `Vec<f64>`, support only `PartialEq`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum Observation<S> {
     /// Fully observed state of the environment.
     Full(S),
 
     /// Partially observed state of the environment.
+    ///
+    /// The state may contain only a subset of the components of a full
+    /// observation, e.g. the leading components of a `Vec`; see
+    /// [`Observation::padded`].
     Partial(S),
 
     /// Terminal state of the environment.
@@ -125,6 +141,32 @@ impl<S> Observation<S> {
     }
 }
 
+impl<T: Clone> Observation<Vec<T>> {
+    /// Pad a partially observed state out to `dim` components with `fill`.
+    ///
+    /// Full and terminal observations are returned unchanged.
+    ///
+    /// # Panics
+    /// If a partially observed state has more than `dim` components.
+    pub fn padded(self, dim: usize, fill: T) -> Observation<Vec<T>> {
+        match self {
+            Observation::Partial(mut state) => {
+                assert!(
+                    state.len() <= dim,
+                    "Partial observation has {} components, expected at most {}.",
+                    state.len(),
+                    dim
+                );
+
+                state.resize(dim, fill);
+
+                Observation::Partial(state)
+            },
+            obs => obs,
+        }
+    }
+}
+
 /// Container class for data associated with a domain transition.
 #[derive(Clone, Copy, Debug)]
 pub struct Transition<S, A> {
@@ -337,6 +379,23 @@ pub struct Trajectory<S, A> {
 }
 
 impl<S, A> Trajectory<S, A> {
+    /// Construct an empty trajectory beginning in the observation `start`.
+    ///
+    /// Transitions can then be appended as an episode unfolds using
+    /// [`Trajectory::record`].
+    pub fn new(start: Observation<S>) -> Self {
+        Trajectory {
+            start,
+            steps: vec![],
+        }
+    }
+
+    /// Append a transition to the trajectory.
+    ///
+    /// Only the action, reward and successor observation of `t` are stored;
+    /// the `from` observation is assumed to match the end of the trajectory.
+    pub fn record(&mut self, t: Transition<S, A>) { self.steps.push((t.to, t.action, t.reward)); }
+
     pub fn n_states(&self) -> usize { self.steps.len() + 1 }
 
     pub fn n_transitions(&self) -> usize { self.steps.len() }
@@ -355,10 +414,10 @@ impl<S, A> Trajectory<S, A> {
             self.first()
         } else {
             Transition {
-                from: self.steps[index].0.borrowed(),
-                action: &self.steps[index + 1].1,
-                reward: self.steps[index + 1].2,
-                to: self.steps[index + 1].0.borrowed(),
+                from: self.steps[index - 1].0.borrowed(),
+                action: &self.steps[index].1,
+                reward: self.steps[index].2,
+                to: self.steps[index].0.borrowed(),
             }
         }
     }
@@ -372,6 +431,77 @@ impl<S, A> Trajectory<S, A> {
 
     pub fn total_reward(&self) -> Reward { self.steps.iter().map(|oar| oar.2).sum() }
 
+    /// Return the sequence of rewards, one per transition.
+    pub fn rewards(&self) -> Array1<Reward> { self.steps.iter().map(|oar| oar.2).collect() }
+
+    /// Return the sequence of actions, one per transition.
+    pub fn actions(&self) -> Array1<A>
+    where A: Clone {
+        self.steps.iter().map(|oar| oar.1.clone()).collect()
+    }
+
+    /// Return the sequence of visited states, including the start state.
+    pub fn states(&self) -> Array1<S>
+    where S: Clone {
+        iter::once(&self.start)
+            .chain(self.steps.iter().map(|oar| &oar.0))
+            .map(|obs| obs.state().clone())
+            .collect()
+    }
+
+    /// Return the visited states, including the start state, as the rows of a
+    /// matrix.
+    ///
+    /// # Panics
+    /// If the states do not all have the same number of components.
+    pub fn state_matrix(&self) -> Array2<f64>
+    where S: AsRef<[f64]> {
+        let dim = self.start.state().as_ref().len();
+        let values: Vec<f64> = iter::once(&self.start)
+            .chain(self.steps.iter().map(|oar| &oar.0))
+            .flat_map(|obs| {
+                let x = obs.state().as_ref();
+
+                assert_eq!(x.len(), dim, "States must have equal dimensionality.");
+
+                x.iter().cloned()
+            })
+            .collect();
+
+        Array2::from_shape_vec((self.n_states(), dim), values).unwrap()
+    }
+
+    /// Write the trajectory to `writer` in CSV format, one row per transition.
+    ///
+    /// The columns are `step,from,action,reward,to,terminal`; states and
+    /// actions are written (quoted) using their `Debug` representation.
+    pub fn write_csv<W: Write>(&self, mut writer: W) -> io::Result<()>
+    where
+        S: Debug,
+        A: Debug,
+    {
+        fn quoted<T: Debug>(x: &T) -> String {
+            format!("\"{}\"", format!("{:?}", x).replace('"', "\"\""))
+        }
+
+        writeln!(writer, "step,from,action,reward,to,terminal")?;
+
+        for (i, t) in self.iter().enumerate() {
+            writeln!(
+                writer,
+                "{},{},{},{},{},{}",
+                i,
+                quoted(t.from.state()),
+                quoted(t.action),
+                t.reward,
+                quoted(t.to.state()),
+                t.terminated(),
+            )?;
+        }
+
+        Ok(())
+    }
+
     pub fn to_batch(&self) -> Batch<S, A> { self.iter().collect() }
 
     pub fn into_batch(mut self) -> Batch<S, A>
@@ -394,7 +524,7 @@ impl<S, A> Trajectory<S, A> {
         }];
 
         for (i, (ns, a, r)) in steps.enumerate() {
-            let from = batch[i].from.clone();
+            let from = batch[i].to.clone();
 
             batch.push(Transition {
                 from,
@@ -427,10 +557,35 @@ pub trait Domain {
     /// Returns an instance of the action space type class.
     fn action_space(&self) -> Self::ActionSpace;
 
+    /// Return the discount factor conventionally used with this domain.
+    ///
+    /// Agents may use this to initialise their own `gamma`; it has no effect on
+    /// the dynamics. Defaults to 1, i.e. undiscounted.
+    fn discount(&self) -> f64 { 1.0 }
+
+    /// Returns true if the task is episodic, i.e. it has terminal states, and
+    /// false if it is continuing. Defaults to true.
+    fn is_episodic(&self) -> bool { true }
+
+    /// Return a human-readable rendering of the current state of the
+    /// environment, if one is available.
+    ///
+    /// This is intended purely for inspection, e.g. to sanity-check a domain
+    /// while debugging; the format is domain-specific and may change. Defaults
+    /// to `None`.
+    fn render(&self) -> Option<String> { None }
+
     /// Emit an observation of the current state of the environment.
     fn emit(&self) -> Observation<State<Self>>;
 
+    /// Reset the environment to the initial state of a new episode.
+    fn reset(&mut self);
+
     /// Transition the environment forward a single step given an action, `a`.
+    ///
+    /// The action type is derived from the action space: discrete domains
+    /// (e.g. with an `Ordinal` space) accept a `usize` index, whereas
+    /// continuous domains (e.g. with an `Interval` space) accept an `f64`.
     fn step(&mut self, a: &Action<Self>) -> (Observation<State<Self>>, Reward);
 
     fn transition(&mut self, a: Action<Self>) -> Transition<State<Self>, Action<Self>> {
@@ -479,13 +634,90 @@ pub trait Domain {
     }
 }
 
+/// An interface for domains with known dynamics.
+///
+/// Models predict the outcome of taking an action in an arbitrary state
+/// without mutating the domain, which allows for planning methods such as
+/// value iteration and Dyna-style updates.
+pub trait Model: Domain {
+    /// Predict the observation and reward that follow from taking action `a` in
+    /// state `s`.
+    ///
+    /// For stochastic domains this returns a sample outcome; see
+    /// [`Model::possible_transitions`] for the full distribution.
+    fn predict(&self, s: &State<Self>, a: &Action<Self>) -> (Observation<State<Self>>, Reward);
+
+    /// Return every outcome of taking action `a` in state `s` along with its
+    /// probability, as `(probability, observation, reward)` tuples.
+    ///
+    /// The default implementation assumes deterministic dynamics.
+    fn possible_transitions(
+        &self,
+        s: &State<Self>,
+        a: &Action<Self>,
+    ) -> Vec<(f64, Observation<State<Self>>, Reward)> {
+        let (ns, r) = self.predict(s, a);
+
+        vec![(1.0, ns, r)]
+    }
+}
+
+pub type AgentState<D> = <<D as MultiAgentDomain>::StateSpace as Space>::Value;
+pub type AgentAction<D> = <<D as MultiAgentDomain>::ActionSpace as Space>::Value;
+
+/// An interface for domains shared by multiple agents acting simultaneously.
+///
+/// On each step every agent submits an action, and all actions are applied to
+/// the same underlying state at once; each agent then receives its own
+/// observation and reward. Turn-based games can be expressed by ignoring the
+/// actions of agents whose turn it is not. The state and action spaces are
+/// those of a single agent.
+pub trait MultiAgentDomain {
+    /// State space representation type class, as observed by each agent.
+    type StateSpace: Space;
+
+    /// Action space representation type class of each agent.
+    type ActionSpace: Space;
+
+    /// Returns an instance of the state space type class.
+    fn state_space(&self) -> Self::StateSpace;
+
+    /// Returns an instance of the action space type class.
+    fn action_space(&self) -> Self::ActionSpace;
+
+    /// Return the number of agents acting in the domain.
+    fn n_agents(&self) -> usize;
+
+    /// Emit an observation of the current state for each agent, in order.
+    fn emit(&self) -> Vec<Observation<AgentState<Self>>>;
+
+    /// Reset the environment to the initial state of a new episode.
+    fn reset(&mut self);
+
+    /// Transition the environment forward a single step given the joint
+    /// action, with one entry per agent, returning each agent's observation
+    /// and reward.
+    ///
+    /// # Panics
+    /// If `actions` does not contain exactly one action per agent.
+    fn step(
+        &mut self,
+        actions: &[AgentAction<Self>],
+    ) -> (Vec<Observation<AgentState<Self>>>, Vec<Reward>);
+}
+
 mod consts;
-mod grid_world;
 mod macros;
 
 mod ode;
 use self::ode::*;
 
+mod sample_space;
+pub use self::sample_space::SampleSpace;
+
+mod start_distribution;
+pub use self::start_distribution::StartDistribution;
+
 mod mountain_car;
 pub use self::mountain_car::*;
 
@@ -495,16 +727,224 @@ pub use self::cart_pole::*;
 mod acrobot;
 pub use self::acrobot::*;
 
+mod pendulum;
+pub use self::pendulum::*;
+
 mod hiv;
 pub use self::hiv::*;
 
+mod grid_world;
+pub use self::grid_world::GridWorld;
+
 mod cliff_walk;
 pub use self::cliff_walk::*;
 
+mod frozen_lake;
+pub use self::frozen_lake::FrozenLake;
+
+mod taxi;
+pub use self::taxi::Taxi;
+
+mod pursuit;
+pub use self::pursuit::Pursuit;
+
 mod roulette;
 pub use self::roulette::*;
 
+mod bandit;
+pub use self::bandit::{Arm, Bandit};
+
+mod normalized;
+pub use self::normalized::{NormalizedDomain, RunningNormalize, RunningStats};
+
+mod action_repeat;
+pub use self::action_repeat::ActionRepeat;
+
+mod clip_reward;
+pub use self::clip_reward::ClipReward;
+
+mod discretize;
+pub use self::discretize::Discretize;
+
+mod shaped;
+pub use self::shaped::ShapedDomain;
+
+mod time_limit;
+pub use self::time_limit::TimeLimit;
+
+mod vec_domain;
+pub use self::vec_domain::VecDomain;
+
 #[cfg(feature = "openai")]
 mod openai;
 #[cfg(feature = "openai")]
 pub use self::openai::*;
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        Acrobot,
+        Action,
+        Domain,
+        GridWorld,
+        HIVTreatment,
+        Observation,
+        Pendulum,
+        State,
+        TimeLimit,
+        Trajectory,
+        Transition,
+    };
+
+    #[test]
+    fn test_observation_state() {
+        let full = Observation::Full(vec![1.0, 2.0]);
+        let partial = Observation::Partial(vec![1.0]);
+        let terminal = Observation::Terminal(vec![3.0, 4.0]);
+
+        assert_eq!(full.state(), &vec![1.0, 2.0]);
+        assert_eq!(partial.state(), &vec![1.0]);
+        assert_eq!(terminal.state(), &vec![3.0, 4.0]);
+
+        assert!(full.is_full() && !full.is_partial() && !full.is_terminal());
+        assert!(!partial.is_full() && partial.is_partial() && !partial.is_terminal());
+        assert!(!terminal.is_full() && !terminal.is_partial() && terminal.is_terminal());
+    }
+
+    #[test]
+    fn test_observation_padded() {
+        let partial = Observation::Partial(vec![1.0]).padded(3, 0.0);
+
+        assert!(partial.is_partial());
+        assert_eq!(partial.state(), &vec![1.0, 0.0, 0.0]);
+
+        let full = Observation::Full(vec![1.0]).padded(3, 0.0);
+
+        assert_eq!(full.state(), &vec![1.0]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_observation_padded_too_long() {
+        Observation::Partial(vec![1.0, 2.0, 3.0]).padded(2, 0.0);
+    }
+
+    #[test]
+    fn test_observation_hash_map_keys() {
+        use std::collections::HashMap;
+
+        let mut counts = HashMap::new();
+
+        for obs in [Observation::Full(0), Observation::Full(3), Observation::Full(0)].iter() {
+            *counts.entry(*obs).or_insert(0) += 1;
+        }
+
+        *counts.entry(Observation::Terminal(3)).or_insert(0) += 1;
+
+        assert_eq!(counts.len(), 3);
+        assert_eq!(counts[&Observation::Full(0)], 2);
+        assert_eq!(counts[&Observation::Full(3)], 1);
+        assert_eq!(counts[&Observation::Terminal(3)], 1);
+        assert_eq!(counts.get(&Observation::Partial(0)), None);
+
+        // Continuous observations can still be compared for equality.
+        assert_eq!(Observation::Full(vec![0.5]), Observation::Full(vec![0.5]));
+        assert_ne!(Observation::Full(vec![0.5]), Observation::Terminal(vec![0.5]));
+    }
+
+    fn transition_through<D: Domain>(
+        mut domain: D,
+        a: Action<D>,
+    ) -> Transition<State<D>, Action<D>>
+    {
+        domain.transition(a)
+    }
+
+    #[test]
+    fn test_discrete_and_continuous_actions() {
+        let t = transition_through(Acrobot::default(), 2);
+
+        assert_eq!(t.action, 2);
+        assert_eq!(t.from.state().len(), 4);
+
+        let t = transition_through(Pendulum::default(), 0.5);
+
+        assert_eq!(t.action, 0.5);
+        assert_eq!(t.from.state().len(), 3);
+    }
+
+    #[test]
+    fn test_trajectory_recording() {
+        let mut domain = GridWorld::new(6, 1, [0, 0], &[[5, 0]], 10.0);
+        let mut trajectory = Trajectory::new(domain.emit());
+
+        for _ in 0..5 {
+            trajectory.record(domain.transition(3));
+        }
+
+        assert_eq!(trajectory.n_transitions(), 5);
+        assert_eq!(trajectory.rewards(), array![-1.0, -1.0, -1.0, -1.0, 10.0]);
+        assert_eq!(trajectory.actions(), array![3, 3, 3, 3, 3]);
+        assert_eq!(trajectory.states(), array![0, 1, 2, 3, 4, 5]);
+        assert_eq!(trajectory.total_reward(), 6.0);
+
+        let t = trajectory.get(2);
+
+        assert_eq!((**t.from.state(), **t.to.state(), t.reward), (2, 3, -1.0));
+
+        let mut csv = vec![];
+
+        trajectory.write_csv(&mut csv).unwrap();
+
+        let csv = String::from_utf8(csv).unwrap();
+        let rows: Vec<&str> = csv.lines().collect();
+
+        assert_eq!(rows.len(), 6);
+        assert_eq!(rows[0], "step,from,action,reward,to,terminal");
+        assert_eq!(rows[1], "0,\"0\",\"3\",-1,\"1\",false");
+        assert_eq!(rows[5], "4,\"4\",\"3\",10,\"5\",true");
+
+        let batch = trajectory.into_batch();
+
+        assert!(batch.iter().enumerate().all(|(i, t)| *t.from.state() == i));
+        assert!(batch.iter().enumerate().all(|(i, t)| *t.to.state() == i + 1));
+    }
+
+    #[test]
+    fn test_trajectory_state_matrix() {
+        let mut trajectory = Trajectory::new(Observation::Full(vec![0.0, 1.0]));
+
+        trajectory.record(Transition {
+            from: Observation::Full(vec![0.0, 1.0]),
+            action: (),
+            reward: 0.5,
+            to: Observation::Terminal(vec![2.0, 3.0]),
+        });
+
+        assert_eq!(trajectory.state_matrix(), array![[0.0, 1.0], [2.0, 3.0]]);
+    }
+
+    #[test]
+    fn test_discount_and_episodic() {
+        let acrobot = Acrobot::default();
+
+        assert!(acrobot.is_episodic());
+        assert_eq!(acrobot.discount(), 1.0);
+
+        let pendulum = Pendulum::default();
+
+        assert!(!pendulum.is_episodic());
+        assert_eq!(pendulum.discount(), 0.99);
+
+        let hiv = HIVTreatment::default();
+
+        assert!(!hiv.is_episodic());
+        assert_eq!(hiv.discount(), 0.98);
+
+        // Wrappers report the properties of the underlying domain.
+        let wrapped = TimeLimit::new(Pendulum::default(), 200);
+
+        assert!(!wrapped.is_episodic());
+        assert_eq!(wrapped.discount(), 0.99);
+    }
+}
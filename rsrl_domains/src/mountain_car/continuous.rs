@@ -22,11 +22,31 @@ const REWARD_GOAL: f64 = 0.0;
 const MIN_ACTION: f64 = -1.0;
 const MAX_ACTION: f64 = 1.0;
 
+const FUEL_COST: f64 = 0.1;
+const REWARD_BONUS: f64 = 100.0;
+
+/// Reward structure of a [`ContinuousMountainCar`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MountainCarReward {
+    /// A penalty of `-1` per step, and `0` on reaching the goal.
+    StepPenalty,
+
+    /// A cost of `-0.1 a^2` for the (clipped) force `a`, plus a bonus of `100`
+    /// on reaching the goal.
+    ///
+    /// Since idling is free, a policy must trade off fuel against the delayed
+    /// bonus, which makes this a standard benchmark for policy-gradient
+    /// methods.
+    FuelCost,
+}
+
+#[derive(Clone, Debug)]
 pub struct ContinuousMountainCar {
     x: f64,
     v: f64,
 
     action_space: Interval,
+    reward: MountainCarReward,
 }
 
 impl ContinuousMountainCar {
@@ -35,14 +55,19 @@ impl ContinuousMountainCar {
             x,
             v,
             action_space: Interval::bounded(MIN_ACTION, MAX_ACTION),
+            reward: MountainCarReward::StepPenalty,
         }
     }
 
+    /// Return a new instance with the given reward structure.
+    pub fn with_reward(mut self, reward: MountainCarReward) -> ContinuousMountainCar {
+        self.reward = reward;
+        self
+    }
+
     fn dv(x: f64, a: f64) -> f64 { FORCE_CAR * a + FORCE_G * (HILL_FREQ * x).cos() }
 
     fn update_state(&mut self, a: f64) {
-        let a = self.action_space.map_onto(a);
-
         self.v = clip!(V_MIN, self.v + Self::dv(self.x, a), V_MAX);
         self.x = clip!(X_MIN, self.x + self.v, X_MAX);
     }
@@ -64,14 +89,19 @@ impl Domain for ContinuousMountainCar {
         }
     }
 
+    fn reset(&mut self) { *self = Self::default().with_reward(self.reward); }
+
     fn step(&mut self, action: &f64) -> (Observation<Vec<f64>>, Reward) {
-        self.update_state(*action);
+        let a = self.action_space.map_onto(*action);
+
+        self.update_state(a);
 
         let to = self.emit();
-        let reward = if to.is_terminal() {
-            REWARD_GOAL
-        } else {
-            REWARD_STEP
+        let reward = match (self.reward, to.is_terminal()) {
+            (MountainCarReward::StepPenalty, true) => REWARD_GOAL,
+            (MountainCarReward::StepPenalty, false) => REWARD_STEP,
+            (MountainCarReward::FuelCost, true) => REWARD_BONUS - FUEL_COST * a * a,
+            (MountainCarReward::FuelCost, false) => -FUEL_COST * a * a,
         };
 
         (to, reward)
@@ -89,6 +119,10 @@ mod tests {
     use super::*;
     use crate::{Domain, Observation};
 
+    fn fuel_cost(x: f64, v: f64) -> ContinuousMountainCar {
+        ContinuousMountainCar::new(x, v).with_reward(MountainCarReward::FuelCost)
+    }
+
     #[test]
     fn test_initial_observation() {
         let m = ContinuousMountainCar::default();
@@ -120,4 +154,54 @@ mod tests {
             .emit()
             .is_terminal());
     }
+
+    #[test]
+    fn test_continuous_action_clipped() {
+        let mut m1 = fuel_cost(-0.5, 0.0);
+        let mut m2 = fuel_cost(-0.5, 0.0);
+
+        let (s1, r1) = m1.step(&5.0);
+        let (s2, r2) = m2.step(&1.0);
+
+        assert_eq!(s1.state(), s2.state());
+        assert_eq!(r1, r2);
+        assert_eq!(r1, -FUEL_COST);
+
+        let (s1, r1) = m1.step(&-3.0);
+        let (s2, r2) = m2.step(&-1.0);
+
+        assert_eq!(s1.state(), s2.state());
+        assert_eq!(r1, r2);
+    }
+
+    #[test]
+    fn test_fuel_cost() {
+        let reward = |a: f64| fuel_cost(-0.5, 0.0).step(&a).1;
+
+        assert_eq!(reward(0.0), 0.0);
+        assert!(reward(0.5) < 0.0);
+        assert!(reward(1.0) < reward(0.5));
+        assert_eq!(reward(-0.5), reward(0.5));
+        assert!((reward(0.5) + 0.025).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_goal_bonus() {
+        let mut m = fuel_cost(X_MAX - 0.001, V_MAX);
+        let (to, reward) = m.step(&0.0);
+
+        assert!(to.is_terminal());
+        assert_eq!(reward, REWARD_BONUS);
+    }
+
+    #[test]
+    fn test_reset_keeps_reward() {
+        let mut m = fuel_cost(X_MAX - 0.001, V_MAX);
+
+        m.step(&1.0);
+        m.reset();
+
+        assert_eq!(m.emit().state(), &vec![-0.5, 0.0]);
+        assert_eq!(m.step(&1.0).1, -FUEL_COST);
+    }
 }
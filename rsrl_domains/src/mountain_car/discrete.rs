@@ -3,7 +3,9 @@ use crate::{
     Domain,
     Observation,
     Reward,
+    StartDistribution,
 };
+use rand::rngs::StdRng;
 
 const X_MIN: f64 = -1.2;
 const X_MAX: f64 = 0.6;
@@ -47,13 +49,41 @@ const ALL_ACTIONS: [f64; 3] = [-1.0, 0.0, 1.0];
 /// replacing eligibility traces. Recent Advances in Reinforcement Learning,
 /// 123-158. - Sutton, R. S., & Barto, A. G. (1998). Reinforcement learning: An
 /// introduction (Vol. 1, No. 1). Cambridge: MIT press.
+#[derive(Clone, Debug)]
 pub struct MountainCar {
     x: f64,
     v: f64,
+
+    start: Option<StartDistribution>,
 }
 
 impl MountainCar {
-    pub fn new(x: f64, v: f64) -> MountainCar { MountainCar { x, v } }
+    pub fn new(x: f64, v: f64) -> MountainCar { MountainCar { x, v, start: None } }
+
+    /// Return a new instance whose initial `[position, velocity]` is drawn
+    /// from `start` on each reset, rather than being fixed at the bottom of
+    /// the valley.
+    ///
+    /// # Panics
+    /// If `start` is not 2-dimensional.
+    pub fn with_start_distribution(mut self, start: StartDistribution) -> MountainCar {
+        assert_eq!(start.dim(), 2, "Mountain car start distribution must be 2-dimensional.");
+
+        self.start = Some(start);
+        self
+    }
+
+    /// Return a new instance whose initial position is drawn uniformly from
+    /// `[-0.6, -0.4]`, at rest, using the given `rng` on each reset, such that
+    /// two instances with identically seeded generators start from the same
+    /// states.
+    pub fn with_rng(rng: StdRng) -> MountainCar {
+        let start = StartDistribution::new(vec![-0.6, 0.0], vec![-0.4, 0.0], rng);
+        let mut domain = MountainCar::default().with_start_distribution(start);
+
+        domain.reset();
+        domain
+    }
 
     fn dv(x: f64, a: f64) -> f64 { FORCE_CAR * a + FORCE_G * (HILL_FREQ * x).cos() }
 
@@ -81,6 +111,20 @@ impl Domain for MountainCar {
         }
     }
 
+    fn reset(&mut self) {
+        let [x, v] = match self.start {
+            Some(ref mut start) => {
+                let s = start.sample();
+
+                [s[0], s[1]]
+            },
+            None => [-0.5, 0.0],
+        };
+
+        self.x = x;
+        self.v = v;
+    }
+
     fn step(&mut self, action: &usize) -> (Observation<Vec<f64>>, Reward) {
         self.update_state(*action);
 
@@ -99,12 +143,17 @@ impl Domain for MountainCar {
     }
 
     fn action_space(&self) -> Ordinal { Ordinal::new(3) }
+
+    fn render(&self) -> Option<String> {
+        Some(format!("position: {:.4}, velocity: {:.4}", self.x, self.v))
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::{Domain, Observation};
+    use rand::{rngs::StdRng, SeedableRng};
 
     #[test]
     fn test_initial_observation() {
@@ -135,4 +184,41 @@ mod tests {
             .emit()
             .is_terminal());
     }
+
+    #[test]
+    fn test_reset() {
+        let mut m = MountainCar::new(0.3, 0.05);
+
+        m.reset();
+
+        assert_eq!(m.emit().state(), &vec![-0.5, 0.0]);
+
+        let rng = StdRng::seed_from_u64(0);
+        let start = StartDistribution::new(vec![-0.6, 0.0], vec![-0.4, 0.0], rng);
+        let mut m = m.with_start_distribution(start);
+
+        m.reset();
+
+        let s = m.emit().state().clone();
+
+        assert!(s[0] >= -0.6 && s[0] <= -0.4 && s[0] != -0.5);
+        assert_eq!(s[1], 0.0);
+    }
+
+    #[test]
+    fn test_with_rng() {
+        let mut m1 = MountainCar::with_rng(StdRng::seed_from_u64(0));
+        let mut m2 = MountainCar::with_rng(StdRng::seed_from_u64(0));
+
+        for _ in 0..5 {
+            assert_eq!(m1.emit().state(), m2.emit().state());
+
+            for a in [0, 2, 1, 2].iter() {
+                assert_eq!(m1.step(a), m2.step(a));
+            }
+
+            m1.reset();
+            m2.reset();
+        }
+    }
 }
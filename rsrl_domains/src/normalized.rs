@@ -0,0 +1,377 @@
+use super::{Action, Domain, Observation, Reward};
+use crate::spaces::{real::Interval, BoundedSpace, ProductSpace};
+
+/// Wrapper that rescales the state of a bounded domain onto a fixed interval.
+///
+/// Each component of the wrapped domain's state is mapped linearly from the
+/// bounds of the corresponding dimension of its `ProductSpace<Interval>` onto
+/// `[0, 1]` (see [`new`](NormalizedDomain::new)) or `[-1, 1]` (see
+/// [`symmetric`](NormalizedDomain::symmetric)). Values outside the original
+/// bounds are mapped outside the target interval; no clipping is applied.
+#[derive(Clone, Debug)]
+pub struct NormalizedDomain<D> {
+    domain: D,
+
+    bounds: Vec<(f64, f64)>,
+    range: (f64, f64),
+}
+
+impl<D: Domain<StateSpace = ProductSpace<Interval>>> NormalizedDomain<D> {
+    fn with_range(domain: D, range: (f64, f64)) -> NormalizedDomain<D> {
+        let bounds = domain
+            .state_space()
+            .iter()
+            .map(|d| match (d.inf(), d.sup()) {
+                (Some(lb), Some(ub)) => (lb, ub),
+                _ => panic!("NormalizedDomain requires a bounded state space, got {:?}.", d),
+            })
+            .collect();
+
+        NormalizedDomain {
+            domain,
+
+            bounds,
+            range,
+        }
+    }
+
+    /// Construct a wrapper mapping states onto `[0, 1]`.
+    ///
+    /// # Panics
+    /// If any dimension of the domain's state space is unbounded.
+    pub fn new(domain: D) -> NormalizedDomain<D> {
+        NormalizedDomain::with_range(domain, (0.0, 1.0))
+    }
+
+    /// Construct a wrapper mapping states onto `[-1, 1]`.
+    ///
+    /// # Panics
+    /// If any dimension of the domain's state space is unbounded.
+    pub fn symmetric(domain: D) -> NormalizedDomain<D> {
+        NormalizedDomain::with_range(domain, (-1.0, 1.0))
+    }
+
+    /// Return a reference to the wrapped domain.
+    pub fn inner(&self) -> &D { &self.domain }
+
+    /// Consume the wrapper and return the underlying domain.
+    pub fn into_inner(self) -> D { self.domain }
+
+    /// Map a raw state of the wrapped domain onto the target interval.
+    pub fn normalise(&self, state: &[f64]) -> Vec<f64> {
+        let (lo, hi) = self.range;
+
+        state
+            .iter()
+            .zip(self.bounds.iter())
+            .map(|(x, (lb, ub))| lo + (hi - lo) * (x - lb) / (ub - lb))
+            .collect()
+    }
+}
+
+impl<D: Domain<StateSpace = ProductSpace<Interval>>> Domain for NormalizedDomain<D> {
+    type StateSpace = ProductSpace<Interval>;
+    type ActionSpace = D::ActionSpace;
+
+    fn emit(&self) -> Observation<Vec<f64>> { self.domain.emit().map(|s| self.normalise(s)) }
+
+    fn reset(&mut self) { self.domain.reset() }
+
+    fn step(&mut self, action: &Action<Self>) -> (Observation<Vec<f64>>, Reward) {
+        let (to, reward) = self.domain.step(action);
+
+        (to.map(|s| self.normalise(s)), reward)
+    }
+
+    fn state_space(&self) -> Self::StateSpace {
+        let (lo, hi) = self.range;
+
+        self.bounds.iter().map(|_| Interval::bounded(lo, hi)).collect()
+    }
+
+    fn action_space(&self) -> Self::ActionSpace { self.domain.action_space() }
+
+    fn discount(&self) -> f64 { self.domain.discount() }
+
+    fn is_episodic(&self) -> bool { self.domain.is_episodic() }
+
+    fn render(&self) -> Option<String> { self.domain.render() }
+}
+
+/// Online estimate of the per-component mean and variance of a vector stream.
+///
+/// Uses Welford's algorithm, which is numerically stable for long streams.
+#[derive(Clone, Debug)]
+pub struct RunningStats {
+    count: usize,
+    mean: Vec<f64>,
+    m2: Vec<f64>,
+}
+
+impl RunningStats {
+    pub fn new(dim: usize) -> RunningStats {
+        RunningStats {
+            count: 0,
+            mean: vec![0.0; dim],
+            m2: vec![0.0; dim],
+        }
+    }
+
+    /// Return the number of samples observed.
+    pub fn count(&self) -> usize { self.count }
+
+    /// Return the sample mean of each component.
+    pub fn mean(&self) -> &[f64] { &self.mean }
+
+    /// Return the (population) variance of each component.
+    pub fn variance(&self) -> Vec<f64> {
+        if self.count == 0 {
+            vec![0.0; self.m2.len()]
+        } else {
+            self.m2.iter().map(|m2| m2 / self.count as f64).collect()
+        }
+    }
+
+    /// Incorporate a new sample into the statistics.
+    pub fn update(&mut self, x: &[f64]) {
+        self.count += 1;
+
+        let n = self.count as f64;
+
+        for ((m, m2), xi) in self.mean.iter_mut().zip(self.m2.iter_mut()).zip(x.iter()) {
+            let delta = xi - *m;
+
+            *m += delta / n;
+            *m2 += delta * (xi - *m);
+        }
+    }
+
+    /// Standardise a sample to zero mean and unit variance under the current
+    /// statistics; components with (near) zero variance are only centred.
+    pub fn standardise(&self, x: &[f64]) -> Vec<f64> {
+        x.iter()
+            .zip(self.mean.iter())
+            .zip(self.variance())
+            .map(|((xi, m), v)| {
+                let sd = v.sqrt();
+
+                if sd < 1e-8 { xi - m } else { (xi - m) / sd }
+            })
+            .collect()
+    }
+}
+
+/// Wrapper that standardises the state of a domain using running statistics.
+///
+/// This is the counterpart of [`NormalizedDomain`] for domains whose state
+/// spaces are not bounded. The per-component mean and variance are estimated
+/// online from every state the wrapped domain produces (on construction,
+/// `reset` and `step`), and observations are standardised using the current
+/// estimates. The statistics can optionally be frozen after a warm-up period
+/// so that the mapping is stationary thereafter.
+#[derive(Clone, Debug)]
+pub struct RunningNormalize<D> {
+    domain: D,
+
+    stats: RunningStats,
+    warmup: Option<usize>,
+}
+
+impl<D: Domain<StateSpace = ProductSpace<Interval>>> RunningNormalize<D> {
+    pub fn new(domain: D) -> RunningNormalize<D> {
+        let dim = domain.state_space().iter().count();
+        let mut wrapper = RunningNormalize {
+            domain,
+
+            stats: RunningStats::new(dim),
+            warmup: None,
+        };
+
+        wrapper.observe();
+        wrapper
+    }
+
+    /// Stop updating the statistics once `n` states have been observed.
+    pub fn with_warmup(mut self, n: usize) -> RunningNormalize<D> {
+        self.warmup = Some(n);
+        self
+    }
+
+    /// Stop updating the statistics immediately.
+    pub fn freeze(&mut self) { self.warmup = Some(self.stats.count()); }
+
+    /// Return true if the statistics are no longer being updated.
+    pub fn is_frozen(&self) -> bool { self.warmup.is_some_and(|n| self.stats.count() >= n) }
+
+    /// Return a reference to the running statistics.
+    pub fn stats(&self) -> &RunningStats { &self.stats }
+
+    /// Return a reference to the wrapped domain.
+    pub fn inner(&self) -> &D { &self.domain }
+
+    /// Consume the wrapper and return the underlying domain.
+    pub fn into_inner(self) -> D { self.domain }
+
+    fn observe(&mut self) {
+        if !self.is_frozen() {
+            self.stats.update(self.domain.emit().state());
+        }
+    }
+}
+
+impl<D: Domain<StateSpace = ProductSpace<Interval>>> Domain for RunningNormalize<D> {
+    type StateSpace = ProductSpace<Interval>;
+    type ActionSpace = D::ActionSpace;
+
+    fn emit(&self) -> Observation<Vec<f64>> {
+        self.domain.emit().map(|s| self.stats.standardise(s))
+    }
+
+    fn reset(&mut self) {
+        self.domain.reset();
+        self.observe();
+    }
+
+    fn step(&mut self, action: &Action<Self>) -> (Observation<Vec<f64>>, Reward) {
+        let (to, reward) = self.domain.step(action);
+
+        if !self.is_frozen() {
+            self.stats.update(to.state());
+        }
+
+        (to.map(|s| self.stats.standardise(s)), reward)
+    }
+
+    fn state_space(&self) -> Self::StateSpace {
+        self.domain.state_space().iter().map(|_| Interval::unbounded()).collect()
+    }
+
+    fn action_space(&self) -> Self::ActionSpace { self.domain.action_space() }
+
+    fn discount(&self) -> f64 { self.domain.discount() }
+
+    fn is_episodic(&self) -> bool { self.domain.is_episodic() }
+
+    fn render(&self) -> Option<String> { self.domain.render() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{NormalizedDomain, RunningNormalize, RunningStats};
+    use crate::{
+        spaces::{real::Interval, BoundedSpace, Dim, ProductSpace, Space},
+        Domain,
+        MountainCar,
+        Observation,
+        Reward,
+    };
+
+    #[test]
+    fn test_unit_bounds() {
+        let domain = NormalizedDomain::new(MountainCar::default());
+
+        assert_eq!(domain.normalise(&[-1.2, -0.07]), vec![0.0, 0.0]);
+        assert_eq!(domain.normalise(&[0.6, 0.07]), vec![1.0, 1.0]);
+
+        let mid = domain.normalise(&[-0.3, 0.0]);
+
+        assert!((mid[0] - 0.5).abs() < 1e-10);
+        assert!((mid[1] - 0.5).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_symmetric_bounds() {
+        let domain = NormalizedDomain::symmetric(MountainCar::default());
+
+        assert_eq!(domain.normalise(&[-1.2, -0.07]), vec![-1.0, -1.0]);
+        assert_eq!(domain.normalise(&[0.6, 0.07]), vec![1.0, 1.0]);
+        assert!(domain.normalise(&[-0.3, 0.0]).iter().all(|x| x.abs() < 1e-10));
+    }
+
+    #[test]
+    fn test_emit_and_space() {
+        let mut domain = NormalizedDomain::new(MountainCar::new(-0.3, 0.0));
+        let space = domain.state_space();
+
+        assert_eq!(space.dim(), Dim::Finite(2));
+        assert!(space.iter().all(|d| d.inf() == Some(0.0) && d.sup() == Some(1.0)));
+
+        let s = domain.emit().state().clone();
+
+        assert!((s[0] - 0.5).abs() < 1e-10 && (s[1] - 0.5).abs() < 1e-10);
+
+        let (ns, _) = domain.step(&1);
+
+        assert!(ns.state().iter().all(|x| *x >= 0.0 && *x <= 1.0));
+    }
+
+    #[test]
+    fn test_running_stats() {
+        let stream: Vec<[f64; 2]> = (0..100).map(|i| [i as f64, 5.0 - 3.0 * i as f64]).collect();
+        let mut stats = RunningStats::new(2);
+
+        for x in stream.iter() {
+            stats.update(x);
+        }
+
+        assert_eq!(stats.count(), 100);
+        assert!((stats.mean()[0] - 49.5).abs() < 1e-10);
+        assert!((stats.variance()[0] - 833.25).abs() < 1e-8);
+
+        let zs: Vec<Vec<f64>> = stream.iter().map(|x| stats.standardise(x)).collect();
+
+        for j in 0..2 {
+            let mean = zs.iter().map(|z| z[j]).sum::<f64>() / 100.0;
+            let var = zs.iter().map(|z| (z[j] - mean).powi(2)).sum::<f64>() / 100.0;
+
+            assert!(mean.abs() < 1e-10);
+            assert!((var - 1.0).abs() < 1e-10);
+        }
+    }
+
+    // Unbounded domain whose state counts the number of steps taken.
+    #[derive(Clone, Debug)]
+    struct Counter(f64);
+
+    impl Domain for Counter {
+        type StateSpace = ProductSpace<Interval>;
+        type ActionSpace = Interval;
+
+        fn emit(&self) -> Observation<Vec<f64>> { Observation::Full(vec![self.0]) }
+
+        fn reset(&mut self) { self.0 = 0.0; }
+
+        fn step(&mut self, _: &f64) -> (Observation<Vec<f64>>, Reward) {
+            self.0 += 1.0;
+
+            (self.emit(), 0.0)
+        }
+
+        fn state_space(&self) -> Self::StateSpace { ProductSpace::empty() + Interval::unbounded() }
+
+        fn action_space(&self) -> Interval { Interval::unbounded() }
+    }
+
+    #[test]
+    fn test_running_normalize_warmup() {
+        let mut domain = RunningNormalize::new(Counter(0.0)).with_warmup(5);
+
+        for _ in 0..10 {
+            domain.step(&0.0);
+        }
+
+        // Statistics cover states 0..=4 only.
+        assert!(domain.is_frozen());
+        assert_eq!(domain.stats().count(), 5);
+        assert!((domain.stats().mean()[0] - 2.0).abs() < 1e-10);
+
+        let z = domain.emit().state()[0];
+
+        assert!((z - 8.0 / 2.0f64.sqrt()).abs() < 1e-10);
+
+        domain.reset();
+
+        assert_eq!(domain.stats().count(), 5);
+        assert!((domain.emit().state()[0] + 2.0 / 2.0f64.sqrt()).abs() < 1e-10);
+    }
+}
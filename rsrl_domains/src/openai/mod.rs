@@ -89,6 +89,15 @@ impl Domain for OpenAIGym {
         }
     }
 
+    fn reset(&mut self) {
+        let py = self.client.py();
+        let obs = self.env.call_method(py, "reset", NoArgs, None).unwrap();
+
+        self.state = OpenAIGym::parse_vec(py, &obs);
+        self.terminal = false;
+        self.last_reward = 0.0;
+    }
+
     fn step(&mut self, a: usize) -> Transition<Vec<f64>, usize> {
         let from = self.emit();
 
@@ -0,0 +1,161 @@
+use super::{runge_kutta4, Domain, Observation, Reward};
+use crate::spaces::{real::Interval, ProductSpace, Surjection};
+use std::f64::consts::PI;
+
+// Pendulum mass and length:
+const M: f64 = 1.0;
+const L: f64 = 1.0;
+
+// Gravitational acceleration:
+const G: f64 = 10.0;
+
+const DT: f64 = 0.05;
+
+const LIMITS_DTHETA: [f64; 2] = [-8.0, 8.0];
+const LIMITS_TORQUE: [f64; 2] = [-2.0, 2.0];
+
+const COST_THETA: f64 = 1.0;
+const COST_DTHETA: f64 = 0.1;
+const COST_TORQUE: f64 = 0.001;
+
+make_index!(StateIndex [
+    THETA => 0, DTHETA => 1
+]);
+
+/// Classic inverted pendulum swing-up domain.
+///
+/// A frictionless pendulum starts hanging downwards and must be swung up and
+/// balanced upright using a single continuous torque applied at the pivot. The
+/// actuator is too weak to lift the pendulum directly, so the agent must learn
+/// to build up momentum first. The problem is continuing: there is no terminal
+/// state.
+///
+/// The observation is `[cos θ, sin θ, θ̇]`, where `θ = 0` corresponds to the
+/// upright position. The reward on each step is given by
+/// `-(θ² + 0.1 θ̇² + 0.001 u²)`, with `θ` normalised to `[-π, π]` and `u` the
+/// (clipped) torque.
+#[derive(Clone, Debug)]
+pub struct Pendulum([f64; 2]);
+
+impl Pendulum {
+    pub fn new(theta: f64, dtheta: f64) -> Pendulum { Pendulum([theta, dtheta]) }
+
+    fn update_state(&mut self, torque: f64) {
+        let fx = |_x, y| Pendulum::grad(torque, y);
+        let ns = runge_kutta4(fx, 0.0, self.0.to_vec(), DT);
+
+        self.0[StateIndex::THETA] = wrap!(-PI, ns[StateIndex::THETA], PI);
+        self.0[StateIndex::DTHETA] =
+            clip!(LIMITS_DTHETA[0], ns[StateIndex::DTHETA], LIMITS_DTHETA[1]);
+    }
+
+    fn grad(torque: f64, mut buffer: Vec<f64>) -> Vec<f64> {
+        let theta = buffer[StateIndex::THETA];
+        let dtheta = buffer[StateIndex::DTHETA];
+
+        buffer[StateIndex::THETA] = dtheta;
+        buffer[StateIndex::DTHETA] =
+            3.0 * G / (2.0 * L) * theta.sin() + 3.0 / (M * L * L) * torque;
+
+        buffer
+    }
+
+    fn cost(&self, torque: f64) -> f64 {
+        let theta = self.0[StateIndex::THETA];
+        let dtheta = self.0[StateIndex::DTHETA];
+
+        COST_THETA * theta * theta + COST_DTHETA * dtheta * dtheta + COST_TORQUE * torque * torque
+    }
+}
+
+impl Default for Pendulum {
+    fn default() -> Pendulum { Pendulum::new(PI, 0.0) }
+}
+
+impl Domain for Pendulum {
+    type StateSpace = ProductSpace<Interval>;
+    type ActionSpace = Interval;
+
+    fn emit(&self) -> Observation<Vec<f64>> {
+        let theta = self.0[StateIndex::THETA];
+
+        Observation::Full(vec![theta.cos(), theta.sin(), self.0[StateIndex::DTHETA]])
+    }
+
+    fn reset(&mut self) { *self = Self::default(); }
+
+    fn step(&mut self, action: &f64) -> (Observation<Vec<f64>>, Reward) {
+        let torque = self.action_space().map_onto(*action);
+        let reward = -self.cost(torque);
+
+        self.update_state(torque);
+
+        (self.emit(), reward)
+    }
+
+    fn state_space(&self) -> Self::StateSpace {
+        ProductSpace::empty()
+            + Interval::bounded(-1.0, 1.0)
+            + Interval::bounded(-1.0, 1.0)
+            + Interval::bounded(LIMITS_DTHETA[0], LIMITS_DTHETA[1])
+    }
+
+    fn action_space(&self) -> Interval { Interval::bounded(LIMITS_TORQUE[0], LIMITS_TORQUE[1]) }
+
+    fn discount(&self) -> f64 { 0.99 }
+
+    fn is_episodic(&self) -> bool { false }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Domain, Observation};
+
+    #[test]
+    fn test_initial_observation() {
+        let m = Pendulum::default();
+
+        match m.emit() {
+            Observation::Full(ref state) => {
+                assert_eq!(state.len(), 3);
+                assert!((state[0] + 1.0).abs() < 1e-7);
+                assert!(state[1].abs() < 1e-7);
+                assert_eq!(state[2], 0.0);
+            },
+            _ => panic!("Should yield a fully observable state."),
+        }
+
+        assert!(!m.emit().is_terminal());
+    }
+
+    #[test]
+    fn test_upright_reward() {
+        let mut m = Pendulum::new(0.0, 0.0);
+        let (ns, r) = m.step(&0.0);
+
+        assert_eq!(r, 0.0);
+        assert!(!ns.is_terminal());
+    }
+
+    #[test]
+    fn test_torque_clipping() {
+        let mut m1 = Pendulum::new(0.5, 0.0);
+        let mut m2 = Pendulum::new(0.5, 0.0);
+
+        let (s1, r1) = m1.step(&100.0);
+        let (s2, r2) = m2.step(&LIMITS_TORQUE[1]);
+
+        assert_eq!(r1, r2);
+        assert_eq!(s1.state(), s2.state());
+
+        let mut m3 = Pendulum::new(0.5, 0.0);
+        let mut m4 = Pendulum::new(0.5, 0.0);
+
+        let (s3, r3) = m3.step(&-100.0);
+        let (s4, r4) = m4.step(&LIMITS_TORQUE[0]);
+
+        assert_eq!(r3, r4);
+        assert_eq!(s3.state(), s4.state());
+    }
+}
@@ -0,0 +1,211 @@
+use crate::{spaces::discrete::Ordinal, MultiAgentDomain, Observation, Reward};
+
+const REWARD_STEP: f64 = -1.0;
+const REWARD_CAPTURE: f64 = 10.0;
+
+/// Two-player pursuit-evasion game on an open grid.
+///
+/// A pursuer (agent 0) and an evader (agent 1) move simultaneously between the
+/// cells of a `width` x `height` grid. The episode terminates once the evader
+/// is captured, i.e. when both players end a step in the same cell, or when
+/// they swap cells by moving through one another. Movement off the edge of
+/// the grid leaves a player where it is.
+///
+/// The game is zero-sum: each step yields a reward of -1 to the pursuer and +1
+/// to the evader, with the exception of the capturing step, which yields +10
+/// to the pursuer and -10 to the evader.
+///
+/// # Technical details
+/// Both players observe the full joint **state**, given by the ordinal
+/// encoding `pursuer * width * height + evader`, where each player's location
+/// is the cell index `y * width + x`.
+///
+/// The **actions** of each player are given by:
+///
+/// | Index | Motion |
+/// | ----- | ------ |
+/// | 0     | Up     |
+/// | 1     | Down   |
+/// | 2     | Left   |
+/// | 3     | Right  |
+/// | 4     | Stay   |
+#[derive(Clone, Debug)]
+pub struct Pursuit {
+    width: usize,
+    height: usize,
+
+    start: [[usize; 2]; 2],
+    locs: [[usize; 2]; 2],
+    captured: bool,
+}
+
+impl Pursuit {
+    /// Construct a new instance with the pursuer starting at `pursuer` and the
+    /// evader at `evader`, both given as `[x, y]` cell locations.
+    ///
+    /// # Panics
+    /// If either location lies outside the grid, or if they coincide.
+    pub fn new(width: usize, height: usize, pursuer: [usize; 2], evader: [usize; 2]) -> Pursuit {
+        for loc in [pursuer, evader].iter() {
+            assert!(
+                loc[0] < width && loc[1] < height,
+                "Location {:?} lies outside the grid.",
+                loc
+            );
+        }
+
+        assert!(pursuer != evader, "Pursuer and evader must start in different cells.");
+
+        Pursuit {
+            width,
+            height,
+
+            start: [pursuer, evader],
+            locs: [pursuer, evader],
+            captured: false,
+        }
+    }
+
+    /// Return the `[x, y]` location of the pursuer.
+    pub fn pursuer(&self) -> [usize; 2] { self.locs[0] }
+
+    /// Return the `[x, y]` location of the evader.
+    pub fn evader(&self) -> [usize; 2] { self.locs[1] }
+
+    fn cell(&self, loc: [usize; 2]) -> usize { loc[1] * self.width + loc[0] }
+
+    fn move_from(&self, loc: [usize; 2], action: usize) -> [usize; 2] {
+        let [x, y] = loc;
+
+        match action {
+            0 => [x, (y + 1).min(self.height - 1)],
+            1 => [x, y.saturating_sub(1)],
+            2 => [x.saturating_sub(1), y],
+            3 => [(x + 1).min(self.width - 1), y],
+            4 => loc,
+            _ => panic!("Unknown action {}!", action),
+        }
+    }
+}
+
+impl Default for Pursuit {
+    fn default() -> Pursuit { Pursuit::new(5, 5, [0, 0], [4, 4]) }
+}
+
+impl MultiAgentDomain for Pursuit {
+    type StateSpace = Ordinal;
+    type ActionSpace = Ordinal;
+
+    fn state_space(&self) -> Ordinal { Ordinal::new((self.width * self.height).pow(2)) }
+
+    fn action_space(&self) -> Ordinal { Ordinal::new(5) }
+
+    fn n_agents(&self) -> usize { 2 }
+
+    fn emit(&self) -> Vec<Observation<usize>> {
+        let s = self.cell(self.locs[0]) * self.width * self.height + self.cell(self.locs[1]);
+        let obs = if self.captured {
+            Observation::Terminal(s)
+        } else {
+            Observation::Full(s)
+        };
+
+        vec![obs; 2]
+    }
+
+    fn reset(&mut self) {
+        self.locs = self.start;
+        self.captured = false;
+    }
+
+    fn step(&mut self, actions: &[usize]) -> (Vec<Observation<usize>>, Vec<Reward>) {
+        assert_eq!(actions.len(), 2, "Pursuit expects one action for each of its 2 agents.");
+
+        let [p, e] = self.locs;
+        let np = self.move_from(p, actions[0]);
+        let ne = self.move_from(e, actions[1]);
+
+        self.locs = [np, ne];
+        self.captured = np == ne || (np == e && ne == p);
+
+        let reward = if self.captured {
+            REWARD_CAPTURE
+        } else {
+            REWARD_STEP
+        };
+
+        (self.emit(), vec![reward, -reward])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Pursuit;
+    use crate::{spaces::discrete::Ordinal, MultiAgentDomain};
+
+    #[test]
+    fn test_spaces_and_initial_observations() {
+        let domain = Pursuit::default();
+        let obs = domain.emit();
+
+        assert_eq!(domain.n_agents(), 2);
+        assert_eq!(domain.state_space(), Ordinal::new(625));
+        assert_eq!(domain.action_space(), Ordinal::new(5));
+
+        assert_eq!(obs.len(), 2);
+        assert_eq!(*obs[0].state(), 24);
+        assert_eq!(*obs[1].state(), 24);
+    }
+
+    #[test]
+    fn test_simultaneous_step() {
+        let mut domain = Pursuit::new(5, 1, [0, 0], [3, 0]);
+
+        // Both players move right, applied to the same prior state.
+        let (obs, rewards) = domain.step(&[3, 3]);
+
+        assert_eq!(obs.len(), 2);
+        assert_eq!(rewards, vec![-1.0, 1.0]);
+        assert_eq!(domain.pursuer(), [1, 0]);
+        assert_eq!(domain.evader(), [4, 0]);
+        // Joint state: pursuer in cell 1, evader in cell 4 of 5.
+        assert!(obs.iter().all(|o| !o.is_terminal() && *o.state() == 9));
+
+        // The evader is stuck against the edge; the pursuer closes in.
+        domain.step(&[3, 3]);
+
+        let (obs, rewards) = domain.step(&[3, 4]);
+
+        assert_eq!(domain.pursuer(), [3, 0]);
+        assert_eq!(rewards, vec![-1.0, 1.0]);
+        assert!(!obs[0].is_terminal());
+
+        // Moving into the same cell captures the evader.
+        let (obs, rewards) = domain.step(&[3, 4]);
+
+        assert_eq!(rewards, vec![10.0, -10.0]);
+        assert!(obs.iter().all(|o| o.is_terminal()));
+
+        domain.reset();
+
+        assert_eq!(domain.pursuer(), [0, 0]);
+        assert!(!domain.emit()[1].is_terminal());
+    }
+
+    #[test]
+    fn test_swap_captures() {
+        let mut domain = Pursuit::new(2, 1, [0, 0], [1, 0]);
+
+        // Moving through one another counts as a capture.
+        let (obs, rewards) = domain.step(&[3, 2]);
+
+        assert_eq!(domain.pursuer(), [1, 0]);
+        assert_eq!(domain.evader(), [0, 0]);
+        assert_eq!(rewards, vec![10.0, -10.0]);
+        assert!(obs[0].is_terminal() && obs[1].is_terminal());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_wrong_number_of_actions() { Pursuit::default().step(&[0]); }
+}
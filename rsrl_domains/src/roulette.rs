@@ -4,27 +4,35 @@ use crate::{
     Observation,
     Reward,
 };
-use rand::{rngs::ThreadRng, thread_rng, Rng};
+use rand::{rngs::StdRng, Rng, SeedableRng};
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct Roulette {
     active: bool,
     reward: f64,
     wealth: f64,
+    budget: f64,
     bet_size: f64,
 
-    rng: ThreadRng,
+    rng: StdRng,
 }
 
 impl Roulette {
     pub fn new(budget: f64, bet_size: f64) -> Self {
+        Self::with_rng(budget, bet_size, StdRng::from_entropy())
+    }
+
+    /// Construct a new instance which draws outcomes from the given `rng`, such that
+    /// two instances with identically seeded generators produce the same spins.
+    pub fn with_rng(budget: f64, bet_size: f64, rng: StdRng) -> Self {
         Self {
             active: true,
             reward: 0.0,
             wealth: budget,
+            budget,
             bet_size,
 
-            rng: thread_rng(),
+            rng,
         }
     }
 
@@ -301,6 +309,12 @@ impl Domain for Roulette {
         }
     }
 
+    fn reset(&mut self) {
+        self.active = true;
+        self.reward = 0.0;
+        self.wealth = self.budget;
+    }
+
     fn step(&mut self, action: &usize) -> (Observation<f64>, Reward) {
         self.update_state(*action);
 
@@ -311,3 +325,30 @@ impl Domain for Roulette {
 
     fn action_space(&self) -> Self::ActionSpace { Ordinal::new(157) }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seeded_determinism() {
+        let mut d1 = Roulette::with_rng(100.0, 1.0, StdRng::seed_from_u64(0));
+        let mut d2 = Roulette::with_rng(100.0, 1.0, StdRng::seed_from_u64(0));
+
+        for _ in 0..50 {
+            let (o1, r1) = d1.step(&0);
+            let (o2, r2) = d2.step(&0);
+
+            assert_eq!(o1.state(), o2.state());
+            assert_eq!(r1, r2);
+        }
+    }
+
+    #[test]
+    fn test_quit() {
+        let mut d = Roulette::default();
+        let (o, _) = d.step(&156);
+
+        assert!(o.is_terminal());
+    }
+}
@@ -0,0 +1,91 @@
+use crate::spaces::{discrete::Ordinal, real::Interval, BoundedSpace, ProductSpace, Space};
+use rand::{
+    distributions::{Distribution, Uniform},
+    Rng,
+};
+
+/// Extension of [`Space`] for spaces from which elements can be drawn at
+/// random, e.g. to act uniformly or to randomise the start of an episode.
+///
+/// Sampling is uniform over the space: integer-valued for [`Ordinal`],
+/// continuous for bounded [`Interval`]s, and independent across the
+/// dimensions of a [`ProductSpace`].
+pub trait SampleSpace: Space {
+    /// Draw a random element of the space using `rng`.
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Self::Value;
+}
+
+impl SampleSpace for Ordinal {
+    /// # Panics
+    /// If the space is empty.
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> usize {
+        let n = self.sup().map_or(0, |ub| ub + 1);
+
+        Uniform::new(0, n).sample(rng)
+    }
+}
+
+impl SampleSpace for Interval {
+    /// # Panics
+    /// If either bound of the interval is infinite.
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> f64 {
+        match (self.inf(), self.sup()) {
+            (Some(lb), Some(ub)) => lb + (ub - lb) * rng.gen::<f64>(),
+            _ => panic!("Cannot sample uniformly from an unbounded interval."),
+        }
+    }
+}
+
+impl<D: SampleSpace> SampleSpace for ProductSpace<D> {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Vec<D::Value> {
+        self.iter().map(|d| d.sample(rng)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SampleSpace;
+    use crate::spaces::{discrete::Ordinal, real::Interval, ProductSpace};
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn test_ordinal() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let space = Ordinal::new(5);
+        let mut counts = [0; 5];
+
+        for _ in 0..1000 {
+            let a = space.sample(&mut rng);
+
+            assert!(a < 5);
+
+            counts[a] += 1;
+        }
+
+        assert!(counts.iter().all(|&c| c > 150 && c < 250));
+    }
+
+    #[test]
+    fn test_product_space() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let space = ProductSpace::empty()
+            + Interval::bounded(-1.0, 1.0)
+            + Interval::bounded(10.0, 20.0)
+            + Interval::bounded(0.5, 0.5);
+
+        for _ in 0..1000 {
+            let x = space.sample(&mut rng);
+
+            assert_eq!(x.len(), 3);
+            assert!(x[0] >= -1.0 && x[0] < 1.0);
+            assert!(x[1] >= 10.0 && x[1] < 20.0);
+            assert_eq!(x[2], 0.5);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_unbounded_interval() {
+        Interval::left_bounded(0.0).sample(&mut StdRng::seed_from_u64(0));
+    }
+}
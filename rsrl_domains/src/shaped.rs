@@ -0,0 +1,128 @@
+use super::{Action, Domain, Observation, Reward, State};
+
+/// Wrapper that applies potential-based reward shaping to a domain.
+///
+/// Each transition from `s` to `s'` has its reward augmented by the shaping
+/// term `γ Φ(s') - Φ(s)` for a user-supplied potential function, `Φ`. The
+/// potential of terminal states is taken to be zero, so that the shaping terms
+/// along any episode telescope to `-Φ(s_0)`; optimal policies of the wrapped
+/// domain are therefore preserved, while a well-chosen potential (e.g. the
+/// negative distance to a goal) can greatly speed up learning in domains with
+/// sparse rewards.
+///
+/// # References
+/// - Ng, A. Y., Harada, D., Russell, S. (1999). Policy invariance under reward
+///   transformations: Theory and application to reward shaping. In Proceedings
+///   of ICML, pp. 278–287.
+#[derive(Clone, Debug)]
+pub struct ShapedDomain<D, F> {
+    domain: D,
+    potential: F,
+    gamma: f64,
+}
+
+impl<D: Domain, F: Fn(&State<D>) -> f64> ShapedDomain<D, F> {
+    pub fn new(domain: D, potential: F, gamma: f64) -> ShapedDomain<D, F> {
+        ShapedDomain {
+            domain,
+            potential,
+            gamma,
+        }
+    }
+
+    /// Return the potential associated with an observation.
+    pub fn potential(&self, obs: &Observation<State<D>>) -> f64 {
+        if obs.is_terminal() {
+            0.0
+        } else {
+            (self.potential)(obs.state())
+        }
+    }
+
+    /// Return a reference to the wrapped domain.
+    pub fn inner(&self) -> &D { &self.domain }
+
+    /// Consume the wrapper and return the underlying domain.
+    pub fn into_inner(self) -> D { self.domain }
+}
+
+impl<D: Domain, F: Fn(&State<D>) -> f64> Domain for ShapedDomain<D, F> {
+    type StateSpace = D::StateSpace;
+    type ActionSpace = D::ActionSpace;
+
+    fn emit(&self) -> Observation<State<D>> { self.domain.emit() }
+
+    fn reset(&mut self) { self.domain.reset() }
+
+    fn step(&mut self, action: &Action<Self>) -> (Observation<State<D>>, Reward) {
+        let phi = self.potential(&self.domain.emit());
+        let (to, reward) = self.domain.step(action);
+        let shaping = self.gamma * self.potential(&to) - phi;
+
+        (to, reward + shaping)
+    }
+
+    fn state_space(&self) -> Self::StateSpace { self.domain.state_space() }
+
+    fn action_space(&self) -> Self::ActionSpace { self.domain.action_space() }
+
+    fn discount(&self) -> f64 { self.domain.discount() }
+
+    fn is_episodic(&self) -> bool { self.domain.is_episodic() }
+
+    fn render(&self) -> Option<String> { self.domain.render() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ShapedDomain;
+    use crate::{Domain, GridWorld};
+
+    // Corridor of 5 cells with the goal at the right-hand end.
+    fn corridor() -> GridWorld { GridWorld::new(5, 1, [0, 0], &[[4, 0]], 10.0) }
+
+    #[test]
+    fn test_constant_potential() {
+        let mut base = corridor();
+        let mut shaped = ShapedDomain::new(corridor(), |_: &usize| 3.0, 1.0);
+
+        for &a in [3, 2, 3, 3].iter() {
+            let (ns, r) = base.step(&a);
+            let (ns_shaped, r_shaped) = shaped.step(&a);
+
+            assert_eq!(ns.state(), ns_shaped.state());
+            assert!(!ns_shaped.is_terminal());
+            assert_eq!(r, r_shaped);
+        }
+    }
+
+    #[test]
+    fn test_distance_potential() {
+        let mut base = corridor();
+        let mut shaped = ShapedDomain::new(corridor(), |s: &usize| -((4 - *s) as f64), 0.9);
+
+        // Moving towards the goal is rewarded, moving away is penalised.
+        let (_, r) = shaped.step(&3);
+
+        assert_eq!(base.step(&3).1, -1.0);
+        assert!((r - (-1.0 + 0.9 * -3.0 + 4.0)).abs() < 1e-10);
+
+        let (_, r) = shaped.step(&2);
+
+        assert_eq!(base.step(&2).1, -1.0);
+        assert!((r - (-1.0 + 0.9 * -4.0 + 3.0)).abs() < 1e-10);
+
+        for _ in 0..3 {
+            base.step(&3);
+            shaped.step(&3);
+        }
+
+        // The final step enters the goal, whose potential is zero.
+        let (ns, r) = shaped.step(&3);
+        let (ns_base, r_base) = base.step(&3);
+
+        assert!(ns.is_terminal() && ns_base.is_terminal());
+        assert_eq!(ns.state(), ns_base.state());
+        assert!((r - (r_base + 1.0)).abs() < 1e-10);
+    }
+}
@@ -0,0 +1,291 @@
+use crate::{spaces::discrete::Ordinal, Domain, Observation, Reward};
+
+const SIZE: usize = 5;
+
+/// Pickup/dropoff locations, `[row, col]`: R, G, Y and B.
+const LANDMARKS: [[usize; 2]; 4] = [[0, 0], [0, 4], [4, 0], [4, 3]];
+
+/// Passenger location encoding the passenger riding in the taxi.
+const IN_TAXI: usize = 4;
+
+const REWARD_STEP: f64 = -1.0;
+const REWARD_DROPOFF: f64 = 20.0;
+const REWARD_ILLEGAL: f64 = -10.0;
+
+/// Taxi domain of Dietterich (2000).
+///
+/// A taxi moves around a 5x5 grid with four landmarks, R, G, Y and B, which
+/// are laid out (with walls `|`) as follows:
+///
+/// ```text
+/// +---------+
+/// |R: | : :G|
+/// | : | : : |
+/// | : : : : |
+/// | | : | : |
+/// |Y| : |B: |
+/// +---------+
+/// ```
+///
+/// A passenger waits at one landmark and wants to travel to another. The
+/// episode terminates once the taxi has picked the passenger up and dropped
+/// them off at their destination. Each step incurs a reward of -1, except for
+/// the successful dropoff, which yields +20, and any attempt to pickup or
+/// dropoff when it is not allowed, which yields -10.
+///
+/// # Technical details
+/// The **state** is the ordinal encoding
+/// `((row * 5 + col) * 5 + passenger) * 4 + destination`, giving 500 states,
+/// where `row` 0 is the top of the grid, `passenger` indexes the landmarks
+/// (with 4 denoting a passenger in the taxi) and `destination` indexes the
+/// landmarks in the order R, G, Y, B.
+///
+/// The **actions** are given by:
+///
+/// | Index | Action  |
+/// | ----- | ------- |
+/// | 0     | Up      |
+/// | 1     | Down    |
+/// | 2     | Left    |
+/// | 3     | Right   |
+/// | 4     | Pickup  |
+/// | 5     | Dropoff |
+///
+/// # References
+/// - Dietterich, T. G. (2000). Hierarchical reinforcement learning with the
+///   MAXQ value function decomposition. Journal of Artificial Intelligence
+///   Research, 13, 227-303.
+#[derive(Clone, Debug)]
+pub struct Taxi {
+    start: ([usize; 2], usize, usize),
+
+    taxi: [usize; 2],
+    passenger: usize,
+    destination: usize,
+    delivered: bool,
+}
+
+impl Taxi {
+    /// Construct a new instance with the taxi at `[row, col]`, and the
+    /// passenger waiting at the landmark with index `passenger` to be taken to
+    /// the landmark with index `destination`.
+    ///
+    /// # Panics
+    /// If any of the arguments lie outside the grid or landmark ranges.
+    pub fn new(taxi: [usize; 2], passenger: usize, destination: usize) -> Taxi {
+        assert!(taxi[0] < SIZE && taxi[1] < SIZE, "Taxi {:?} lies outside the grid.", taxi);
+        assert!(passenger < IN_TAXI, "Unknown passenger location {}.", passenger);
+        assert!(destination < IN_TAXI, "Unknown destination {}.", destination);
+
+        Taxi {
+            start: (taxi, passenger, destination),
+
+            taxi,
+            passenger,
+            destination,
+            delivered: false,
+        }
+    }
+
+    /// Return the `[row, col]` location of the taxi.
+    pub fn location(&self) -> [usize; 2] { self.taxi }
+
+    /// Return the location of the passenger; 4 if riding in the taxi.
+    pub fn passenger(&self) -> usize { self.passenger }
+
+    pub fn destination(&self) -> usize { self.destination }
+
+    /// Encode a configuration as an ordinal state.
+    pub fn encode(taxi: [usize; 2], passenger: usize, destination: usize) -> usize {
+        ((taxi[0] * SIZE + taxi[1]) * (IN_TAXI + 1) + passenger) * LANDMARKS.len() + destination
+    }
+
+    /// Decode an ordinal state into its `(taxi, passenger, destination)`
+    /// configuration.
+    pub fn decode(state: usize) -> ([usize; 2], usize, usize) {
+        let destination = state % LANDMARKS.len();
+        let state = state / LANDMARKS.len();
+        let passenger = state % (IN_TAXI + 1);
+        let cell = state / (IN_TAXI + 1);
+
+        ([cell / SIZE, cell % SIZE], passenger, destination)
+    }
+
+    fn blocked(col: usize, row: usize) -> bool {
+        // Walls between column `col` and `col + 1`.
+        match col {
+            0 => row >= 3,
+            1 => row <= 1,
+            2 => row >= 3,
+            _ => false,
+        }
+    }
+
+    fn update_state(&mut self, action: usize) -> Reward {
+        let [row, col] = self.taxi;
+
+        match action {
+            0 => self.taxi = [row.saturating_sub(1), col],
+            1 => self.taxi = [(row + 1).min(SIZE - 1), col],
+            2 if col > 0 && !Self::blocked(col - 1, row) => self.taxi = [row, col - 1],
+            3 if col + 1 < SIZE && !Self::blocked(col, row) => self.taxi = [row, col + 1],
+            2 | 3 => {},
+            4 => {
+                if self.passenger < IN_TAXI && LANDMARKS[self.passenger] == self.taxi {
+                    self.passenger = IN_TAXI;
+                } else {
+                    return REWARD_ILLEGAL;
+                }
+            },
+            5 => {
+                if self.passenger == IN_TAXI && LANDMARKS[self.destination] == self.taxi {
+                    self.passenger = self.destination;
+                    self.delivered = true;
+
+                    return REWARD_DROPOFF;
+                } else {
+                    return REWARD_ILLEGAL;
+                }
+            },
+            _ => panic!("Unknown action {}!", action),
+        }
+
+        REWARD_STEP
+    }
+}
+
+impl Default for Taxi {
+    fn default() -> Taxi { Taxi::new([2, 2], 0, 1) }
+}
+
+impl Domain for Taxi {
+    type StateSpace = Ordinal;
+    type ActionSpace = Ordinal;
+
+    fn emit(&self) -> Observation<usize> {
+        let s = Self::encode(self.taxi, self.passenger, self.destination);
+
+        if self.delivered {
+            Observation::Terminal(s)
+        } else {
+            Observation::Full(s)
+        }
+    }
+
+    fn reset(&mut self) {
+        let (taxi, passenger, destination) = self.start;
+
+        *self = Taxi::new(taxi, passenger, destination);
+    }
+
+    fn step(&mut self, action: &usize) -> (Observation<usize>, Reward) {
+        let reward = self.update_state(*action);
+
+        (self.emit(), reward)
+    }
+
+    fn state_space(&self) -> Self::StateSpace {
+        Ordinal::new(SIZE * SIZE * (IN_TAXI + 1) * LANDMARKS.len())
+    }
+
+    fn action_space(&self) -> Ordinal { Ordinal::new(6) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Taxi;
+    use crate::{spaces::discrete::Ordinal, Domain};
+
+    #[test]
+    fn test_spaces_and_encoding() {
+        let taxi = Taxi::default();
+
+        assert_eq!(taxi.state_space(), Ordinal::new(500));
+        assert_eq!(taxi.action_space(), Ordinal::new(6));
+
+        for s in 0..500 {
+            let (loc, p, d) = Taxi::decode(s);
+
+            assert_eq!(Taxi::encode(loc, p, d), s);
+        }
+
+        assert_eq!(Taxi::decode(*taxi.emit().state()), ([2, 2], 0, 1));
+    }
+
+    #[test]
+    fn test_walls() {
+        let mut taxi = Taxi::new([0, 1], 0, 1);
+
+        taxi.step(&3);
+        assert_eq!(taxi.location(), [0, 1]);
+
+        taxi.step(&1);
+        taxi.step(&1);
+        taxi.step(&3);
+        assert_eq!(taxi.location(), [2, 2]);
+
+        taxi.step(&1);
+        taxi.step(&3);
+        assert_eq!(taxi.location(), [3, 2]);
+    }
+
+    #[test]
+    fn test_illegal_actions() {
+        let mut taxi = Taxi::new([0, 0], 0, 1);
+
+        // Dropoff without a passenger.
+        let (ns, r) = taxi.step(&5);
+
+        assert_eq!(r, -10.0);
+        assert!(!ns.is_terminal());
+
+        // Pickup away from the passenger.
+        taxi.step(&3);
+
+        let (_, r) = taxi.step(&4);
+
+        assert_eq!(r, -10.0);
+        assert_eq!(taxi.passenger(), 0);
+
+        // Dropoff away from the destination.
+        taxi.step(&2);
+        taxi.step(&4);
+
+        let (ns, r) = taxi.step(&5);
+
+        assert_eq!(r, -10.0);
+        assert!(!ns.is_terminal());
+        assert_eq!(taxi.passenger(), 4);
+    }
+
+    #[test]
+    fn test_successful_dropoff() {
+        let mut taxi = Taxi::new([0, 0], 0, 3);
+
+        let (_, r) = taxi.step(&4);
+
+        assert_eq!(r, -1.0);
+        assert_eq!(taxi.passenger(), 4);
+
+        for &a in [1, 1, 3, 3, 3, 1, 1].iter() {
+            let (ns, r) = taxi.step(&a);
+
+            assert_eq!(r, -1.0);
+            assert!(!ns.is_terminal());
+        }
+
+        assert_eq!(taxi.location(), [4, 3]);
+
+        let (ns, r) = taxi.step(&5);
+
+        assert!(ns.is_terminal());
+        assert_eq!(r, 20.0);
+        assert_eq!(Taxi::decode(*ns.state()), ([4, 3], 3, 3));
+
+        taxi.reset();
+
+        assert!(!taxi.emit().is_terminal());
+        assert_eq!(taxi.location(), [0, 0]);
+        assert_eq!(taxi.passenger(), 0);
+    }
+}
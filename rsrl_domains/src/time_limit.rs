@@ -0,0 +1,121 @@
+use super::{Action, Domain, Observation, Reward, State};
+
+/// Wrapper that truncates episodes after a fixed number of steps.
+///
+/// Reaching the step limit is a _truncation_ of the episode rather than a
+/// genuine termination: the state that the limit is reached in is not
+/// absorbing, so value estimates should still bootstrap from it. The wrapper
+/// therefore leaves the final observation as it is, and instead reports the
+/// truncation via [`is_truncated`](TimeLimit::is_truncated). Agents will then
+/// bootstrap as usual on the last transition, and the training loop should
+/// check `is_truncated` (in addition to `Observation::is_terminal`) to decide
+/// when to reset the domain.
+#[derive(Clone, Debug)]
+pub struct TimeLimit<D> {
+    domain: D,
+
+    limit: usize,
+    steps: usize,
+}
+
+impl<D: Domain> TimeLimit<D> {
+    pub fn new(domain: D, limit: usize) -> TimeLimit<D> {
+        TimeLimit {
+            domain,
+
+            limit,
+            steps: 0,
+        }
+    }
+
+    /// Return the maximum number of steps per episode.
+    pub fn limit(&self) -> usize { self.limit }
+
+    /// Return the number of steps taken in the current episode.
+    pub fn steps(&self) -> usize { self.steps }
+
+    /// Return true if the current episode has been truncated by the step
+    /// limit without reaching a terminal state.
+    pub fn is_truncated(&self) -> bool {
+        self.steps >= self.limit && !self.domain.emit().is_terminal()
+    }
+
+    /// Return true if the current episode has ended, either by termination or
+    /// truncation.
+    pub fn is_done(&self) -> bool { self.steps >= self.limit || self.domain.emit().is_terminal() }
+
+    /// Return a reference to the wrapped domain.
+    pub fn inner(&self) -> &D { &self.domain }
+
+    /// Consume the wrapper and return the underlying domain.
+    pub fn into_inner(self) -> D { self.domain }
+}
+
+impl<D: Domain> Domain for TimeLimit<D> {
+    type StateSpace = D::StateSpace;
+    type ActionSpace = D::ActionSpace;
+
+    fn emit(&self) -> Observation<State<D>> { self.domain.emit() }
+
+    fn reset(&mut self) {
+        self.domain.reset();
+        self.steps = 0;
+    }
+
+    fn step(&mut self, action: &Action<Self>) -> (Observation<State<D>>, Reward) {
+        self.steps += 1;
+        self.domain.step(action)
+    }
+
+    fn state_space(&self) -> Self::StateSpace { self.domain.state_space() }
+
+    fn action_space(&self) -> Self::ActionSpace { self.domain.action_space() }
+
+    fn discount(&self) -> f64 { self.domain.discount() }
+
+    fn is_episodic(&self) -> bool { self.domain.is_episodic() }
+
+    fn render(&self) -> Option<String> { self.domain.render() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TimeLimit;
+    use crate::{Domain, GridWorld};
+
+    #[test]
+    fn test_truncation() {
+        let mut domain = TimeLimit::new(GridWorld::default(), 3);
+
+        for i in 1..=3 {
+            assert!(!domain.is_done());
+
+            let t = domain.transition(3);
+
+            assert_eq!(domain.steps(), i);
+            assert!(!t.terminated());
+        }
+
+        // The limit has been reached, but the state is not absorbing.
+        assert!(domain.is_truncated());
+        assert!(domain.is_done());
+        assert!(!domain.emit().is_terminal());
+
+        domain.reset();
+
+        assert_eq!(domain.steps(), 0);
+        assert!(!domain.is_truncated());
+    }
+
+    #[test]
+    fn test_termination() {
+        let mut domain = TimeLimit::new(GridWorld::new(2, 1, [0, 0], &[[1, 0]], 0.0), 1);
+
+        let t = domain.transition(3);
+
+        // Genuine termination at the limit takes precedence.
+        assert!(t.terminated());
+        assert!(domain.is_done());
+        assert!(!domain.is_truncated());
+    }
+}
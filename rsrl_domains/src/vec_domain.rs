@@ -0,0 +1,107 @@
+use super::{Action, Domain, Observation, Reward, State};
+use rayon::prelude::*;
+
+/// Collection of independent domain instances stepped in parallel.
+///
+/// Each sub-domain is stepped on a separate worker thread from the `rayon`
+/// pool. Stochastic domains retain their own random number generators, so
+/// instances should be constructed with distinct seeds (e.g. via
+/// [`Roulette::with_rng`](crate::Roulette::with_rng)) if they are to produce
+/// different outcomes.
+#[derive(Clone, Debug)]
+pub struct VecDomain<D> {
+    domains: Vec<D>,
+}
+
+impl<D: Domain> VecDomain<D> {
+    /// Construct a vectorised domain from a collection of instances.
+    pub fn new(domains: Vec<D>) -> VecDomain<D> { VecDomain { domains } }
+
+    /// Construct a vectorised domain from `n` clones of a single instance.
+    pub fn replicate(domain: &D, n: usize) -> VecDomain<D>
+    where D: Clone {
+        VecDomain::new(vec![domain.clone(); n])
+    }
+
+    /// Return the number of sub-domains.
+    pub fn len(&self) -> usize { self.domains.len() }
+
+    /// Return true if there are no sub-domains.
+    pub fn is_empty(&self) -> bool { self.domains.is_empty() }
+
+    /// Return a reference to the sub-domain instances.
+    pub fn domains(&self) -> &[D] { &self.domains }
+
+    /// Emit an observation from each sub-domain.
+    pub fn emit_all(&self) -> Vec<Observation<State<D>>> {
+        self.domains.iter().map(|d| d.emit()).collect()
+    }
+
+    /// Reset every sub-domain to the start of a new episode.
+    pub fn reset_all(&mut self) {
+        for d in self.domains.iter_mut() {
+            d.reset();
+        }
+    }
+
+    /// Step each sub-domain in parallel with the corresponding action.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of actions differs from the number of sub-domains.
+    pub fn step_all(&mut self, actions: &[Action<D>]) -> Vec<(Observation<State<D>>, Reward)>
+    where
+        D: Send,
+        Action<D>: Sync,
+        State<D>: Send,
+    {
+        assert_eq!(
+            actions.len(),
+            self.domains.len(),
+            "Expected one action per sub-domain."
+        );
+
+        self.domains
+            .par_iter_mut()
+            .zip(actions.par_iter())
+            .map(|(d, a)| d.step(a))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Acrobot, Domain};
+
+    #[test]
+    fn test_matches_individual() {
+        let inits = vec![
+            Acrobot::new(0.0, 0.0, 0.0, 0.0),
+            Acrobot::new(0.5, -0.5, 0.0, 0.0),
+            Acrobot::new(-1.0, 0.2, 1.0, -1.0),
+        ];
+
+        let mut vd = VecDomain::new(inits.clone());
+        let mut singles = inits;
+
+        for i in 0..20 {
+            let actions: Vec<usize> = (0..3).map(|j| (i + j) % 3).collect();
+            let results = vd.step_all(&actions);
+
+            for ((d, a), (obs, r)) in singles.iter_mut().zip(actions.iter()).zip(results) {
+                let (obs_single, r_single) = d.step(a);
+
+                assert_eq!(obs.state(), obs_single.state());
+                assert_eq!(obs.is_terminal(), obs_single.is_terminal());
+                assert_eq!(r, r_single);
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_action_count_mismatch() {
+        VecDomain::replicate(&Acrobot::default(), 2).step_all(&[0]);
+    }
+}